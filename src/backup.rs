@@ -0,0 +1,129 @@
+use crate::config::{Config, Storage};
+use crate::db::Database;
+use chrono::NaiveDate;
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Backups go through SQLite's backup API, so the other backends are left to
+/// their own tools.
+pub fn sqlite_only(config: &Config) -> Result<(), Box<dyn Error>> {
+    match config.storage {
+        Storage::Sqlite => Ok(()),
+        _ => Err("backup and restore only work with the sqlite storage backend".into()),
+    }
+}
+
+pub fn backup(config: &Config, path: &Path) -> Result<(), Box<dyn Error>> {
+    sqlite_only(config)?;
+    let db_path = config.database_path()?;
+    Database::new(&db_path)?.backup(path)?;
+    println!("Backed up {} to {}", db_path.display(), path.display());
+    Ok(())
+}
+
+pub fn restore(config: &Config, path: &Path) -> Result<(), Box<dyn Error>> {
+    sqlite_only(config)?;
+    if !path.exists() {
+        return Err(format!("{} does not exist", path.display()).into());
+    }
+    let db_path = config.database_path()?;
+    Database::new(&db_path)?.restore(path)?;
+    println!("Restored {} from {}", db_path.display(), path.display());
+    Ok(())
+}
+
+/// `reminder db check`: stops at the integrity check if it finds problems,
+/// since compacting a damaged file could lose more.
+pub fn check(config: &Config) -> Result<(), Box<dyn Error>> {
+    sqlite_only(config)?;
+    let db_path = config.database_path()?;
+    let db = Database::new(&db_path)?;
+
+    let problems = db.integrity_check()?;
+    if !problems.is_empty() {
+        for problem in &problems {
+            println!("  {}", problem);
+        }
+        return Err(format!(
+            "{} failed its integrity check with {} problem(s); restore a backup with `reminder restore`",
+            db_path.display(),
+            problems.len()
+        )
+        .into());
+    }
+    println!("Integrity check: ok");
+
+    let size = || fs::metadata(&db_path).map(|m| m.len() / 1024).unwrap_or(0);
+    let before = size();
+    db.vacuum_and_analyze()?;
+    println!("Vacuumed: {} KiB -> {} KiB", before, size());
+    println!("Analyzed");
+    Ok(())
+}
+
+/// Makes today's backup if `[backup]` is configured and it doesn't exist
+/// yet, then deletes all but the newest `keep`. Returns the path of a backup
+/// it made.
+pub fn daily(config: &Config, today: NaiveDate) -> Result<Option<PathBuf>, Box<dyn Error>> {
+    let (Some(backup), Storage::Sqlite) = (&config.backup, &config.storage) else {
+        return Ok(None);
+    };
+    // Profiles sharing a directory each get their own series.
+    let prefix = config.profile.as_deref().unwrap_or("reminders");
+    let path = backup.dir.join(format!("{}-{}.db", prefix, today.format("%Y-%m-%d")));
+    if path.exists() {
+        return Ok(None);
+    }
+    fs::create_dir_all(&backup.dir)?;
+    Database::new(config.database_path()?)?.backup(&path)?;
+
+    let backups: Vec<PathBuf> = fs::read_dir(&backup.dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect();
+    for old in expired(backups, prefix, backup.keep) {
+        fs::remove_file(old)?;
+    }
+    Ok(Some(path))
+}
+
+/// The daily backups named `prefix-DATE.db` among `paths` beyond the newest
+/// `keep`. Their names sort by date; anything else in the directory is left
+/// alone.
+fn expired(paths: Vec<PathBuf>, prefix: &str, keep: usize) -> Vec<PathBuf> {
+    let mut backups: Vec<PathBuf> = paths
+        .into_iter()
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix(prefix)?.strip_prefix('-')?.strip_suffix(".db"))
+                .is_some_and(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok())
+        })
+        .collect();
+    backups.sort();
+    let excess = backups.len().saturating_sub(keep.max(1));
+    backups.truncate(excess);
+    backups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expires_the_oldest_daily_backups() {
+        let paths = [
+            "reminders-2026-10-14.db",
+            "notes.txt",
+            "reminders-2026-10-12.db",
+            "work-2026-10-11.db",
+            "reminders-2026-10-13.db",
+        ]
+            .iter()
+            .map(|name| Path::new("backups").join(name))
+            .collect();
+        assert_eq!(expired(paths, "reminders", 2), [Path::new("backups").join("reminders-2026-10-12.db")]);
+    }
+}
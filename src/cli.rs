@@ -0,0 +1,146 @@
+use clap::{Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+#[command(version, about = "A terminal-based reminder application")]
+pub struct Cli {
+    /// Run only the notification worker, without the TUI
+    #[arg(long)]
+    pub daemon: bool,
+
+    /// Use this SQLite database instead of the one in the data directory
+    #[arg(long, global = true, env = "REMINDER_DB", value_name = "PATH")]
+    pub db: Option<PathBuf>,
+
+    /// Use a named profile, with its own database and `[profiles.NAME]` settings
+    #[arg(long, global = true, env = "REMINDER_PROFILE", value_name = "NAME")]
+    pub profile: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Manage the background notification daemon
+    Daemon {
+        #[command(subcommand)]
+        action: DaemonAction,
+    },
+    /// Add the reminders in a JSON file, all at once
+    Import {
+        /// A list of reminders, or a file from the JSON storage backend
+        path: PathBuf,
+        /// What to do with reminders that have the same title and time as one already here
+        #[arg(long, value_enum, default_value_t = OnDuplicate::Ask)]
+        duplicates: OnDuplicate,
+    },
+    /// Add the reminders from another database file, skipping ones already here
+    Merge {
+        /// A SQLite database, e.g. one made by running the app in another directory
+        path: PathBuf,
+    },
+    /// Mirror reminders to a directory of files, e.g. in a git repo or Syncthing folder
+    Sync {
+        #[command(subcommand)]
+        action: SyncAction,
+    },
+    /// Look after the database file
+    Db {
+        #[command(subcommand)]
+        action: DbAction,
+    },
+    /// Suspend reminders between two dates; they resume by themselves afterwards
+    Vacation {
+        #[command(subcommand)]
+        action: VacationAction,
+    },
+    /// Print the open reminders, one line each or as JSON
+    List {
+        /// Print them as a JSON array with every field, and when each next goes off
+        #[arg(long, conflicts_with = "format")]
+        json: bool,
+        /// How to print each one, e.g. "{time} {title}"; the fields are id, title,
+        /// description, time, date, tags, priority and next
+        #[arg(long, value_name = "TEMPLATE")]
+        format: Option<String>,
+    },
+    /// Print the next reminder and how many are due today
+    Status {
+        /// Print a JSON object for a Waybar custom module with "return-type": "json"
+        #[arg(long)]
+        waybar: bool,
+    },
+    /// Print the next reminder
+    Next {
+        /// Print it compactly for a tmux status line, e.g. "⏰ Standup 09:30 (in 12m)"
+        #[arg(long)]
+        short: bool,
+        /// Print nothing unless it goes off within this many minutes
+        #[arg(long, value_name = "MINUTES")]
+        within: Option<i64>,
+    },
+    /// Copy the database to a file, safely while the app is running
+    Backup { path: PathBuf },
+    /// Replace the database with a backup; quit the TUI and daemon first
+    Restore { path: PathBuf },
+    /// Print a completion script for bash, zsh, fish, elvish or PowerShell
+    Completions { shell: clap_complete::Shell },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum OnDuplicate {
+    /// Ask about each one, or skip them all when not run from a terminal
+    Ask,
+    Skip,
+    /// Overwrite the one already here
+    Replace,
+    /// Add it anyway
+    Keep,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SyncAction {
+    /// Write one file per reminder to the directory, removing files of reminders that are gone
+    Export {
+        /// Defaults to `dir` under `[sync]` in the config
+        dir: Option<PathBuf>,
+    },
+    /// Make the reminders match the directory's files, adding, updating and removing as needed
+    Import {
+        /// Defaults to `dir` under `[sync]` in the config
+        dir: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum VacationAction {
+    /// Suspend reminders from one day to another, both included
+    Add {
+        /// First day off (YYYY-MM-DD)
+        from: String,
+        /// Last day off (YYYY-MM-DD)
+        to: String,
+        /// Only suspend reminders with this tag; repeat for several
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+    },
+    /// List the vacations that haven't ended yet
+    List,
+    /// Cancel a vacation
+    Remove { id: i32 },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DbAction {
+    /// Check the database for corruption, then compact it and refresh its statistics
+    Check,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DaemonAction {
+    /// Write a systemd user unit that runs `reminder --daemon`
+    Install,
+    /// Remove the systemd user unit
+    Uninstall,
+}
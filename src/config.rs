@@ -0,0 +1,654 @@
+use crate::validate_time_format;
+use chrono::{Duration, NaiveDateTime, NaiveTime, Timelike};
+use ratatui::style::Color;
+use reminder::schedule;
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub quiet_hours: Option<QuietHours>,
+    pub http: Option<HttpConfig>,
+    pub time_format: TimeFormat,
+    /// List overdue reminders before everything else in the TUI.
+    pub overdue_first: bool,
+    /// How the TUI's list is split up by the time of day.
+    pub sections: Sections,
+    /// What the TUI's list is sorted by; `None` keeps each tab's own order.
+    /// Saved by `o` and `O` with [`save_sort`].
+    pub sort: Option<SortOrder>,
+    pub sort_descending: bool,
+    /// Colors the TUI shows tags in, e.g. `work = "blue"`; see [`Config::tag_colors`].
+    pub tag_colors: BTreeMap<String, String>,
+    /// Keys the TUI's list takes besides the usual ones.
+    pub keymap: KeyPreset,
+    /// Move one-off reminders to the trash once they have gone off.
+    pub delete_after_firing: bool,
+    /// Start with desktop notifications muted; `m` in the TUI toggles it.
+    pub muted: bool,
+    /// An iCalendar or plain list of dates that reminders marked to skip
+    /// holidays stay quiet on.
+    pub holidays: Option<PathBuf>,
+    /// How long "Snooze all" puts off the reminders that are due.
+    pub snooze_minutes: Option<i64>,
+    /// What `z` in the TUI offers to snooze a reminder for; see [`snooze_until`].
+    pub snooze_presets: Vec<String>,
+    pub storage: Storage,
+    pub backup: Option<BackupConfig>,
+    pub lock: Option<LockConfig>,
+    pub sync: Option<SyncConfig>,
+    /// Set from `--db` or `REMINDER_DB`, never from the file.
+    #[serde(skip)]
+    pub db_path: Option<PathBuf>,
+    /// The profile these settings are for; `None` is the default one.
+    #[serde(skip)]
+    pub profile: Option<String>,
+    /// Every profile with a `[profiles.NAME]` section, for the TUI's switcher.
+    #[serde(skip)]
+    pub profiles: Vec<String>,
+}
+
+/// The directory `reminder sync` uses when none is given.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SyncConfig {
+    pub dir: PathBuf,
+}
+
+/// Blanks the TUI after a while without input until the PIN is typed.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LockConfig {
+    pub pin: String,
+    #[serde(default = "default_idle_minutes")]
+    pub idle_minutes: u64,
+}
+
+fn default_idle_minutes() -> u64 {
+    5
+}
+
+/// Where reminders are kept, chosen with `backend = "..."` under `[storage]`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(tag = "backend", rename_all = "lowercase")]
+pub enum Storage {
+    /// `reminders.db` in the data directory, or the file given with `--db`.
+    #[default]
+    Sqlite,
+    /// A readable JSON file, `reminders.json` in the working directory
+    /// unless `path` says otherwise.
+    Json {
+        #[serde(default = "default_json_path")]
+        path: PathBuf,
+    },
+    /// A shared PostgreSQL database, only usable when built with the
+    /// `postgres` feature.
+    #[cfg_attr(not(feature = "postgres"), allow(dead_code))]
+    Postgres { url: String },
+    /// `reminders.db` encrypted with SQLCipher, only usable when built with
+    /// the `sqlcipher` feature. The passphrase is asked for on startup, or
+    /// read from `REMINDER_PASSPHRASE`.
+    Sqlcipher,
+}
+
+/// How the TUI shows and reads times. SQLite always stores `HH:MM`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+pub enum TimeFormat {
+    #[default]
+    #[serde(rename = "24h")]
+    H24,
+    #[serde(rename = "12h")]
+    H12,
+}
+
+/// What the TUI's list can be sorted by, in the order `o` goes through them.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    /// When they next go off, date and all.
+    Time,
+    Title,
+    /// When they were added.
+    Created,
+    /// Most urgent first.
+    Priority,
+}
+
+impl SortOrder {
+    pub const ALL: [SortOrder; 4] = [SortOrder::Time, SortOrder::Title, SortOrder::Created, SortOrder::Priority];
+
+    /// How it is written in the config.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SortOrder::Time => "time",
+            SortOrder::Title => "title",
+            SortOrder::Created => "created",
+            SortOrder::Priority => "priority",
+        }
+    }
+}
+
+/// Headers the TUI's list is split into by the time reminders go off.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Sections {
+    /// One plain list.
+    Off,
+    /// Night, Morning, Afternoon and Evening.
+    #[default]
+    Parts,
+    /// A header for every hour.
+    Hours,
+}
+
+/// Sets of extra keys for the TUI's list, chosen with `keymap = "..."`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyPreset {
+    /// Just the usual keys.
+    #[default]
+    Default,
+    /// `j`/`k`, `gg`/`G` and `dd` as in vim, with `d` then waiting for a
+    /// second `d`.
+    Vim,
+}
+
+/// A daily window, e.g. `22:00`–`07:00`, during which notifications are held back.
+#[derive(Debug, Clone, Deserialize)]
+pub struct QuietHours {
+    pub start: String,
+    pub end: String,
+}
+
+/// Settings for the REST API, only used when built with the `http` feature.
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(not(feature = "http"), allow(dead_code))]
+pub struct HttpConfig {
+    #[serde(default = "default_bind")]
+    pub bind: String,
+    /// Clients must send `Authorization: Bearer <token>`; it can't be blank.
+    pub token: String,
+}
+
+/// Daily copies of the SQLite database, made by whichever worker is running.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BackupConfig {
+    #[serde(default = "default_backup_dir")]
+    pub dir: PathBuf,
+    /// How many daily copies to keep; older ones are deleted.
+    #[serde(default = "default_backup_keep")]
+    pub keep: usize,
+}
+
+fn default_backup_dir() -> PathBuf {
+    dirs::data_dir()
+        .map(|dir| dir.join("reminder").join("backups"))
+        .unwrap_or_else(|| PathBuf::from("backups"))
+}
+
+fn default_backup_keep() -> usize {
+    7
+}
+
+fn default_json_path() -> PathBuf {
+    PathBuf::from("reminders.json")
+}
+
+/// The longest snooze taken, by `snooze_minutes` or the control socket: a year.
+pub const MAX_SNOOZE_MINUTES: i64 = 366 * 24 * 60;
+
+/// Offered by `z` when the config doesn't list any.
+const DEFAULT_SNOOZE_PRESETS: [&str; 4] = ["5m", "15m", "1h", "tomorrow"];
+
+/// When a snooze picked at `now` ends: `preset` is a span such as `5m`, `2h`
+/// or `1d`, a time such as `18:00` (tomorrow if it has passed), or
+/// `tomorrow`, at the same time or at the one given after it. `None` if it
+/// can't be read, or ends too far off to count.
+pub fn snooze_until(preset: &str, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let preset = preset.trim();
+    if let Some(rest) = preset.strip_prefix("tomorrow") {
+        let tomorrow = now.checked_add_signed(Duration::days(1))?;
+        return match rest.trim() {
+            "" => Some(tomorrow),
+            time => Some(tomorrow.date().and_time(NaiveTime::parse_from_str(time, "%H:%M").ok()?)),
+        };
+    }
+    if let Ok(time) = NaiveTime::parse_from_str(preset, "%H:%M") {
+        let today = now.date().and_time(time);
+        return if today > now { Some(today) } else { today.checked_add_signed(Duration::days(1)) };
+    }
+
+    let split = preset.find(|c: char| !c.is_ascii_digit()).filter(|&i| i > 0)?;
+    let amount: i64 = preset[..split].parse().ok()?;
+    let span = match &preset[split..] {
+        "m" => Duration::try_minutes(amount)?,
+        "h" => Duration::try_hours(amount)?,
+        "d" => Duration::try_days(amount)?,
+        _ => return None,
+    };
+    now.checked_add_signed(span).filter(|_| amount > 0)
+}
+
+fn default_bind() -> String {
+    "127.0.0.1:8080".to_string()
+}
+
+impl Config {
+    /// [`Config::snooze_minutes`], 10 unless set.
+    pub fn snooze_all_minutes(&self) -> i64 {
+        self.snooze_minutes.unwrap_or(10).clamp(1, MAX_SNOOZE_MINUTES)
+    }
+
+    /// [`Config::snooze_presets`], or 5m, 15m, 1h and tomorrow unless set.
+    pub fn snooze_choices(&self) -> Vec<String> {
+        if self.snooze_presets.is_empty() {
+            DEFAULT_SNOOZE_PRESETS.iter().map(|preset| preset.to_string()).collect()
+        } else {
+            self.snooze_presets.clone()
+        }
+    }
+
+    /// [`Config::tag_colors`] read as colors: names like `blue` or
+    /// `lightred`, `#rrggbb`, or a 0–255 terminal color. Tags are matched
+    /// without their `#` and case.
+    pub fn tag_colors(&self) -> HashMap<String, Color> {
+        self.tag_colors
+            .iter()
+            .filter_map(|(tag, color)| Some((tag.trim_start_matches('#').to_lowercase(), color.parse().ok()?)))
+            .collect()
+    }
+
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("reminder").join("config.toml"))
+    }
+
+    /// Loads the config file for `profile`, falling back to defaults when it
+    /// doesn't exist. `default` is the same as no profile.
+    pub fn load(profile: Option<&str>) -> Result<Self, Box<dyn Error>> {
+        let profile = profile.filter(|name| *name != "default");
+        if let Some(name) = profile {
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+                return Err(format!("invalid profile name {:?}; use letters, digits, - and _", name).into());
+            }
+        }
+        let Some(path) = Self::path().filter(|p| p.exists()) else {
+            return Ok(Config { profile: profile.map(str::to_string), ..Config::default() });
+        };
+        Ok(Self::parse(&fs::read_to_string(&path)?, profile).map_err(|e| format!("{}: {}", path.display(), e))?)
+    }
+
+    /// Reads a config file's text. The keys in `profile`'s `[profiles.NAME]`
+    /// section replace the top-level ones.
+    fn parse(text: &str, profile: Option<&str>) -> Result<Self, String> {
+        let mut table: toml::Table = toml::from_str(text).map_err(|e| e.to_string())?;
+        let profiles = match table.remove("profiles") {
+            Some(toml::Value::Table(profiles)) => profiles,
+            Some(_) => return Err("profiles must be a table of [profiles.NAME] sections".to_string()),
+            None => toml::Table::new(),
+        };
+        if let Some(section) = profile.and_then(|name| profiles.get(name)) {
+            let toml::Value::Table(section) = section else {
+                return Err(format!("profiles.{} must be a table", profile.unwrap_or_default()));
+            };
+            table.extend(section.clone());
+        }
+
+        let mut config: Config = table.try_into().map_err(|e: toml::de::Error| e.to_string())?;
+        if let Some(quiet) = &config.quiet_hours {
+            if quiet.window().is_none() {
+                return Err("quiet_hours must use HH:MM times".to_string());
+            }
+        }
+        if config.http.as_ref().is_some_and(|http| http.token.trim().is_empty()) {
+            return Err("http.token must not be empty".to_string());
+        }
+        if config.snooze_minutes.is_some_and(|minutes| minutes > MAX_SNOOZE_MINUTES) {
+            return Err(format!("snooze_minutes can be at most {} (a year)", MAX_SNOOZE_MINUTES));
+        }
+        let any_time = NaiveDateTime::default();
+        if let Some(preset) = config.snooze_presets.iter().find(|p| snooze_until(p, any_time).is_none()) {
+            return Err(format!("snooze preset {:?} should look like 15m, 2h, 18:00 or tomorrow 09:00", preset));
+        }
+        if let Some((tag, color)) = config.tag_colors.iter().find(|(_, color)| color.parse::<Color>().is_err()) {
+            return Err(format!("tag_colors.{}: {:?} should be a color name like blue, #rrggbb or 0-255", tag, color));
+        }
+        config.profile = profile.map(str::to_string);
+        config.profiles = profiles.keys().cloned().collect();
+        Ok(config)
+    }
+
+    /// The SQLite database to use: the one given with `--db` or
+    /// `REMINDER_DB`, otherwise the profile's [`database_path`].
+    pub fn database_path(&self) -> io::Result<PathBuf> {
+        match &self.db_path {
+            Some(path) => Ok(path.clone()),
+            None => database_path(self.profile.as_deref()),
+        }
+    }
+}
+
+/// Writes the list's sort order to the config file, so the TUI starts with
+/// it next time: at the top, or in `profile`'s `[profiles.NAME]` section,
+/// which is added if need be. Only those two lines change; comments and
+/// everything else stay as they were. A profile with no sort of its own
+/// goes back to the top-level one.
+pub fn save_sort(profile: Option<&str>, sort: Option<SortOrder>, descending: bool) -> io::Result<()> {
+    let Some(path) = Config::path() else {
+        return Err(io::Error::new(io::ErrorKind::NotFound, "no config directory"));
+    };
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e),
+    };
+    let sort = sort.map(|sort| format!("{:?}", sort.as_str()));
+    let descending = descending.then(|| "true".to_string());
+    let table = profile.map(|name| format!("profiles.{}", name));
+    let text = set_keys(&text, table.as_deref(), &[("sort", sort), ("sort_descending", descending)]);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, text)
+}
+
+/// Replaces or removes the `key = value` lines for `keys` in a TOML file,
+/// either at the top level or in `[table]`, putting the new ones first,
+/// where they can't end up inside another table or a multi-line array. A
+/// missing table is added at the end.
+fn set_keys(text: &str, table: Option<&str>, keys: &[(&str, Option<String>)]) -> String {
+    let header = table.map(|name| format!("[{}]", name));
+    let mut lines: Vec<String> = Vec::new();
+    // Where the new lines go, once the place for them has been seen.
+    let mut at = header.is_none().then_some(0);
+    let mut in_table = header.is_none();
+    for line in text.lines() {
+        if line.trim_start().starts_with('[') {
+            let name = line.split('#').next().unwrap_or_default().replace(char::is_whitespace, "");
+            in_table = header.as_ref() == Some(&name);
+            if in_table {
+                at = Some(lines.len() + 1);
+            }
+        }
+        let key = line.split('=').next().unwrap_or_default().trim();
+        if in_table && line.contains('=') && keys.iter().any(|(name, _)| *name == key) {
+            continue;
+        }
+        lines.push(line.to_string());
+    }
+    let added: Vec<String> =
+        keys.iter().filter_map(|(name, value)| Some(format!("{} = {}", name, value.as_ref()?))).collect();
+    match (at, header) {
+        (Some(at), _) => {
+            lines.splice(at..at, added);
+        }
+        (None, Some(header)) if !added.is_empty() => {
+            if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+                lines.push(String::new());
+            }
+            lines.push(header);
+            lines.extend(added);
+        }
+        (None, _) => {}
+    }
+    let mut text = lines.join("\n");
+    text.push('\n');
+    text
+}
+
+/// Where older versions kept the database: the directory they were run from.
+const LEGACY_DB_PATH: &str = "reminders.db";
+
+/// Where the SQLite database lives, `$XDG_DATA_HOME/reminder/reminders.db`
+/// or the platform equivalent, creating the directory. A `reminders.db` left
+/// in the working directory by an older version is moved there first.
+///
+/// Each other profile gets its own file, `profiles/NAME.db` beside it.
+pub fn database_path(profile: Option<&str>) -> io::Result<PathBuf> {
+    let Some(data_dir) = dirs::data_dir() else {
+        return Ok(PathBuf::from(match profile {
+            Some(name) => format!("reminders-{}.db", name),
+            None => LEGACY_DB_PATH.to_string(),
+        }));
+    };
+    let dir = data_dir.join("reminder");
+    if let Some(name) = profile {
+        let dir = dir.join("profiles");
+        fs::create_dir_all(&dir)?;
+        return Ok(dir.join(format!("{}.db", name)));
+    }
+    fs::create_dir_all(&dir)?;
+    let path = dir.join("reminders.db");
+    if move_legacy_database(Path::new(LEGACY_DB_PATH), &path)? {
+        eprintln!("Moved {} to {}", LEGACY_DB_PATH, path.display());
+    }
+    Ok(path)
+}
+
+/// Moves the database at `legacy`, with its WAL files, to `path` unless
+/// there is already one there. Returns whether it moved anything.
+fn move_legacy_database(legacy: &Path, path: &Path) -> io::Result<bool> {
+    if path.exists() || !legacy.exists() {
+        return Ok(false);
+    }
+    for suffix in ["-wal", "-shm", ""] {
+        let mut from = legacy.as_os_str().to_owned();
+        from.push(suffix);
+        let mut to = path.as_os_str().to_owned();
+        to.push(suffix);
+        if Path::new(&from).exists() && fs::rename(&from, &to).is_err() {
+            // Renaming fails across filesystems.
+            fs::copy(&from, &to)?;
+            fs::remove_file(&from)?;
+        }
+    }
+    Ok(true)
+}
+
+impl QuietHours {
+    fn window(&self) -> Option<(NaiveTime, NaiveTime)> {
+        let start = NaiveTime::parse_from_str(&self.start, "%H:%M").ok()?;
+        let end = NaiveTime::parse_from_str(&self.end, "%H:%M").ok()?;
+        Some((start, end))
+    }
+
+    /// The first time after `now` that they end.
+    pub fn next_end(&self, now: NaiveDateTime) -> Option<NaiveDateTime> {
+        let today = now.date().and_time(self.window()?.1);
+        Some(if today > now { today } else { today + Duration::days(1) })
+    }
+
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        match self.window() {
+            Some((start, end)) if start <= end => start <= time && time < end,
+            Some((start, end)) => time >= start || time < end,
+            None => false,
+        }
+    }
+}
+
+impl TimeFormat {
+    /// Shows the seconds only if there are any.
+    pub fn format(&self, time: NaiveTime) -> String {
+        let seconds = time.second() != 0;
+        match (self, seconds) {
+            (TimeFormat::H24, false) => time.format("%H:%M").to_string(),
+            (TimeFormat::H24, true) => time.format("%H:%M:%S").to_string(),
+            (TimeFormat::H12, false) => time.format("%-I:%M %p").to_string(),
+            (TimeFormat::H12, true) => time.format("%-I:%M:%S %p").to_string(),
+        }
+    }
+
+    /// Always shows the seconds, for the clock in the status bar.
+    pub fn format_exact(&self, time: NaiveTime) -> String {
+        match self {
+            TimeFormat::H24 => time.format("%H:%M:%S").to_string(),
+            TimeFormat::H12 => time.format("%-I:%M:%S %p").to_string(),
+        }
+    }
+
+    /// Renders a stored `HH:MM` or `HH:MM:SS` time, leaving anything else as it is.
+    pub fn render(&self, time: &str) -> String {
+        match schedule::parse_time(time) {
+            Some(parsed) => self.format(parsed),
+            None => time.to_string(),
+        }
+    }
+
+    /// Reads a time typed in this format, e.g. `18:59`, `6:59 PM` or
+    /// `18:59:30`, as stored `HH:MM` or `HH:MM:SS`.
+    pub fn parse(&self, input: &str) -> Option<String> {
+        let input = input.trim();
+        match self {
+            TimeFormat::H24 => validate_time_format(input).then(|| schedule::parse_time(input)).flatten().map(schedule::time_text),
+            TimeFormat::H12 => {
+                let upper = input.to_uppercase();
+                let (clock, meridiem) = upper
+                    .strip_suffix("AM")
+                    .map(|clock| (clock, "AM"))
+                    .or_else(|| upper.strip_suffix("PM").map(|clock| (clock, "PM")))?;
+                let (hour, rest) = clock.trim().split_once(':')?;
+                let format = match rest.len() {
+                    2 => "%I:%M %p",
+                    5 if rest.as_bytes()[2] == b':' => "%I:%M:%S %p",
+                    _ => return None,
+                };
+                NaiveTime::parse_from_str(&format!("{}:{} {}", hour, rest, meridiem), format)
+                    .ok()
+                    .map(schedule::time_text)
+            }
+        }
+    }
+
+    /// Describes the expected input, for form hints and errors.
+    pub fn example(&self) -> &'static str {
+        match self {
+            TimeFormat::H24 => "HH:MM[:SS] (e.g., 06:59)",
+            TimeFormat::H12 => "h:MM[:SS] AM/PM (e.g., 6:59 PM)",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_sections_replace_top_level_keys() {
+        let text = r#"
+            time_format = "12h"
+            delete_after_firing = true
+
+            [profiles.work]
+            delete_after_firing = false
+            storage = { backend = "json", path = "work.json" }
+
+            [profiles.home]
+        "#;
+        let work = Config::parse(text, Some("work")).unwrap();
+        assert_eq!(work.time_format, TimeFormat::H12);
+        assert!(!work.delete_after_firing);
+        assert!(matches!(work.storage, Storage::Json { ref path } if path == Path::new("work.json")));
+        assert_eq!(work.profiles, ["home", "work"]);
+
+        let default = Config::parse(text, None).unwrap();
+        assert!(default.delete_after_firing);
+        assert!(matches!(default.storage, Storage::Sqlite));
+    }
+
+    #[test]
+    fn saving_the_sort_keeps_the_rest_of_the_file() {
+        let text = "# Times like 6:59 PM\ntime_format = \"12h\"\nsort = \"title\"\n\n[lock]\npin = \"1234\"\nsort = \"kept\"\n";
+        let saved = set_keys(text, None, &[("sort", Some("\"priority\"".to_string())), ("sort_descending", Some("true".to_string()))]);
+        assert_eq!(
+            saved,
+            "sort = \"priority\"\nsort_descending = true\n# Times like 6:59 PM\ntime_format = \"12h\"\n\n[lock]\npin = \"1234\"\nsort = \"kept\"\n"
+        );
+        let config = Config::parse(&saved, None).unwrap();
+        assert_eq!(config.sort, Some(SortOrder::Priority));
+        assert!(config.sort_descending);
+
+        let cleared = set_keys(&saved, None, &[("sort", None), ("sort_descending", None)]);
+        assert_eq!(Config::parse(&cleared, None).unwrap().sort, None);
+        assert_eq!(set_keys("", None, &[("sort", Some("\"time\"".to_string()))]), "sort = \"time\"\n");
+    }
+
+    #[test]
+    fn a_profile_saves_its_sort_in_its_own_section() {
+        let text = "sort = \"title\"\n\n[profiles.work]\nsort = \"time\"\nmuted = true\n\n[profiles.home]\nmuted = false\n";
+        let priority = [("sort", Some("\"priority\"".to_string())), ("sort_descending", None)];
+        let saved = set_keys(text, Some("profiles.work"), &priority);
+        assert_eq!(
+            saved,
+            "sort = \"title\"\n\n[profiles.work]\nsort = \"priority\"\nmuted = true\n\n[profiles.home]\nmuted = false\n"
+        );
+        assert_eq!(Config::parse(&saved, Some("work")).unwrap().sort, Some(SortOrder::Priority));
+        assert_eq!(Config::parse(&saved, None).unwrap().sort, Some(SortOrder::Title));
+
+        let added = set_keys(&saved, Some("profiles.play"), &priority);
+        assert!(added.ends_with("muted = false\n\n[profiles.play]\nsort = \"priority\"\n"));
+        assert_eq!(Config::parse(&added, Some("play")).unwrap().sort, Some(SortOrder::Priority));
+        assert_eq!(set_keys(text, Some("profiles.play"), &[("sort", None)]), text);
+    }
+
+    #[test]
+    fn the_http_token_must_not_be_blank() {
+        assert!(Config::parse("[http]\ntoken = \"s3cret\"\n", None).is_ok());
+        for token in ["\"\"", "\"  \""] {
+            assert!(Config::parse(&format!("[http]\ntoken = {}\n", token), None).unwrap_err().contains("http.token"));
+        }
+    }
+
+    #[test]
+    fn tag_colors_are_checked_when_loading() {
+        let config = Config::parse("[tag_colors]\nwork = \"blue\"\n\"#Home\" = \"#ff8800\"\nmeds = \"202\"\n", None).unwrap();
+        let colors = config.tag_colors();
+        assert_eq!(colors["work"], Color::Blue);
+        assert_eq!(colors["home"], Color::Rgb(0xff, 0x88, 0x00));
+        assert_eq!(colors["meds"], Color::Indexed(202));
+        assert!(Config::parse("[tag_colors]\nwork = \"bluish\"\n", None).is_err());
+    }
+
+    #[test]
+    fn snooze_presets_end_at_the_right_time() {
+        let now = NaiveDateTime::parse_from_str("2026-10-15 14:30", "%Y-%m-%d %H:%M").unwrap();
+        let until = |preset| snooze_until(preset, now).map(|t| t.format("%Y-%m-%d %H:%M").to_string());
+        assert_eq!(until("15m").as_deref(), Some("2026-10-15 14:45"));
+        assert_eq!(until("2h").as_deref(), Some("2026-10-15 16:30"));
+        assert_eq!(until("18:00").as_deref(), Some("2026-10-15 18:00"));
+        assert_eq!(until("09:00").as_deref(), Some("2026-10-16 09:00"));
+        assert_eq!(until("tomorrow").as_deref(), Some("2026-10-16 14:30"));
+        assert_eq!(until("tomorrow 08:15").as_deref(), Some("2026-10-16 08:15"));
+        assert_eq!(until("0m"), None);
+        assert_eq!(until("soon"), None);
+        assert_eq!(until("99999999999d"), None);
+        assert_eq!(until("99999999999999999999m"), None);
+
+        assert!(Config::parse("snooze_presets = [\"10m\", \"later\"]", None).is_err());
+        assert!(Config::parse("snooze_presets = [\"99999999999d\"]", None).is_err());
+        assert!(Config::parse("snooze_minutes = 99999999999999", None).is_err());
+        assert_eq!(Config::parse("snooze_minutes = -5", None).unwrap().snooze_all_minutes(), 1);
+    }
+
+    #[test]
+    fn moves_a_legacy_database_once() {
+        let dir = std::env::temp_dir().join(format!("reminder-legacy-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (legacy, path) = (dir.join("old.db"), dir.join("new.db"));
+        fs::write(&legacy, "db").unwrap();
+        fs::write(dir.join("old.db-wal"), "wal").unwrap();
+
+        assert!(move_legacy_database(&legacy, &path).unwrap());
+        assert!(!legacy.exists());
+        assert_eq!(fs::read_to_string(dir.join("new.db-wal")).unwrap(), "wal");
+
+        fs::write(&legacy, "stale").unwrap();
+        assert!(!move_legacy_database(&legacy, &path).unwrap());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "db");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,144 @@
+use crate::config::Config;
+use crate::events;
+use crate::ipc;
+use crate::worker::{notification_worker, WorkerEvent};
+use reminder::SharedStore;
+use std::{
+    env,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+use tokio::{
+    signal::unix::{signal, SignalKind},
+    sync::mpsc,
+};
+
+/// `reminder.service`, or `reminder-NAME.service` for a profile, so each
+/// profile can have a daemon of its own.
+fn unit_name(profile: Option<&str>) -> String {
+    match profile {
+        Some(name) => format!("reminder-{}.service", name),
+        None => "reminder.service".to_string(),
+    }
+}
+
+fn unit_path(profile: Option<&str>) -> Result<PathBuf, Box<dyn Error>> {
+    let config_dir = dirs::config_dir().ok_or("could not determine the config directory")?;
+    Ok(config_dir.join("systemd").join("user").join(unit_name(profile)))
+}
+
+/// Runs the notification worker in the foreground. SIGTERM and SIGINT stop it
+/// cleanly; SIGHUP reloads the config file.
+pub fn run(mut config: Config) -> Result<(), Box<dyn Error>> {
+    let db_path = config.db_path.clone();
+    let config_profile = config.profile.clone();
+    let reload = Arc::new(AtomicBool::new(false));
+
+    // Opened before entering the runtime: the PostgreSQL client runs one of its own.
+    let db = Arc::new(SharedStore::new(crate::open_store(&config)?));
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(async {
+        let events = events::new_bus();
+        let owns_socket = ipc::spawn_server(Arc::clone(&db), events.clone())?;
+        crate::start_http(&config, Arc::clone(&db), events.clone());
+
+        let (tx, mut inbox) = mpsc::unbounded_channel();
+        events::forward(&events, tx.clone(), WorkerEvent::Reminder);
+        let (mut term, mut int, mut hup) =
+            (signal(SignalKind::terminate())?, signal(SignalKind::interrupt())?, signal(SignalKind::hangup())?);
+        let reload_on_hup = Arc::clone(&reload);
+        tokio::spawn(async move {
+            loop {
+                let hangup = tokio::select! {
+                    _ = term.recv() => false,
+                    _ = int.recv() => false,
+                    _ = hup.recv() => true,
+                };
+                reload_on_hup.store(hangup, Ordering::Relaxed);
+                if tx.send(WorkerEvent::Stop).is_err() {
+                    return;
+                }
+            }
+        });
+
+        loop {
+            notification_worker(config, Arc::clone(&db), &mut inbox, events.clone()).await;
+
+            if !reload.swap(false, Ordering::Relaxed) {
+                if owns_socket {
+                    let _ = fs::remove_file(ipc::socket_path());
+                }
+                return Ok(());
+            }
+            config = Config::load(config_profile.as_deref())?;
+            config.db_path = db_path.clone();
+        }
+    })
+}
+
+/// Writes the unit, passing on `profile` and `db` so the daemon watches the
+/// same database.
+pub fn install(profile: Option<&str>, db: Option<&Path>) -> Result<(), Box<dyn Error>> {
+    let profile = profile.filter(|name| *name != "default");
+    let exe = env::current_exe()?;
+    let mut args = String::new();
+    if let Some(name) = profile {
+        args.push_str(&format!(" --profile {}", name));
+    }
+    if let Some(db) = db {
+        args.push_str(&format!(" --db \"{}\"", std::path::absolute(db)?.display()));
+    }
+    // Relative paths in the config, such as a JSON store's, resolve against
+    // the directory the TUI is being used from.
+    let working_dir = env::current_dir()?;
+    let unit = format!(
+        "[Unit]
+Description=Reminder notification daemon
+After=graphical-session.target
+PartOf=graphical-session.target
+
+[Service]
+ExecStart=\"{}\" --daemon{}
+ExecReload=/bin/kill -HUP $MAINPID
+WorkingDirectory={}
+Restart=on-failure
+
+[Install]
+WantedBy=graphical-session.target
+",
+        exe.display(),
+        args,
+        working_dir.display()
+    );
+
+    let path = unit_path(profile)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, unit)?;
+
+    println!("Wrote {}", path.display());
+    println!("Enable it with:");
+    println!("  systemctl --user daemon-reload");
+    println!("  systemctl --user enable --now {}", unit_name(profile));
+    Ok(())
+}
+
+pub fn uninstall(profile: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let profile = profile.filter(|name| *name != "default");
+    let path = unit_path(profile)?;
+    if path.exists() {
+        fs::remove_file(&path)?;
+        println!("Removed {}", path.display());
+        println!("Stop the running service with:");
+        println!("  systemctl --user disable --now {}", unit_name(profile));
+    } else {
+        println!("No unit installed at {}", path.display());
+    }
+    Ok(())
+}
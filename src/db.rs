@@ -1,94 +1,1767 @@
-use rusqlite::{Connection, Result, params};
-use chrono::Local;
+use rusqlite::{
+    params, params_from_iter, Connection, DatabaseName, OpenFlags, OptionalExtension, Result, Row, Transaction, TransactionBehavior,
+};
+use crate::schedule;
+use crate::store::{self, ReminderStore};
+use chrono::{Local, NaiveDate, NaiveDateTime, SecondsFormat, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A stored reminder.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Reminder {
     pub id: i32,
+    /// Stays the same when the reminder is exported, imported, merged or
+    /// synced, unlike `id`, which only means something in one database.
+    #[serde(default)]
+    pub uuid: String,
     pub title: String,
     pub description: String,
+    /// `HH:MM` it goes off at; empty for cron reminders.
     pub time: String,
+    /// RFC 3339 timestamp of when it was added.
     pub created_at: String,
+    /// UTC RFC 3339 timestamp of when it was last changed; the later copy
+    /// wins when syncing or merging. Empty for a reminder never stamped.
+    #[serde(default)]
+    pub updated_at: String,
+    /// Minutes before `time` at which to notify; `0` means at the time itself.
+    pub alerts: Vec<i64>,
+    /// `YYYY-MM-DD` for a one-off reminder; `None` repeats every day.
+    pub date: Option<String>,
+    /// Lowercase tags without the `#`.
+    pub tags: Vec<String>,
+    /// A five-field cron expression that replaces `time` and `date` when set.
+    pub cron: Option<String>,
+    /// Repeat every this many minutes from `time` until `until`.
+    pub interval_minutes: Option<i64>,
+    /// `HH:MM` end of the window for interval reminders.
+    pub until: Option<String>,
+    /// Days of the week a repeating reminder goes off on, as read by
+    /// [`schedule::parse_weekdays`] (e.g. `weekdays` or `mon,wed,fri`);
+    /// `None` is every day.
+    pub weekdays: Option<String>,
+    /// Local `YYYY-MM-DD HH:MM` an interval reminder last went off, kept by the worker.
+    pub last_fired_at: Option<String>,
+    /// IANA zone such as `Europe/Berlin` that `time`, `date` and `cron` are
+    /// read in; `None` means the machine's local time.
+    pub timezone: Option<String>,
+    /// `YYYY-MM-DD HH:MM` it was marked done; done reminders no longer fire.
+    pub completed_at: Option<String>,
+    /// `YYYY-MM-DD HH:MM` it was moved to the trash.
+    pub deleted_at: Option<String>,
+    /// Set by the worker once a one-off reminder has gone off for good.
+    pub archived: bool,
+    /// Move it to the trash once it has gone off.
+    pub delete_after_firing: bool,
+    /// Stay quiet on the holidays listed in the file named by the config.
+    #[serde(default)]
+    pub skip_holidays: bool,
+    /// Days a repeating reminder is skipped or goes off differently on, by date.
+    #[serde(default)]
+    pub exceptions: Vec<Exception>,
+    /// Icon name (e.g. `dialog-warning`) or image path its notifications show.
+    #[serde(default)]
+    pub icon: Option<String>,
+    /// Notification category, e.g. `im` or `x-medication`, which some
+    /// notification servers style or file by.
+    #[serde(default)]
+    pub category: Option<String>,
+    /// Keep its notifications on screen until dismissed.
+    #[serde(default)]
+    pub resident: bool,
+    /// How urgent it is, which the list shows by color.
+    #[serde(default)]
+    pub priority: Priority,
 }
 
+/// How urgent a reminder is, from least to most.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+impl Priority {
+    /// How it is stored.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Priority::Low => "low",
+            Priority::Normal => "normal",
+            Priority::High => "high",
+        }
+    }
+
+    /// Reads what [`as_str`](Self::as_str) stored; anything else counts as normal.
+    pub fn from_stored(text: &str) -> Self {
+        match text {
+            "low" => Priority::Low,
+            "high" => Priority::High,
+            _ => Priority::Normal,
+        }
+    }
+
+    /// One step more urgent, staying at the top.
+    pub fn raised(self) -> Self {
+        match self {
+            Priority::Low => Priority::Normal,
+            _ => Priority::High,
+        }
+    }
+
+    /// One step less urgent, staying at the bottom.
+    pub fn lowered(self) -> Self {
+        match self {
+            Priority::High => Priority::Normal,
+            _ => Priority::Low,
+        }
+    }
+}
+
+/// The user-supplied fields of a reminder that hasn't been stored yet.
+///
+/// Deserializes from the JSON of a [`Reminder`], ignoring the stored fields,
+/// so exported reminders can be imported again.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct NewReminder {
+    /// Keeps the identity of a reminder exported from elsewhere; a new one is
+    /// made when `None`.
+    pub uuid: Option<String>,
+    pub title: String,
+    pub description: String,
+    pub time: String,
+    pub date: Option<String>,
+    pub alerts: Vec<i64>,
+    pub tags: Vec<String>,
+    pub cron: Option<String>,
+    pub interval_minutes: Option<i64>,
+    pub until: Option<String>,
+    pub weekdays: Option<String>,
+    pub timezone: Option<String>,
+    pub delete_after_firing: bool,
+    pub skip_holidays: bool,
+    pub icon: Option<String>,
+    pub category: Option<String>,
+    pub resident: bool,
+    pub priority: Priority,
+}
+
+impl NewReminder {
+    /// Whether `reminder` is probably this one added before: it has the same
+    /// UUID, or the same title, ignoring case, and goes off at the same time.
+    pub fn duplicates(&self, reminder: &Reminder) -> bool {
+        self.uuid.as_deref() == Some(reminder.uuid.as_str())
+            || (reminder.title.trim().to_lowercase() == self.title.trim().to_lowercase()
+                && reminder.time == self.time
+                && reminder.cron == self.cron)
+    }
+
+    /// Takes `reminder`'s notification settings and priority, for a form
+    /// that has no fields for them: editing `reminder`, or copying it.
+    pub fn with_settings_of(self, reminder: &Reminder) -> Self {
+        NewReminder {
+            icon: reminder.icon.clone(),
+            category: reminder.category.clone(),
+            resident: reminder.resident,
+            priority: reminder.priority,
+            ..self
+        }
+    }
+}
+
+/// A notification the worker showed or tried to, kept so it can be checked
+/// later whether something actually went off.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoggedNotification {
+    /// The reminder it was for; `None` for a summary of several.
+    pub reminder_id: Option<i32>,
+    /// Local `YYYY-MM-DD HH:MM:SS` it was shown or held back.
+    pub at: String,
+    /// The notification's title.
+    pub summary: String,
+    pub delivery: Delivery,
+    /// Why it couldn't be shown, if it failed.
+    pub error: Option<String>,
+}
+
+/// What became of a notification.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Delivery {
+    Delivered,
+    Failed,
+    /// Kept for the summary at the end of quiet hours.
+    HeldBack,
+    /// Not shown because notifications were muted.
+    Muted,
+}
+
+impl Delivery {
+    /// How it is stored.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Delivery::Delivered => "delivered",
+            Delivery::Failed => "failed",
+            Delivery::HeldBack => "held_back",
+            Delivery::Muted => "muted",
+        }
+    }
+
+    /// Reads what [`as_str`](Self::as_str) stored; anything else counts as failed.
+    pub fn from_stored(text: &str) -> Self {
+        match text {
+            "delivered" => Delivery::Delivered,
+            "held_back" => Delivery::HeldBack,
+            "muted" => Delivery::Muted,
+            _ => Delivery::Failed,
+        }
+    }
+}
+
+/// Days off, when reminders are suspended; they resume by themselves once
+/// it ends.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Vacation {
+    pub id: i32,
+    /// First day off, `YYYY-MM-DD`.
+    pub start: String,
+    /// Last day off, `YYYY-MM-DD`, included.
+    pub end: String,
+    /// Only reminders with one of these tags are suspended; empty means all.
+    pub tags: Vec<String>,
+}
+
+impl Vacation {
+    /// Whether `reminder` is suspended on `day`.
+    pub fn covers(&self, reminder: &Reminder, day: NaiveDate) -> bool {
+        let day = day.format("%Y-%m-%d").to_string();
+        self.start <= day
+            && day <= self.end
+            && (self.tags.is_empty() || reminder.tags.iter().any(|tag| self.tags.contains(tag)))
+    }
+}
+
+/// One day of a repeating reminder that doesn't follow its schedule, e.g. a
+/// weekly meeting cancelled or moved this week. It replaces the schedule for
+/// that day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Exception {
+    /// `YYYY-MM-DD` in the reminder's timezone.
+    pub date: String,
+    /// `HH:MM` it goes off at that day instead; `None` skips the day.
+    pub time: Option<String>,
+    /// Added to that day's notification, above the description.
+    pub note: Option<String>,
+}
+
+/// Now, in the form of [`Reminder::updated_at`]; these sort by time as text.
+pub(crate) fn timestamp() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// A random UUID for a new reminder.
+pub(crate) fn new_uuid() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+impl Reminder {
+    /// Records that it was changed just now.
+    pub fn touch(&mut self) {
+        self.updated_at = timestamp();
+    }
+
+    /// Adds `exception`, replacing any other for the same day.
+    pub fn set_exception(&mut self, exception: Exception) {
+        self.remove_exception(&exception.date);
+        self.exceptions.push(exception);
+        self.exceptions.sort_by(|a, b| a.date.cmp(&b.date));
+    }
+
+    /// Puts `date` back on the usual schedule.
+    pub fn remove_exception(&mut self, date: &str) {
+        self.exceptions.retain(|e| e.date != date);
+    }
+
+    /// Replaces everything the user typed with `new`'s, keeping the id,
+    /// timestamps and state.
+    pub fn apply(&mut self, new: NewReminder) {
+        self.title = new.title;
+        self.description = new.description;
+        self.time = new.time;
+        self.date = new.date;
+        self.alerts = new.alerts;
+        self.tags = new.tags;
+        self.cron = new.cron;
+        self.interval_minutes = new.interval_minutes;
+        self.until = new.until;
+        self.weekdays = new.weekdays;
+        self.timezone = new.timezone;
+        self.delete_after_firing = new.delete_after_firing;
+        self.skip_holidays = new.skip_holidays;
+        self.icon = new.icon;
+        self.category = new.category;
+        self.resident = new.resident;
+        self.priority = new.priority;
+    }
+
+    /// Takes the user's fields that differ between `before` and `after`,
+    /// leaving the rest as they are here, so an edit of an older copy only
+    /// writes what was actually changed.
+    pub fn apply_changes(&mut self, before: &Reminder, after: &Reminder) {
+        macro_rules! changed {
+            ($($field:ident),*) => {
+                $(if before.$field != after.$field {
+                    self.$field = after.$field.clone();
+                })*
+            };
+        }
+        changed!(
+            title, description, time, date, alerts, tags, cron, interval_minutes, until, weekdays, timezone,
+            delete_after_firing, skip_holidays, icon, category, resident, priority
+        );
+    }
+}
+
+/// How many reminders' alerts, tags and exceptions are read per query,
+/// well under SQLite's limit on parameters.
+const DETAILS_BATCH: usize = 500;
+
+const REMINDER_COLUMNS: &str =
+    "id, title, description, time, created_at, date, cron, interval_minutes, until, last_fired_at, timezone, completed_at, deleted_at, archived, delete_after_firing, uuid, updated_at, weekdays, skip_holidays, icon, category, resident, priority";
+
+/// Schema changes in the order they were made; a database whose
+/// `user_version` is N has had the first N applied. Only ever append a step,
+/// never edit or reorder a released one.
+const MIGRATIONS: &[fn(&Connection) -> Result<()>] = &[create_tables, add_search_index, add_time_indexes, add_uuids, add_updated_at, add_worker_state, add_notification_log, add_vacations, add_weekdays, add_skip_holidays, add_exceptions, add_exception_changes, add_notification_style, add_priority, add_notification_log_index, claim_by_occurrence];
+
+/// Reminders stored in an SQLite file.
+///
+/// Several processes may open the same file; notifications are claimed in the
+/// database so each alert is delivered once however many workers run.
 pub struct Database {
     conn: Connection,
 }
 
 impl Database {
-    pub fn new(path: &str) -> Result<Self> {
+    /// Opens (or creates) the database at `path`, bringing an older schema up
+    /// to date. `":memory:"` gives a private in-memory database.
+    pub fn new(path: impl AsRef<Path>) -> store::Result<Self> {
+        Self::setup(Connection::open(path)?)
+    }
+
+    /// Opens (or creates) a database encrypted with SQLCipher. Fails with
+    /// [`ReminderError::WrongPassphrase`](crate::ReminderError::WrongPassphrase)
+    /// if `passphrase` is wrong.
+    #[cfg(feature = "sqlcipher")]
+    pub fn new_encrypted(path: impl AsRef<Path>, passphrase: &str) -> store::Result<Self> {
         let conn = Connection::open(path)?;
+        conn.pragma_update(None, "key", passphrase)?;
+        // The key is only checked once something is read.
+        match conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |row| row.get::<_, i64>(0)) {
+            Err(e) if e.sqlite_error_code() == Some(rusqlite::ErrorCode::NotADatabase) => {
+                return Err(store::Error::WrongPassphrase)
+            }
+            result => result?,
+        };
+        Self::setup(conn)
+    }
+
+    fn setup(conn: Connection) -> store::Result<Self> {
+        // The TUI and a `--daemon` process may hold the file open at the same time.
+        conn.busy_timeout(Duration::from_secs(5))?;
+        // Lets one process read while another writes.
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(()))?;
         let db = Database { conn };
-        db.init_schema()?;
+        db.migrate()?;
         Ok(db)
     }
 
-    fn init_schema(&self) -> Result<()> {
-        self.conn.execute(
-            "CREATE TABLE IF NOT EXISTS reminders (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                title TEXT NOT NULL,
-                description TEXT NOT NULL,
-                time TEXT NOT NULL,
-                created_at TEXT NOT NULL
-            )",
+    /// Copies the database to `path` while it stays in use, replacing any
+    /// file already there.
+    pub fn backup(&self, path: &Path) -> store::Result<()> {
+        Ok(self.conn.backup(DatabaseName::Main, path, None)?)
+    }
+
+    /// Replaces everything in the database with the backup at `path`, then
+    /// brings its schema up to date.
+    pub fn restore(&mut self, path: &Path) -> store::Result<()> {
+        let source = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        let tables: i64 = source.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'reminders'",
             [],
+            |row| row.get(0),
         )?;
+        if tables == 0 {
+            return Err(store::Error::NotReminderDatabase(path.to_path_buf()));
+        }
+        drop(source);
+        self.conn.restore(DatabaseName::Main, path, None::<fn(rusqlite::backup::Progress)>)?;
+        self.migrate()
+    }
+
+    /// Runs `PRAGMA integrity_check`, returning the problems it finds; an
+    /// empty list means the database is sound.
+    pub fn integrity_check(&self) -> store::Result<Vec<String>> {
+        let mut stmt = self.conn.prepare("PRAGMA integrity_check")?;
+        let results = stmt.query_map([], |row| row.get::<_, String>(0))?.collect::<Result<Vec<_>>>()?;
+        Ok(results.into_iter().filter(|result| result != "ok").collect())
+    }
+
+    /// Rebuilds the file without free pages and refreshes the statistics the
+    /// query planner uses.
+    pub fn vacuum_and_analyze(&self) -> store::Result<()> {
+        Ok(self.conn.execute_batch("VACUUM; ANALYZE;")?)
+    }
+
+    /// Applies the steps in [`MIGRATIONS`] the database hasn't had yet,
+    /// recording progress in `PRAGMA user_version`. Runs in one immediate
+    /// transaction so two processes starting together don't both migrate.
+    fn migrate(&self) -> store::Result<()> {
+        let tx = Transaction::new_unchecked(&self.conn, TransactionBehavior::Immediate)?;
+        let version: usize = tx.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version > MIGRATIONS.len() {
+            return Err(store::Error::SchemaTooNew { found: version, supported: MIGRATIONS.len() });
+        }
+        for step in &MIGRATIONS[version..] {
+            step(&tx)?;
+        }
+        tx.pragma_update(None, "user_version", MIGRATIONS.len())?;
+        Ok(tx.commit()?)
+    }
+
+    /// Runs `SELECT <columns> FROM reminders <clause>` and loads each row's details.
+    fn query_reminders(&self, clause: &str, params: impl rusqlite::Params) -> Result<Vec<Reminder>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM reminders {}",
+            REMINDER_COLUMNS, clause
+        ))?;
+
+        let mut reminders = stmt.query_map(params, Self::reminder_from_row)?.collect::<Result<Vec<_>>>()?;
+        self.load_details(&mut reminders)?;
+        Ok(reminders)
+    }
+}
+
+impl ReminderStore for Database {
+    fn reminders(&self) -> store::Result<Vec<Reminder>> {
+        Ok(self.query_reminders("ORDER BY id", [])?)
+    }
+
+    fn unsnooze_reminder(&self, id: i32) -> store::Result<()> {
+        self.conn.execute("DELETE FROM snoozes WHERE reminder_id = ?", params![id])?;
         Ok(())
     }
 
-    pub fn add_reminder(&self, title: String, description: String, time: String) -> Result<Reminder> {
+    /// Stores a new reminder and returns it with its id.
+    fn add_reminder(&self, new: NewReminder) -> store::Result<Reminder> {
         let now = Local::now().to_rfc3339();
+        let uuid = new.uuid.unwrap_or_else(new_uuid);
+        let updated_at = timestamp();
         self.conn.execute(
-            "INSERT INTO reminders (title, description, time, created_at) VALUES (?, ?, ?, ?)",
-            params![&title, &description, &time, &now],
+            "INSERT INTO reminders (uuid, updated_at, title, description, time, created_at, date, cron, interval_minutes,
+             until, timezone, delete_after_firing, weekdays, skip_holidays, icon, category, resident, priority)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                &uuid,
+                &updated_at,
+                &new.title,
+                &new.description,
+                &new.time,
+                &now,
+                &new.date,
+                &new.cron,
+                new.interval_minutes,
+                &new.until,
+                &new.timezone,
+                new.delete_after_firing,
+                &new.weekdays,
+                new.skip_holidays,
+                &new.icon,
+                &new.category,
+                new.resident,
+                new.priority.as_str()
+            ],
         )?;
         
         let id = self.conn.last_insert_rowid() as i32;
+        self.set_alerts(id, &new.alerts)?;
+        self.set_tags(id, &new.tags)?;
         Ok(Reminder {
             id,
-            title,
-            description,
-            time,
+            uuid,
+            title: new.title,
+            description: new.description,
+            time: new.time,
             created_at: now,
+            updated_at,
+            alerts: new.alerts,
+            date: new.date,
+            tags: new.tags,
+            cron: new.cron,
+            interval_minutes: new.interval_minutes,
+            until: new.until,
+            weekdays: new.weekdays,
+            last_fired_at: None,
+            timezone: new.timezone,
+            completed_at: None,
+            deleted_at: None,
+            archived: false,
+            delete_after_firing: new.delete_after_firing,
+            skip_holidays: new.skip_holidays,
+            exceptions: Vec::new(),
+            icon: new.icon,
+            category: new.category,
+            resident: new.resident,
+            priority: new.priority,
         })
     }
 
-    pub fn get_all_reminders(&self) -> Result<Vec<Reminder>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, title, description, time, created_at FROM reminders ORDER BY time ASC"
+    /// Adds them all in one transaction: one sync to disk instead of one per
+    /// reminder, and nothing is added if any of them fails.
+    fn add_reminders_batch(&self, new: Vec<NewReminder>) -> store::Result<Vec<Reminder>> {
+        let tx = self.conn.unchecked_transaction()?;
+        let added = new.into_iter().map(|new| self.add_reminder(new)).collect::<store::Result<_>>()?;
+        tx.commit()?;
+        Ok(added)
+    }
+
+    /// Every reminder that isn't archived or in the trash: repeating ones by
+    /// time, then dated ones by date and time.
+    fn get_all_reminders(&self) -> store::Result<Vec<Reminder>> {
+        Ok(self.query_reminders("WHERE deleted_at IS NULL AND archived = 0 ORDER BY date IS NOT NULL, date, time, id", [])?)
+    }
+
+    /// One page of `get_all_reminders`, in the same order.
+    fn get_reminders_page(&self, offset: usize, limit: usize) -> store::Result<Vec<Reminder>> {
+        Ok(self.query_reminders("WHERE deleted_at IS NULL AND archived = 0 ORDER BY date IS NOT NULL, date, time, id LIMIT ? OFFSET ?", params![limit as i64, offset as i64])?)
+    }
+
+    /// How many reminders `get_all_reminders` returns.
+    fn count_reminders(&self) -> store::Result<usize> {
+        Ok(self.conn.query_row("SELECT COUNT(*) FROM reminders WHERE deleted_at IS NULL AND archived = 0", [], |row| row.get(0))?)
+    }
+
+    /// Open reminders that go off on `today`.
+    fn get_today_reminders(&self, today: NaiveDate) -> store::Result<Vec<Reminder>> {
+        // A day either side, since a zoned reminder's date may not be the local one.
+        let mut reminders = self.query_reminders(
+            "WHERE completed_at IS NULL AND deleted_at IS NULL AND archived = 0 AND (date IS NULL OR date BETWEEN ? AND ?) ORDER BY time ASC",
+            [
+                (today - chrono::Duration::days(1)).format("%Y-%m-%d").to_string(),
+                (today + chrono::Duration::days(1)).format("%Y-%m-%d").to_string(),
+            ],
         )?;
-        
-        let reminders = stmt.query_map([], |row| {
-            Ok(Reminder {
-                id: row.get(0)?,
-                title: row.get(1)?,
-                description: row.get(2)?,
-                time: row.get(3)?,
-                created_at: row.get(4)?,
-            })
-        })?;
+        reminders.retain(|r| schedule::fires_on(r, today));
+        Ok(reminders)
+    }
+
+    /// Open reminders that will still go off at or after `now`, soonest first.
+    fn get_upcoming_reminders(&self, now: NaiveDateTime) -> store::Result<Vec<Reminder>> {
+        let mut reminders = self.query_reminders(
+            "WHERE completed_at IS NULL AND deleted_at IS NULL AND archived = 0 AND (date IS NULL OR date >= ?)",
+            [(now - chrono::Duration::days(1)).format("%Y-%m-%d").to_string()],
+        )?;
+        let mut upcoming: Vec<_> = reminders
+            .drain(..)
+            .filter_map(|r| Some((schedule::next_fire(&r, now)?, r)))
+            .collect();
+        upcoming.sort_by_key(|(at, _)| *at);
+        Ok(upcoming.into_iter().map(|(_, r)| r).collect())
+    }
+
+    /// Lets the time index narrow things down to reminders with an alert in
+    /// this minute, plus the cron, interval and timezone ones whose times
+    /// can't be worked out in SQL and those with an occurrence moved to
+    /// another time; `schedule` has the final say on each.
+    fn get_due_reminders(&self, now: NaiveDateTime) -> store::Result<Vec<Reminder>> {
+        let mut due = self.query_reminders(
+            "WHERE deleted_at IS NULL AND archived = 0 AND completed_at IS NULL AND (
+                cron IS NOT NULL OR interval_minutes IS NOT NULL OR timezone IS NOT NULL
+                OR EXISTS (SELECT 1 FROM exceptions WHERE exceptions.reminder_id = reminders.id
+                           AND exceptions.time IS NOT NULL AND exceptions.date >= ?2)
+                OR ((date IS NULL OR date >= ?2) AND (
+                    substr(time, 1, 5) IN (SELECT strftime('%H:%M', ?1, offset_minutes || ' minutes') FROM alerts
+                                           WHERE alerts.reminder_id = reminders.id)
+                    OR (substr(time, 1, 5) = ?1 AND NOT EXISTS (SELECT 1 FROM alerts WHERE alerts.reminder_id = reminders.id))
+                ))
+            )",
+            params![now.format("%H:%M").to_string(), now.format("%Y-%m-%d").to_string()],
+        )?;
+        due.retain(|r| store::is_due(r, now));
+        Ok(due)
+    }
 
-        let mut result = Vec::new();
+    /// Only reads the reminders dated within a day of the window, besides
+    /// the undated ones.
+    fn get_reminders_due_by(&self, now: NaiveDateTime, until: NaiveDateTime) -> store::Result<Vec<Reminder>> {
+        let reminders = self.query_reminders(
+            "WHERE completed_at IS NULL AND deleted_at IS NULL AND archived = 0 AND (date IS NULL OR date BETWEEN ? AND ?)",
+            [
+                (now - chrono::Duration::days(1)).format("%Y-%m-%d").to_string(),
+                (until + chrono::Duration::days(1)).format("%Y-%m-%d").to_string(),
+            ],
+        )?;
+        let mut due: Vec<_> = reminders
+            .into_iter()
+            .filter_map(|r| Some((schedule::next_fire(&r, now).filter(|at| *at <= until)?, r)))
+            .collect();
+        due.sort_by_key(|(at, _)| *at);
+        Ok(due.into_iter().map(|(_, r)| r).collect())
+    }
+
+    /// Leaves out the reminders dated after today, and asks the log about
+    /// each of the rest that was due rather than reading it all.
+    fn get_overdue_reminders(&self, now: NaiveDateTime) -> store::Result<Vec<Reminder>> {
+        let reminders = self.query_reminders(
+            "WHERE completed_at IS NULL AND deleted_at IS NULL AND archived = 0 AND (date IS NULL OR date <= ?)",
+            [(now + chrono::Duration::days(1)).format("%Y-%m-%d").to_string()],
+        )?;
+        let mut delivered = self.conn.prepare(
+            "SELECT EXISTS (SELECT 1 FROM notification_log WHERE reminder_id = ? AND delivery = ? AND at >= ?)",
+        )?;
+        let mut overdue = Vec::new();
         for reminder in reminders {
-            result.push(reminder?);
+            let Some(since) = store::chased_since(&reminder, now) else { continue };
+            if !delivered.query_row(params![reminder.id, Delivery::Delivered.as_str(), since], |row| row.get(0))? {
+                overdue.push(reminder);
+            }
+        }
+        Ok(overdue)
+    }
+
+    /// One page of reminders marked done, most recently finished first.
+    fn get_done_reminders_page(&self, offset: usize, limit: usize) -> store::Result<Vec<Reminder>> {
+        Ok(self.query_reminders(
+            "WHERE completed_at IS NOT NULL AND deleted_at IS NULL ORDER BY completed_at DESC, id DESC LIMIT ? OFFSET ?",
+            params![limit as i64, offset as i64],
+        )?)
+    }
+
+    /// How many reminders are marked done.
+    fn count_done_reminders(&self) -> store::Result<usize> {
+        Ok(self.conn.query_row(
+            "SELECT COUNT(*) FROM reminders WHERE completed_at IS NOT NULL AND deleted_at IS NULL",
+            [],
+            |row| row.get(0),
+        )?)
+    }
+
+    /// Marks a reminder done at `at` (`YYYY-MM-DD HH:MM`), or open again for `None`.
+    fn set_completed(&self, id: i32, at: Option<&str>) -> store::Result<()> {
+        self.conn.execute(
+            "UPDATE reminders SET completed_at = ?, updated_at = ? WHERE id = ?",
+            params![at, timestamp(), id],
+        )?;
+        Ok(())
+    }
+
+    /// Archived one-off reminders, most recent first.
+    fn get_archived_reminders(&self) -> store::Result<Vec<Reminder>> {
+        Ok(self.query_reminders(
+            "WHERE archived = 1 AND deleted_at IS NULL ORDER BY date DESC, time DESC",
+            [],
+        )?)
+    }
+
+    /// Archives one-off reminders that won't go off again after `now`.
+    /// Returns how many were archived.
+    fn archive_past(&self, now: NaiveDateTime) -> store::Result<usize> {
+        // A day of slack for reminders in other timezones.
+        let candidates = self.query_reminders(
+            "WHERE archived = 0 AND deleted_at IS NULL AND cron IS NULL AND date <= ?",
+            [(now + chrono::Duration::days(1)).format("%Y-%m-%d").to_string()],
+        )?;
+        let mut archived = 0;
+        for reminder in candidates.iter().filter(|r| schedule::next_fire(r, now).is_none()) {
+            archived += self.conn.execute(
+                "UPDATE reminders SET archived = 1 WHERE id = ?",
+                params![reminder.id],
+            )?;
+        }
+        Ok(archived)
+    }
+
+    /// Reminders in the trash, most recently deleted first.
+    fn get_trash_reminders(&self) -> store::Result<Vec<Reminder>> {
+        Ok(self.query_reminders("WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC", [])?)
+    }
+
+    /// Takes a reminder back out of the trash.
+    fn restore_from_trash(&self, id: i32) -> store::Result<()> {
+        self.conn.execute(
+            "UPDATE reminders SET deleted_at = NULL, updated_at = ? WHERE id = ?",
+            params![timestamp(), id],
+        )?;
+        Ok(())
+    }
+
+    /// Permanently deletes reminders that have been in the trash since before
+    /// `before` (`YYYY-MM-DD HH:MM`).
+    fn purge_trash(&self, before: &str) -> store::Result<usize> {
+        let mut stmt = self.conn.prepare("SELECT id FROM reminders WHERE deleted_at < ?")?;
+        let ids = stmt
+            .query_map(params![before], |row| row.get(0))?
+            .collect::<Result<Vec<i32>>>()?;
+        self.purge_reminders(&ids)?;
+        Ok(ids.len())
+    }
+
+    /// Marks several reminders done (or open again) in one transaction.
+    fn set_completed_many(&self, ids: &[i32], at: Option<&str>) -> store::Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for &id in ids {
+            self.set_completed(id, at)?;
+        }
+        Ok(tx.commit()?)
+    }
+
+    /// Gives several reminders the same priority in one transaction.
+    fn set_priority(&self, ids: &[i32], priority: Priority) -> store::Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        let updated_at = timestamp();
+        for &id in ids {
+            self.conn.execute(
+                "UPDATE reminders SET priority = ?, updated_at = ? WHERE id = ?",
+                params![priority.as_str(), updated_at, id],
+            )?;
         }
-        Ok(result)
+        Ok(tx.commit()?)
     }
 
-    pub fn update_reminder(&self, id: i32, title: String, description: String, time: String) -> Result<()> {
+    /// Sets how a reminder's notifications look, leaving everything else,
+    /// such as whether it is archived, as it is.
+    fn set_notification_settings(&self, id: i32, icon: Option<&str>, category: Option<&str>, resident: bool) -> store::Result<()> {
         self.conn.execute(
-            "UPDATE reminders SET title = ?, description = ?, time = ? WHERE id = ?",
-            params![&title, &description, &time, id],
+            "UPDATE reminders SET icon = ?, category = ?, resident = ?, updated_at = ? WHERE id = ?",
+            params![icon, category, resident, timestamp(), id],
         )?;
         Ok(())
     }
 
-    pub fn delete_reminder(&self, id: i32) -> Result<()> {
+    /// Matches words by prefix through the full-text index, so `dent` finds
+    /// "Dentist" without scanning every row.
+    fn search_reminders(&self, query: &str) -> store::Result<Vec<Reminder>> {
+        let words: Vec<String> = query
+            .split_whitespace()
+            .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+            .collect();
+        if words.is_empty() {
+            return Ok(self.query_reminders("WHERE deleted_at IS NULL ORDER BY time, id", [])?);
+        }
+        Ok(self.query_reminders(
+            "WHERE deleted_at IS NULL AND id IN (SELECT rowid FROM reminders_fts WHERE reminders_fts MATCH ?)
+             ORDER BY time, id",
+            params![words.join(" ")],
+        )?)
+    }
+
+    /// Titles used before that start with `prefix`, most recently used first.
+    fn title_suggestions(&self, prefix: &str, limit: usize) -> store::Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT title FROM reminders
+             WHERE substr(lower(title), 1, length(?1)) = lower(?1) AND title != ?1
+             GROUP BY title ORDER BY MAX(created_at) DESC LIMIT ?2",
+        )?;
+        let titles = stmt.query_map(params![prefix, limit as i64], |row| row.get(0))?;
+        Ok(titles.collect::<Result<_>>()?)
+    }
+
+    /// Tags used before that start with `prefix`, most used first.
+    fn tag_suggestions(&self, prefix: &str, limit: usize) -> store::Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT tag FROM tags WHERE substr(tag, 1, length(?1)) = lower(?1)
+             GROUP BY tag ORDER BY COUNT(*) DESC, tag LIMIT ?2",
+        )?;
+        let tags = stmt.query_map(params![prefix, limit as i64], |row| row.get(0))?;
+        Ok(tags.collect::<Result<_>>()?)
+    }
+
+    fn get_tagged_reminders(&self, tag: &str) -> store::Result<Vec<Reminder>> {
+        Ok(self.query_reminders(
+            "WHERE deleted_at IS NULL AND archived = 0 AND id IN (SELECT reminder_id FROM tags WHERE tag = ?)
+             ORDER BY date IS NOT NULL, date, time, id",
+            params![tag],
+        )?)
+    }
+
+    fn tag_counts(&self) -> store::Result<Vec<(String, usize)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT tag, COUNT(*) FROM tags JOIN reminders ON reminders.id = tags.reminder_id
+             WHERE deleted_at IS NULL AND archived = 0 GROUP BY tag ORDER BY tag",
+        )?;
+        let counts = stmt.query_map([], |row| Ok((row.get(0)?, row.get::<_, i64>(1)? as usize)))?;
+        Ok(counts.collect::<Result<_>>()?)
+    }
+
+    /// Writes the editable fields of `reminder` back to the row with its id,
+    /// taking it out of the archive so the worker looks at its schedule again.
+    fn update_reminder(&self, reminder: &Reminder) -> store::Result<()> {
+        self.conn.execute(
+            "UPDATE reminders SET title = ?, description = ?, time = ?, date = ?, cron = ?, interval_minutes = ?, until = ?,
+             timezone = ?, delete_after_firing = ?, weekdays = ?, skip_holidays = ?, icon = ?, category = ?, resident = ?,
+             priority = ?, archived = 0, updated_at = ? WHERE id = ?",
+            params![
+                &reminder.title,
+                &reminder.description,
+                &reminder.time,
+                &reminder.date,
+                &reminder.cron,
+                reminder.interval_minutes,
+                &reminder.until,
+                &reminder.timezone,
+                reminder.delete_after_firing,
+                &reminder.weekdays,
+                reminder.skip_holidays,
+                &reminder.icon,
+                &reminder.category,
+                reminder.resident,
+                reminder.priority.as_str(),
+                timestamp(),
+                reminder.id
+            ],
+        )?;
+        self.set_alerts(reminder.id, &reminder.alerts)?;
+        self.set_tags(reminder.id, &reminder.tags)?;
+        Ok(())
+    }
+
+    /// Writes `reminder` back exactly as it was, id and all, recreating it if
+    /// it was deleted. Used to undo changes.
+    fn restore_reminder(&self, reminder: &Reminder) -> store::Result<()> {
+        self.conn.execute(
+            // An upsert rather than INSERT OR REPLACE, whose implicit delete
+            // wouldn't fire the trigger that keeps the search index in sync.
+            "INSERT INTO reminders (id, title, description, time, created_at, date, cron, interval_minutes,
+             until, last_fired_at, timezone, completed_at, deleted_at, archived, delete_after_firing, uuid, updated_at, weekdays,
+             skip_holidays, icon, category, resident, priority)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23)
+             ON CONFLICT (id) DO UPDATE SET title = ?2, description = ?3, time = ?4, created_at = ?5, date = ?6,
+             cron = ?7, interval_minutes = ?8, until = ?9, last_fired_at = ?10, timezone = ?11, completed_at = ?12,
+             deleted_at = ?13, archived = ?14, delete_after_firing = ?15, uuid = ?16, updated_at = ?17,
+             weekdays = ?18, skip_holidays = ?19, icon = ?20, category = ?21, resident = ?22, priority = ?23",
+            params![
+                reminder.id,
+                &reminder.title,
+                &reminder.description,
+                &reminder.time,
+                &reminder.created_at,
+                &reminder.date,
+                &reminder.cron,
+                reminder.interval_minutes,
+                &reminder.until,
+                &reminder.last_fired_at,
+                &reminder.timezone,
+                &reminder.completed_at,
+                &reminder.deleted_at,
+                reminder.archived,
+                reminder.delete_after_firing,
+                &reminder.uuid,
+                &reminder.updated_at,
+                &reminder.weekdays,
+                reminder.skip_holidays,
+                &reminder.icon,
+                &reminder.category,
+                reminder.resident,
+                reminder.priority.as_str()
+            ],
+        )?;
+        self.set_alerts(reminder.id, &reminder.alerts)?;
+        self.set_tags(reminder.id, &reminder.tags)?;
+        self.set_exceptions(reminder.id, &reminder.exceptions)?;
+        Ok(())
+    }
+
+    /// Moves several reminders to the trash in one transaction, so either all go or none do.
+    fn delete_reminders(&self, ids: &[i32]) -> store::Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for &id in ids {
+            self.delete_reminder(id)?;
+        }
+        Ok(tx.commit()?)
+    }
+
+    /// Moves a reminder to the trash. It stops firing and can be restored
+    /// until it is purged.
+    fn delete_reminder(&self, id: i32) -> store::Result<()> {
+        let now = Local::now().format("%Y-%m-%d %H:%M").to_string();
+        self.conn.execute(
+            "UPDATE reminders SET deleted_at = ?, updated_at = ? WHERE id = ?",
+            params![now, timestamp(), id],
+        )?;
+        self.unsnooze_reminder(id)
+    }
+
+    /// Permanently deletes several reminders in one transaction.
+    fn purge_reminders(&self, ids: &[i32]) -> store::Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for &id in ids {
+            self.purge_reminder(id)?;
+        }
+        Ok(tx.commit()?)
+    }
+
+    /// Permanently deletes a reminder and everything stored with it.
+    fn purge_reminder(&self, id: i32) -> store::Result<()> {
         self.conn.execute(
             "DELETE FROM reminders WHERE id = ?",
             params![id],
         )?;
+        self.conn.execute(
+            "DELETE FROM alerts WHERE reminder_id = ?",
+            params![id],
+        )?;
+        self.conn.execute(
+            "DELETE FROM notified WHERE reminder_id = ?",
+            params![id],
+        )?;
+        self.conn.execute(
+            "DELETE FROM snoozes WHERE reminder_id = ?",
+            params![id],
+        )?;
+        self.conn.execute(
+            "DELETE FROM tags WHERE reminder_id = ?",
+            params![id],
+        )?;
+        self.conn.execute(
+            "DELETE FROM exceptions WHERE reminder_id = ?",
+            params![id],
+        )?;
+        Ok(())
+    }
+
+    /// The reminder with `id`, unless it doesn't exist or is in the trash.
+    fn get_reminder(&self, id: i32) -> store::Result<Option<Reminder>> {
+        let reminder = self
+            .conn
+            .query_row(
+                &format!("SELECT {} FROM reminders WHERE id = ? AND deleted_at IS NULL", REMINDER_COLUMNS),
+                params![id],
+                Self::reminder_from_row,
+            )
+            .optional()?;
+
+        let mut reminders: Vec<Reminder> = reminder.into_iter().collect();
+        self.load_details(&mut reminders)?;
+        Ok(reminders.pop())
+    }
+
+    /// Schedules one extra notification at `until` (`YYYY-MM-DD HH:MM`),
+    /// replacing any earlier snooze of the same reminder.
+    fn snooze_reminder(&self, id: i32, until: &str) -> store::Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO snoozes (reminder_id, until) VALUES (?, ?)",
+            params![id, until],
+        )?;
+        Ok(())
+    }
+
+    /// Removes and returns the snoozes that are due at `now`. A snooze is only
+    /// returned to the process that actually deleted it.
+    fn claim_due_snoozes(&self, now: &str) -> store::Result<Vec<i32>> {
+        let mut stmt = self.conn.prepare("SELECT reminder_id FROM snoozes WHERE until <= ?")?;
+        let ids = stmt.query_map(params![now], |row| row.get(0))?;
+
+        let mut claimed = Vec::new();
+        for id in ids {
+            let id: i32 = id?;
+            let deleted = self.conn.execute(
+                "DELETE FROM snoozes WHERE reminder_id = ? AND until <= ?",
+                params![id, now],
+            )?;
+            if deleted == 1 {
+                claimed.push(id);
+            }
+        }
+        Ok(claimed)
+    }
+
+    fn next_snooze(&self) -> store::Result<Option<String>> {
+        Ok(self.conn.query_row("SELECT MIN(until) FROM snoozes", [], |row| row.get(0))?)
+    }
+
+    /// Records that an alert is being delivered for the occurrence at
+    /// `occurrence` (`YYYY-MM-DD HH:MM`). Returns `false` if another process
+    /// (or an earlier tick) already claimed it, so every alert is shown at
+    /// most once per occurrence no matter how many notifiers run.
+    fn claim_notification(&self, reminder_id: i32, offset_minutes: i64, occurrence: &str) -> store::Result<bool> {
+        let inserted = self.conn.execute(
+            "INSERT OR IGNORE INTO notified (reminder_id, offset_minutes, occurrence) VALUES (?, ?, ?)",
+            params![reminder_id, offset_minutes, occurrence],
+        )?;
+        Ok(inserted == 1)
+    }
+
+    /// Moves an interval reminder's `last_fired_at` from `previous` to `at`.
+    /// Returns `false` if it no longer equals `previous`, i.e. another process
+    /// fired it first.
+    fn claim_interval_fire(&self, id: i32, previous: Option<&str>, at: Option<&str>) -> store::Result<bool> {
+        let updated = self.conn.execute(
+            "UPDATE reminders SET last_fired_at = ? WHERE id = ? AND last_fired_at IS ?",
+            params![at, id, previous],
+        )?;
+        Ok(updated == 1)
+    }
+
+    /// Undoes a claim whose notification failed to show, so it is retried.
+    fn release_notification(&self, reminder_id: i32, offset_minutes: i64, occurrence: &str) -> store::Result<()> {
+        self.conn.execute(
+            "DELETE FROM notified WHERE reminder_id = ? AND offset_minutes = ? AND occurrence = ?",
+            params![reminder_id, offset_minutes, occurrence],
+        )?;
+        Ok(())
+    }
+
+    fn last_check(&self) -> store::Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row("SELECT value FROM worker_state WHERE name = 'last_check'", [], |row| row.get(0))
+            .optional()?)
+    }
+
+    fn log_notification(&self, notification: &LoggedNotification) -> store::Result<()> {
+        self.conn.execute(
+            "INSERT INTO notification_log (reminder_id, at, summary, delivery, error) VALUES (?, ?, ?, ?, ?)",
+            params![
+                notification.reminder_id,
+                notification.at,
+                notification.summary,
+                notification.delivery.as_str(),
+                notification.error
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn notification_log(&self, limit: usize) -> store::Result<Vec<LoggedNotification>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT reminder_id, at, summary, delivery, error FROM notification_log ORDER BY id DESC LIMIT ?",
+        )?;
+        let log = stmt
+            .query_map(params![limit as i64], |row| {
+                Ok(LoggedNotification {
+                    reminder_id: row.get(0)?,
+                    at: row.get(1)?,
+                    summary: row.get(2)?,
+                    delivery: Delivery::from_stored(&row.get::<_, String>(3)?),
+                    error: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+        Ok(log)
+    }
+
+    /// Keeps the latest minute when several processes record at once.
+    fn record_check(&self, at: &str) -> store::Result<()> {
+        self.conn.execute(
+            "INSERT INTO worker_state (name, value) VALUES ('last_check', ?1)
+             ON CONFLICT (name) DO UPDATE SET value = MAX(value, ?1)",
+            params![at],
+        )?;
+        Ok(())
+    }
+
+    fn add_vacation(&self, start: &str, end: &str, tags: &[String]) -> store::Result<Vacation> {
+        self.conn.execute(
+            "INSERT INTO vacations (start_date, end_date, tags) VALUES (?, ?, ?)",
+            params![start, end, tags.join(" ")],
+        )?;
+        Ok(Vacation {
+            id: self.conn.last_insert_rowid() as i32,
+            start: start.to_string(),
+            end: end.to_string(),
+            tags: tags.to_vec(),
+        })
+    }
+
+    fn vacations(&self) -> store::Result<Vec<Vacation>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, start_date, end_date, tags FROM vacations ORDER BY start_date, id")?;
+        let vacations = stmt
+            .query_map([], |row| {
+                Ok(Vacation {
+                    id: row.get(0)?,
+                    start: row.get(1)?,
+                    end: row.get(2)?,
+                    tags: row.get::<_, String>(3)?.split_whitespace().map(str::to_string).collect(),
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+        Ok(vacations)
+    }
+
+    fn delete_vacation(&self, id: i32) -> store::Result<()> {
+        self.conn.execute("DELETE FROM vacations WHERE id = ?", params![id])?;
+        Ok(())
+    }
+}
+
+impl Database {
+    fn reminder_from_row(row: &Row) -> Result<Reminder> {
+        Ok(Reminder {
+            id: row.get(0)?,
+            uuid: row.get(15)?,
+            updated_at: row.get(16)?,
+            title: row.get(1)?,
+            description: row.get(2)?,
+            time: row.get(3)?,
+            created_at: row.get(4)?,
+            alerts: Vec::new(),
+            date: row.get(5)?,
+            tags: Vec::new(),
+            cron: row.get(6)?,
+            interval_minutes: row.get(7)?,
+            until: row.get(8)?,
+            weekdays: row.get(17)?,
+            last_fired_at: row.get(9)?,
+            timezone: row.get(10)?,
+            completed_at: row.get(11)?,
+            deleted_at: row.get(12)?,
+            archived: row.get(13)?,
+            delete_after_firing: row.get(14)?,
+            skip_holidays: row.get(18)?,
+            exceptions: Vec::new(),
+            icon: row.get(19)?,
+            category: row.get(20)?,
+            resident: row.get(21)?,
+            priority: Priority::from_stored(&row.get::<_, String>(22)?),
+        })
+    }
+
+    /// Fills in the fields that live in side tables, reading each table once
+    /// per [`DETAILS_BATCH`] reminders rather than once per reminder.
+    /// Reminders created before alerts existed have no rows; they fire at the
+    /// time itself.
+    fn load_details(&self, reminders: &mut [Reminder]) -> Result<()> {
+        for batch in reminders.chunks_mut(DETAILS_BATCH) {
+            let ids: Vec<i32> = batch.iter().map(|r| r.id).collect();
+            let placeholders = vec!["?"; ids.len()].join(", ");
+            let mut alerts: HashMap<i32, Vec<i64>> = HashMap::new();
+            let mut tags: HashMap<i32, Vec<String>> = HashMap::new();
+            let mut exceptions: HashMap<i32, Vec<Exception>> = HashMap::new();
+
+            let mut stmt = self.conn.prepare(&format!(
+                "SELECT reminder_id, offset_minutes FROM alerts WHERE reminder_id IN ({}) ORDER BY offset_minutes DESC",
+                placeholders
+            ))?;
+            let mut rows = stmt.query(params_from_iter(&ids))?;
+            while let Some(row) = rows.next()? {
+                alerts.entry(row.get(0)?).or_default().push(row.get(1)?);
+            }
+            let mut stmt = self
+                .conn
+                .prepare(&format!("SELECT reminder_id, tag FROM tags WHERE reminder_id IN ({}) ORDER BY tag", placeholders))?;
+            let mut rows = stmt.query(params_from_iter(&ids))?;
+            while let Some(row) = rows.next()? {
+                tags.entry(row.get(0)?).or_default().push(row.get(1)?);
+            }
+            let mut stmt = self.conn.prepare(&format!(
+                "SELECT reminder_id, date, time, note FROM exceptions WHERE reminder_id IN ({}) ORDER BY date",
+                placeholders
+            ))?;
+            let mut rows = stmt.query(params_from_iter(&ids))?;
+            while let Some(row) = rows.next()? {
+                let exception = Exception { date: row.get(1)?, time: row.get(2)?, note: row.get(3)? };
+                exceptions.entry(row.get(0)?).or_default().push(exception);
+            }
+
+            for reminder in batch {
+                reminder.alerts = alerts.remove(&reminder.id).unwrap_or_else(|| vec![0]);
+                reminder.tags = tags.remove(&reminder.id).unwrap_or_default();
+                reminder.exceptions = exceptions.remove(&reminder.id).unwrap_or_default();
+            }
+        }
+        Ok(())
+    }
+
+    fn set_alerts(&self, reminder_id: i32, alerts: &[i64]) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM alerts WHERE reminder_id = ?",
+            params![reminder_id],
+        )?;
+        for offset in alerts {
+            self.conn.execute(
+                "INSERT INTO alerts (reminder_id, offset_minutes) VALUES (?, ?)",
+                params![reminder_id, offset],
+            )?;
+        }
+        Ok(())
+    }
+
+    fn set_tags(&self, reminder_id: i32, tags: &[String]) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM tags WHERE reminder_id = ?",
+            params![reminder_id],
+        )?;
+        for tag in tags {
+            self.conn.execute(
+                "INSERT OR IGNORE INTO tags (reminder_id, tag) VALUES (?, ?)",
+                params![reminder_id, tag],
+            )?;
+        }
+        Ok(())
+    }
+
+    fn set_exceptions(&self, reminder_id: i32, exceptions: &[Exception]) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM exceptions WHERE reminder_id = ?",
+            params![reminder_id],
+        )?;
+        for exception in exceptions {
+            self.conn.execute(
+                "INSERT OR IGNORE INTO exceptions (reminder_id, date, time, note) VALUES (?, ?, ?, ?)",
+                params![reminder_id, &exception.date, &exception.time, &exception.note],
+            )?;
+        }
         Ok(())
     }
 }
+
+/// The schema as it stood before migrations were numbered. Every statement
+/// is idempotent, so databases created by any earlier version end up alike.
+fn create_tables(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS reminders (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL,
+            description TEXT NOT NULL,
+            time TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS alerts (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            reminder_id INTEGER NOT NULL,
+            offset_minutes INTEGER NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS notified (
+            reminder_id INTEGER NOT NULL,
+            offset_minutes INTEGER NOT NULL,
+            fired_on TEXT NOT NULL,
+            PRIMARY KEY (reminder_id, offset_minutes, fired_on)
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS snoozes (
+            reminder_id INTEGER PRIMARY KEY,
+            until TEXT NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS tags (
+            reminder_id INTEGER NOT NULL,
+            tag TEXT NOT NULL,
+            PRIMARY KEY (reminder_id, tag)
+        )",
+        [],
+    )?;
+    add_column_if_missing(conn, "reminders", "date", "TEXT")?;
+    add_column_if_missing(conn, "reminders", "cron", "TEXT")?;
+    add_column_if_missing(conn, "reminders", "interval_minutes", "INTEGER")?;
+    add_column_if_missing(conn, "reminders", "until", "TEXT")?;
+    add_column_if_missing(conn, "reminders", "last_fired_at", "TEXT")?;
+    add_column_if_missing(conn, "reminders", "timezone", "TEXT")?;
+    add_column_if_missing(conn, "reminders", "completed_at", "TEXT")?;
+    add_column_if_missing(conn, "reminders", "deleted_at", "TEXT")?;
+    add_column_if_missing(conn, "reminders", "archived", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(conn, "reminders", "delete_after_firing", "INTEGER NOT NULL DEFAULT 0")?;
+    Ok(())
+}
+
+/// A full-text index over titles and descriptions for search, kept in sync
+/// with `reminders` by triggers.
+fn add_search_index(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE VIRTUAL TABLE reminders_fts USING fts5(
+            title, description, content = 'reminders', content_rowid = 'id'
+        );
+        CREATE TRIGGER reminders_fts_insert AFTER INSERT ON reminders BEGIN
+            INSERT INTO reminders_fts (rowid, title, description) VALUES (new.id, new.title, new.description);
+        END;
+        CREATE TRIGGER reminders_fts_delete AFTER DELETE ON reminders BEGIN
+            INSERT INTO reminders_fts (reminders_fts, rowid, title, description)
+            VALUES ('delete', old.id, old.title, old.description);
+        END;
+        CREATE TRIGGER reminders_fts_update AFTER UPDATE OF title, description ON reminders BEGIN
+            INSERT INTO reminders_fts (reminders_fts, rowid, title, description)
+            VALUES ('delete', old.id, old.title, old.description);
+            INSERT INTO reminders_fts (rowid, title, description) VALUES (new.id, new.title, new.description);
+        END;
+        INSERT INTO reminders_fts (reminders_fts) VALUES ('rebuild');",
+    )
+}
+
+/// Indexes for the worker's lookups by time and the per-reminder side tables.
+fn add_time_indexes(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE INDEX reminders_time ON reminders (time);
+        CREATE INDEX reminders_date ON reminders (date);
+        CREATE INDEX alerts_reminder ON alerts (reminder_id);",
+    )
+}
+
+/// Gives every reminder a UUID that stays with it when it moves between
+/// databases.
+fn add_uuids(conn: &Connection) -> Result<()> {
+    conn.execute("ALTER TABLE reminders ADD COLUMN uuid TEXT", [])?;
+    let ids = conn
+        .prepare("SELECT id FROM reminders")?
+        .query_map([], |row| row.get::<_, i32>(0))?
+        .collect::<Result<Vec<_>>>()?;
+    for id in ids {
+        conn.execute("UPDATE reminders SET uuid = ? WHERE id = ?", params![new_uuid(), id])?;
+    }
+    conn.execute("CREATE UNIQUE INDEX reminders_uuid ON reminders (uuid)", [])?;
+    Ok(())
+}
+
+/// Tracks when each reminder last changed, starting from when it was added.
+fn add_updated_at(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "ALTER TABLE reminders ADD COLUMN updated_at TEXT NOT NULL DEFAULT '';
+        UPDATE reminders SET updated_at = COALESCE(strftime('%Y-%m-%dT%H:%M:%fZ', created_at), '');",
+    )
+}
+
+/// Remembers when a worker last ran, so the next one can tell what it missed.
+fn add_worker_state(conn: &Connection) -> Result<()> {
+    conn.execute("CREATE TABLE worker_state (name TEXT PRIMARY KEY, value TEXT NOT NULL)", [])?;
+    Ok(())
+}
+
+/// Keeps every notification shown or tried, including ones for reminders
+/// since deleted.
+fn add_notification_log(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE notification_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            reminder_id INTEGER,
+            at TEXT NOT NULL,
+            summary TEXT NOT NULL,
+            delivery TEXT NOT NULL,
+            error TEXT
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Tags are kept space-separated; they never contain spaces.
+fn add_vacations(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE vacations (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            start_date TEXT NOT NULL,
+            end_date TEXT NOT NULL,
+            tags TEXT NOT NULL DEFAULT ''
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+fn add_weekdays(conn: &Connection) -> Result<()> {
+    add_column_if_missing(conn, "reminders", "weekdays", "TEXT")
+}
+
+fn add_skip_holidays(conn: &Connection) -> Result<()> {
+    add_column_if_missing(conn, "reminders", "skip_holidays", "INTEGER NOT NULL DEFAULT 0")
+}
+
+fn add_exceptions(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE exceptions (
+            reminder_id INTEGER NOT NULL,
+            date TEXT NOT NULL,
+            PRIMARY KEY (reminder_id, date)
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+fn add_exception_changes(conn: &Connection) -> Result<()> {
+    add_column_if_missing(conn, "exceptions", "time", "TEXT")?;
+    add_column_if_missing(conn, "exceptions", "note", "TEXT")
+}
+
+fn add_notification_style(conn: &Connection) -> Result<()> {
+    add_column_if_missing(conn, "reminders", "icon", "TEXT")?;
+    add_column_if_missing(conn, "reminders", "category", "TEXT")?;
+    add_column_if_missing(conn, "reminders", "resident", "INTEGER NOT NULL DEFAULT 0")
+}
+
+fn add_priority(conn: &Connection) -> Result<()> {
+    add_column_if_missing(conn, "reminders", "priority", "TEXT NOT NULL DEFAULT 'normal'")
+}
+
+/// Looking up whether a reminder was shown since it was due, for the
+/// overdue ones.
+fn add_notification_log_index(conn: &Connection) -> Result<()> {
+    conn.execute("CREATE INDEX notification_log_reminder ON notification_log (reminder_id, at)", [])?;
+    Ok(())
+}
+
+/// Alerts are claimed per occurrence (`YYYY-MM-DD HH:MM`) rather than per
+/// day, so the column is named for that and the old per-day claims go.
+fn claim_by_occurrence(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "ALTER TABLE notified RENAME COLUMN fired_on TO occurrence;
+        DELETE FROM notified WHERE length(occurrence) = 10;",
+    )
+}
+
+/// Lets databases created by older versions pick up new nullable columns.
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<()> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let mut names = stmt.query_map([], |row| row.get::<_, String>(1))?;
+    if names.any(|name| name.as_deref() == Ok(column)) {
+        return Ok(());
+    }
+    conn.execute(
+        &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+        [],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrates_databases_from_before_versioning() {
+        let path = std::env::temp_dir().join(format!("reminder-migrate-test-{}.db", std::process::id()));
+        let path = path.to_str().unwrap();
+        let _ = std::fs::remove_file(path);
+
+        let old = Connection::open(path).unwrap();
+        old.execute_batch(
+            "CREATE TABLE reminders (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                title TEXT NOT NULL,
+                description TEXT NOT NULL,
+                time TEXT NOT NULL,
+                created_at TEXT NOT NULL
+            );
+            INSERT INTO reminders (title, description, time, created_at) VALUES ('Tea', '', '16:00', '2024-01-01');
+            CREATE TABLE notified (
+                reminder_id INTEGER NOT NULL,
+                offset_minutes INTEGER NOT NULL,
+                fired_on TEXT NOT NULL,
+                PRIMARY KEY (reminder_id, offset_minutes, fired_on)
+            );
+            INSERT INTO notified VALUES (1, 0, '2024-01-01');",
+        )
+        .unwrap();
+        drop(old);
+
+        let db = Database::new(path).unwrap();
+        let version: usize = db.conn.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(version, MIGRATIONS.len());
+        let reminders = db.get_all_reminders().unwrap();
+        assert_eq!(reminders[0].title, "Tea");
+        assert_eq!(reminders[0].alerts, vec![0]);
+        assert_eq!(reminders[0].uuid.len(), 36);
+        assert_eq!(reminders[0].priority, Priority::Normal);
+        // Claims by day are gone; the ones by occurrence work.
+        let claims: usize = db.conn.query_row("SELECT COUNT(*) FROM notified", [], |row| row.get(0)).unwrap();
+        assert_eq!(claims, 0);
+        assert!(db.claim_notification(1, 0, "2024-01-01 16:00").unwrap());
+        drop(db);
+
+        // Opening again has nothing left to do.
+        assert!(Database::new(path).is_ok());
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path, suffix));
+        }
+    }
+
+    #[test]
+    fn refuses_databases_it_cannot_use() {
+        let path = std::env::temp_dir().join(format!("reminder-newer-test-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        Connection::open(&path).unwrap().pragma_update(None, "user_version", 99).unwrap();
+        assert!(matches!(Database::new(&path), Err(store::Error::SchemaTooNew { found: 99, .. })));
+
+        let mut db = Database::new(":memory:").unwrap();
+        let empty = std::env::temp_dir().join(format!("reminder-empty-test-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&empty);
+        Connection::open(&empty).unwrap().execute_batch("CREATE TABLE notes (text TEXT)").unwrap();
+        assert!(matches!(db.restore(&empty), Err(store::Error::NotReminderDatabase(_))));
+
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&empty).unwrap();
+    }
+
+    #[test]
+    fn search_follows_edits_through_the_index() {
+        let db = Database::new(":memory:").unwrap();
+        let dentist = db
+            .add_reminder(NewReminder {
+                title: "Dentist".to_string(),
+                description: "Bring the insurance card".to_string(),
+                time: "10:00".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        let titles = |query: &str| -> Vec<String> {
+            db.search_reminders(query).unwrap().into_iter().map(|r| r.title).collect()
+        };
+
+        assert_eq!(titles("dent insur"), ["Dentist"]);
+        assert!(titles("card \"quoted").is_empty());
+
+        db.update_reminder(&Reminder { title: "Orthodontist".to_string(), ..dentist.clone() }).unwrap();
+        assert!(titles("dent").is_empty());
+        assert_eq!(titles("ortho"), ["Orthodontist"]);
+
+        db.restore_reminder(&dentist).unwrap();
+        assert_eq!(titles("dent"), ["Dentist"]);
+        db.delete_reminder(dentist.id).unwrap();
+        assert!(titles("dent").is_empty());
+    }
+
+    #[test]
+    fn details_are_read_for_every_reminder_across_batches() {
+        let db = Database::new(":memory:").unwrap();
+        let new = (0..=DETAILS_BATCH)
+            .map(|n| NewReminder {
+                title: format!("Reminder {}", n),
+                time: "09:00".to_string(),
+                alerts: if n % 2 == 0 { vec![10, 0] } else { Vec::new() },
+                tags: vec![format!("tag{}", n)],
+                ..Default::default()
+            })
+            .collect();
+        db.add_reminders_batch(new).unwrap();
+        let last = db.add_reminder(NewReminder { title: "Last".to_string(), time: "10:00".to_string(), ..Default::default() }).unwrap();
+        db.add_exception(last.id, &Exception { date: "2026-10-16".to_string(), time: None, note: None }).unwrap();
+
+        let reminders = db.reminders().unwrap();
+        assert_eq!(reminders.len(), DETAILS_BATCH + 2);
+        for (n, reminder) in reminders[..=DETAILS_BATCH].iter().enumerate() {
+            assert_eq!(reminder.tags, [format!("tag{}", n)]);
+            assert_eq!(reminder.alerts, if n % 2 == 0 { vec![10, 0] } else { vec![0] });
+        }
+        assert_eq!(reminders[DETAILS_BATCH + 1].exceptions.len(), 1);
+        assert_eq!(db.get_reminder(last.id).unwrap().unwrap().exceptions, reminders[DETAILS_BATCH + 1].exceptions);
+    }
+
+    #[test]
+    fn only_changed_fields_are_carried_over() {
+        let db = Database::new(":memory:").unwrap();
+        let before =
+            db.add_reminder(NewReminder { title: "Tea".to_string(), time: "16:00".to_string(), ..Default::default() }).unwrap();
+        let mut stored = Reminder { priority: Priority::High, description: "Green".to_string(), ..before.clone() };
+        let after = Reminder { title: "Tea break".to_string(), ..before.clone() };
+
+        stored.apply_changes(&before, &after);
+        assert_eq!(stored.title, "Tea break");
+        assert_eq!((stored.priority, stored.description.as_str()), (Priority::High, "Green"));
+    }
+
+    #[test]
+    fn due_reminders_are_found_by_alert_time() {
+        let db = Database::new(":memory:").unwrap();
+        let add = |title: &str, time: &str, alerts: Vec<i64>| {
+            db.add_reminder(NewReminder { title: title.to_string(), time: time.to_string(), alerts, ..Default::default() })
+                .unwrap();
+        };
+        add("Tea", "16:00", vec![5, 0]);
+        add("Late", "00:10", vec![20]);
+        add("Standup", "09:30", vec![0]);
+        add("Pasta", "12:03:30", vec![1, 0]);
+        let titles = |at: &str| -> Vec<String> {
+            let at = NaiveDateTime::parse_from_str(&format!("2026-10-15 {}", at), "%Y-%m-%d %H:%M").unwrap();
+            db.get_due_reminders(at).unwrap().into_iter().map(|r| r.title).collect()
+        };
+
+        assert_eq!(titles("15:55"), ["Tea"]);
+        assert_eq!(titles("16:00"), ["Tea"]);
+        assert!(titles("15:56").is_empty());
+        assert_eq!(titles("23:50"), ["Late"]);
+        // Found in the minute of the second it goes off at.
+        assert_eq!(titles("12:02"), ["Pasta"]);
+        assert_eq!(titles("12:03"), ["Pasta"]);
+        let pasta = db.get_all_reminders().unwrap().into_iter().find(|r| r.title == "Pasta").unwrap();
+        let noon = NaiveDateTime::parse_from_str("2026-10-15 12:03", "%Y-%m-%d %H:%M").unwrap();
+        assert_eq!(schedule::next_alert(&pasta, noon), Some(noon + chrono::Duration::seconds(30)));
+    }
+
+    #[test]
+    fn logs_notifications_newest_first() {
+        let db = Database::new(":memory:").unwrap();
+        let logged = |minute: u32, delivery| LoggedNotification {
+            reminder_id: Some(1),
+            at: format!("2026-10-15 07:{:02}:00", minute),
+            summary: "Meds".to_string(),
+            delivery,
+            error: (delivery == Delivery::Failed).then(|| "no notification daemon".to_string()),
+        };
+        db.log_notification(&logged(0, Delivery::Failed)).unwrap();
+        db.log_notification(&logged(1, Delivery::Delivered)).unwrap();
+
+        assert_eq!(db.notification_log(10).unwrap(), [logged(1, Delivery::Delivered), logged(0, Delivery::Failed)]);
+        assert_eq!(db.notification_log(1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn tags_are_counted_and_filtered_like_the_default() {
+        let db = Database::new(":memory:").unwrap();
+        let memory = crate::store::MemoryStore::default();
+        for store in [&db as &dyn ReminderStore, &memory] {
+            for (title, time, date, tags) in [
+                ("Standup", "09:00", None, vec!["work"]),
+                ("Review", "16:00", Some("2026-10-20"), vec!["work", "team"]),
+                ("Gym", "07:00", None, vec!["home"]),
+                ("Old", "08:00", None, vec!["work"]),
+            ] {
+                store
+                    .add_reminder(NewReminder {
+                        title: title.to_string(),
+                        time: time.to_string(),
+                        date: date.map(str::to_string),
+                        tags: tags.into_iter().map(str::to_string).collect(),
+                        ..Default::default()
+                    })
+                    .unwrap();
+            }
+            store.delete_reminder(4).unwrap();
+        }
+        for store in [&db as &dyn ReminderStore, &memory] {
+            let counts = [("home".to_string(), 1), ("team".to_string(), 1), ("work".to_string(), 2)];
+            assert_eq!(store.tag_counts().unwrap(), counts);
+            let titles: Vec<String> = store.get_tagged_reminders("work").unwrap().into_iter().map(|r| r.title).collect();
+            assert_eq!(titles, ["Standup", "Review"]);
+        }
+    }
+
+    #[test]
+    fn a_moved_occurrence_is_due_at_its_new_time() {
+        let friday = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        let at = |hour, minute| friday.and_hms_opt(hour, minute, 0).unwrap();
+        let db = Database::new(":memory:").unwrap();
+        let memory = crate::store::MemoryStore::default();
+        for store in [&db as &dyn ReminderStore, &memory] {
+            let standup = store
+                .add_reminder(NewReminder {
+                    title: "Standup".to_string(),
+                    time: "09:00".to_string(),
+                    alerts: vec![10, 0],
+                    ..Default::default()
+                })
+                .unwrap();
+            let moved = Exception { date: "2026-10-16".to_string(), time: Some("10:30".to_string()), note: None };
+            store.add_exception(standup.id, &moved).unwrap();
+
+            for (hour, minute, due) in [(8, 50, false), (9, 0, false), (10, 20, true), (10, 30, true)] {
+                let found = store.get_due_reminders(at(hour, minute)).unwrap();
+                assert_eq!(found.len(), due as usize, "{:02}:{:02}", hour, minute);
+            }
+            assert_eq!(store.get_due_reminders(at(9, 0) + chrono::Duration::days(1)).unwrap().len(), 1);
+        }
+    }
+
+    #[test]
+    fn quick_filters_match_the_default() {
+        let now = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap().and_hms_opt(10, 0, 0).unwrap();
+        let db = Database::new(":memory:").unwrap();
+        let memory = crate::store::MemoryStore::default();
+        for store in [&db as &dyn ReminderStore, &memory] {
+            for (title, time, date) in [
+                ("Call", "10:30", Some("2026-10-15")),
+                ("Lunch", "12:00", None),
+                ("Standup", "09:00", Some("2026-10-15")),
+                ("Coffee", "08:00", None),
+                ("Dentist", "09:00", Some("2026-10-16")),
+                ("Stretch", "10:20", None),
+            ] {
+                store
+                    .add_reminder(NewReminder {
+                        title: title.to_string(),
+                        time: time.to_string(),
+                        date: date.map(str::to_string),
+                        alerts: vec![0],
+                        ..Default::default()
+                    })
+                    .unwrap();
+            }
+            store
+                .log_notification(&LoggedNotification {
+                    reminder_id: Some(4),
+                    at: "2026-10-15 08:00:03".to_string(),
+                    summary: "Coffee".to_string(),
+                    delivery: Delivery::Delivered,
+                    error: None,
+                })
+                .unwrap();
+        }
+        let until = now + chrono::Duration::hours(1);
+        for store in [&db as &dyn ReminderStore, &memory] {
+            let titles = |reminders: Vec<Reminder>| reminders.into_iter().map(|r| r.title).collect::<Vec<_>>();
+            assert_eq!(titles(store.get_reminders_due_by(now, until).unwrap()), ["Stretch", "Call"]);
+            assert_eq!(titles(store.get_overdue_reminders(now).unwrap()), ["Standup"]);
+        }
+    }
+
+    #[test]
+    fn vacations_suspend_tagged_reminders() {
+        let db = Database::new(":memory:").unwrap();
+        let standup = db
+            .add_reminder(NewReminder {
+                title: "Standup".to_string(),
+                time: "09:00".to_string(),
+                tags: vec!["work".to_string()],
+                ..Default::default()
+            })
+            .unwrap();
+        let off = db.add_vacation("2026-12-20", "2027-01-02", &["work".to_string()]).unwrap();
+        assert_eq!(db.vacations().unwrap(), vec![off.clone()]);
+
+        let day = |d: &str| NaiveDate::parse_from_str(d, "%Y-%m-%d").unwrap();
+        assert!(off.covers(&standup, day("2027-01-02")));
+        assert!(!off.covers(&standup, day("2027-01-03")));
+        assert!(db.vacations_on(day("2027-01-03")).unwrap().is_empty());
+        let untagged = Reminder { tags: Vec::new(), ..standup };
+        assert!(!off.covers(&untagged, day("2026-12-24")));
+
+        db.delete_vacation(off.id).unwrap();
+        assert!(db.vacations().unwrap().is_empty());
+    }
+
+    #[test]
+    fn skipping_leaves_out_one_occurrence() {
+        let db = Database::new(":memory:").unwrap();
+        let standup = db
+            .add_reminder(NewReminder { title: "Standup".to_string(), time: "09:00".to_string(), ..Default::default() })
+            .unwrap();
+        let at = |text: &str| NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M").unwrap();
+        let now = at("2026-10-15 12:00");
+
+        let (day, _) = schedule::next_occurrence(&standup, now).unwrap();
+        let skip = Exception { date: day.format("%Y-%m-%d").to_string(), time: None, note: None };
+        db.add_exception(standup.id, &skip).unwrap();
+        let skipped = db.get_reminder(standup.id).unwrap().unwrap();
+        assert_eq!(skipped.exceptions, vec![Exception { date: "2026-10-16".to_string(), time: None, note: None }]);
+        assert!(!schedule::fires_at(&skipped, at("2026-10-16 09:00")));
+        assert_eq!(schedule::next_fire(&skipped, now), Some(at("2026-10-17 09:00")));
+
+        db.remove_exception(standup.id, "2026-10-16").unwrap();
+        let back = db.get_reminder(standup.id).unwrap().unwrap();
+        assert_eq!(schedule::next_fire(&back, now), Some(at("2026-10-16 09:00")));
+    }
+}
@@ -0,0 +1,33 @@
+use std::{env, fs, io, process::Command};
+
+/// The user's editor: `$VISUAL`, then `$EDITOR`, then `vi`.
+fn editor_command() -> String {
+    env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .ok()
+        .filter(|e| !e.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string())
+}
+
+/// Opens `text` in the user's editor and returns what was saved, without the
+/// trailing newline editors add. Returns `None` if the editor exited with an
+/// error, so the caller keeps the old text. The terminal must already be out
+/// of raw mode and the alternate screen.
+pub fn edit(text: &str) -> io::Result<Option<String>> {
+    let path = env::temp_dir().join(format!("reminder-{}.txt", std::process::id()));
+    fs::write(&path, text)?;
+
+    // Allow editors with arguments, e.g. `code --wait`.
+    let command = editor_command();
+    let mut parts = command.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    let status = Command::new(program).args(parts).arg(&path).status();
+
+    let result = match status {
+        Ok(status) if status.success() => fs::read_to_string(&path).map(|s| Some(s.trim_end().to_string())),
+        Ok(_) => Ok(None),
+        Err(e) => Err(io::Error::new(e.kind(), format!("couldn't start {}: {}", program, e))),
+    };
+    let _ = fs::remove_file(&path);
+    result
+}
@@ -0,0 +1,26 @@
+//! The one error type the stores report, whichever backend they use.
+
+use std::{io, path::PathBuf};
+use thiserror::Error;
+
+/// What went wrong reading or changing reminders.
+#[derive(Debug, Error)]
+pub enum ReminderError {
+    #[error("database error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[cfg(feature = "postgres")]
+    #[error("PostgreSQL error: {0}")]
+    Postgres(#[from] postgres::Error),
+    #[error("{0}")]
+    Io(#[from] io::Error),
+    /// A backup to restore from has no reminders table.
+    #[error("{} is not a reminder database", .0.display())]
+    NotReminderDatabase(PathBuf),
+    /// The database was migrated by a newer version of the app.
+    #[error("database schema version {found} is newer than this build understands ({supported})")]
+    SchemaTooNew { found: usize, supported: usize },
+    #[cfg(feature = "sqlcipher")]
+    #[error("wrong passphrase for the database")]
+    WrongPassphrase,
+}
+
@@ -0,0 +1,122 @@
+use serde::Serialize;
+use std::{io, time::Duration};
+use tokio::{
+    signal::unix::{signal, SignalKind},
+    sync::{
+        broadcast::{self, error::RecvError},
+        mpsc::UnboundedSender,
+    },
+};
+
+/// Something that happened to reminders, published to every part of the app
+/// that cares: the TUI, the worker, and HTTP event-stream clients.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReminderEvent {
+    /// A notification was shown (or held back for quiet hours or while muted).
+    Fired {
+        id: i32,
+        title: String,
+        offset_minutes: i64,
+    },
+    /// Reminders were added, edited, deleted or snoozed.
+    Changed,
+    /// The TUI switched to another profile's reminders; `None` is the default
+    /// profile.
+    ProfileSwitched { profile: Option<String> },
+    /// Reminders went off while no worker was running or the machine was
+    /// asleep, found once one is back; or while notifications were muted,
+    /// handed over once they are unmuted.
+    Missed { reminders: Vec<Missed>, while_muted: bool },
+    /// Desktop notifications were muted or unmuted with `m` in the TUI.
+    Muted { muted: bool },
+    /// "Snooze all" was clicked on a notification; the worker snoozes every
+    /// reminder that is due.
+    SnoozeAll,
+}
+
+/// A reminder that went off unnoticed.
+#[derive(Debug, Clone, Serialize)]
+pub struct Missed {
+    pub id: i32,
+    pub title: String,
+    /// When it last should have gone off (`YYYY-MM-DD HH:MM`).
+    pub at: String,
+    /// How many times it should have gone off since the last check.
+    pub times: usize,
+}
+
+pub type EventBus = broadcast::Sender<ReminderEvent>;
+
+pub fn new_bus() -> EventBus {
+    broadcast::channel(64).0
+}
+
+/// Publishes an event; having no subscribers at the moment is not an error.
+pub fn publish(bus: &EventBus, event: ReminderEvent) {
+    let _ = bus.send(event);
+}
+
+/// What the TUI's loop waits on: terminal input, a steady tick for timed
+/// things like the idle lock, and whatever happens to reminders.
+pub enum AppEvent {
+    Input(crossterm::event::Event),
+    Tick,
+    Reminder(ReminderEvent),
+    /// SIGTERM, SIGINT or SIGHUP arrived; time to shut down.
+    Quit,
+}
+
+/// Passes every event on `bus` to `tx`, wrapped by `wrap`, from a task on
+/// the current runtime until the receiving end goes away. Events missed by
+/// falling behind are reported as a [`ReminderEvent::Changed`] so the
+/// receiver reloads.
+pub fn forward<T: Send + 'static>(bus: &EventBus, tx: UnboundedSender<T>, wrap: fn(ReminderEvent) -> T) {
+    let mut rx = bus.subscribe();
+    tokio::spawn(async move {
+        loop {
+            let event = match rx.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(_)) => ReminderEvent::Changed,
+                Err(RecvError::Closed) => return,
+            };
+            if tx.send(wrap(event)).is_err() {
+                return;
+            }
+        }
+    });
+}
+
+/// Sends an [`AppEvent::Tick`] to `tx` every `every`, from a task on the
+/// current runtime, until it goes away.
+pub fn tick(tx: UnboundedSender<AppEvent>, every: Duration) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(every);
+        loop {
+            interval.tick().await;
+            if tx.send(AppEvent::Tick).is_err() {
+                return;
+            }
+        }
+    });
+}
+
+/// Sends an [`AppEvent::Quit`] to `tx` when the process is asked to stop or
+/// its terminal goes away, so the TUI can shut down like it does for `q`.
+pub fn quit_on_signals(tx: UnboundedSender<AppEvent>) -> io::Result<()> {
+    let (mut term, mut int, mut hup) =
+        (signal(SignalKind::terminate())?, signal(SignalKind::interrupt())?, signal(SignalKind::hangup())?);
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = term.recv() => {}
+                _ = int.recv() => {}
+                _ = hup.recv() => {}
+            }
+            if tx.send(AppEvent::Quit).is_err() {
+                return;
+            }
+        }
+    });
+    Ok(())
+}
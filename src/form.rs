@@ -0,0 +1,255 @@
+use crate::config::TimeFormat;
+use crate::db::{Exception, NewReminder, Reminder};
+use crate::schedule;
+use crate::ui::FORM_FIELD_COUNT;
+use crate::{parse_alerts, parse_every, parse_tags, validate_date_format};
+use std::path::Path;
+
+/// Problems with the add/edit form, as (field index, message) pairs.
+pub type FieldErrors = Vec<(usize, String)>;
+
+/// Checks every field of the add/edit form and builds the reminder it
+/// describes, or reports what is wrong with each field.
+pub fn validate(fields: &[String; FORM_FIELD_COUNT], time_format: TimeFormat) -> Result<NewReminder, FieldErrors> {
+    let mut errors = FieldErrors::new();
+    let mut error = |field: usize, message: String| errors.push((field, message));
+
+    if fields[0].trim().is_empty() {
+        error(0, "Title is required".to_string());
+    }
+    if fields[1].trim().is_empty() {
+        error(1, "Description is required".to_string());
+    }
+
+    let cron = fields[6].trim();
+    let cron = (!cron.is_empty()).then(|| cron.to_string());
+    if let Some(Err(e)) = cron.as_deref().map(schedule::parse_cron) {
+        error(6, format!("Invalid cron expression: {}", e));
+    }
+
+    let time = fields[2].trim();
+    let time = if cron.is_some() {
+        if !time.is_empty() {
+            error(2, "Leave empty when using a cron schedule".to_string());
+        }
+        String::new()
+    } else if time.is_empty() {
+        error(2, "Time is required, or a cron schedule".to_string());
+        String::new()
+    } else {
+        time_format.parse(time).unwrap_or_else(|| {
+            error(2, format!("Invalid time. Use {}", time_format.example()));
+            String::new()
+        })
+    };
+
+    let date = fields[3].trim();
+    if !date.is_empty() && cron.is_some() {
+        error(3, "Leave empty when using a cron schedule".to_string());
+    } else if !date.is_empty() && !validate_date_format(date) {
+        error(3, "Invalid date. Use YYYY-MM-DD, or leave empty to repeat daily".to_string());
+    }
+    let date = (!date.is_empty()).then(|| date.to_string());
+
+    let alerts = parse_alerts(&fields[4]).unwrap_or_else(|| {
+        error(4, "Invalid alerts. Use minutes before, e.g. 1h,10m,0".to_string());
+        Vec::new()
+    });
+
+    let every = fields[7].trim();
+    let (interval_minutes, until) = if every.is_empty() {
+        (None, None)
+    } else if cron.is_some() {
+        error(7, "Use either a cron schedule or an interval, not both".to_string());
+        (None, None)
+    } else if let Some((minutes, until)) = parse_every(every, time_format) {
+        if until.as_deref().is_some_and(|until| until < time.as_str()) {
+            error(7, "The interval must end after its start time".to_string());
+        }
+        (Some(minutes), until)
+    } else {
+        error(7, format!("Invalid interval. Use e.g. 45m or 2h until {}", time_format.render("17:00")));
+        (None, None)
+    };
+
+    let weekdays = fields[8].trim();
+    let weekdays = if weekdays.is_empty() {
+        None
+    } else if date.is_some() {
+        error(8, "Leave empty for a one-off reminder".to_string());
+        None
+    } else {
+        match schedule::parse_weekdays(weekdays) {
+            Ok(mask) => Some(schedule::weekdays_text(mask)),
+            Err(e) => {
+                error(8, format!("{}. Use e.g. weekdays or mon,wed,fri", e));
+                None
+            }
+        }
+    };
+
+    let timezone = fields[9].trim();
+    if let Some(Err(e)) = (!timezone.is_empty()).then(|| schedule::parse_timezone(timezone)) {
+        error(9, format!("{}. Use a name like Europe/Berlin", e));
+    }
+    let timezone = (!timezone.is_empty()).then(|| timezone.to_string());
+
+    let delete_after_firing = match fields[10].trim() {
+        "" => false,
+        "delete" => true,
+        _ => {
+            error(10, "Type delete, or leave empty to keep it".to_string());
+            false
+        }
+    };
+
+    let skip_holidays = match fields[11].trim() {
+        "" => false,
+        "skip" => true,
+        _ => {
+            error(11, "Type skip, or leave empty to go off on holidays too".to_string());
+            false
+        }
+    };
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    Ok(NewReminder {
+        uuid: None,
+        title: fields[0].clone(),
+        description: fields[1].clone(),
+        time,
+        date,
+        alerts,
+        tags: parse_tags(&fields[5]),
+        cron,
+        interval_minutes,
+        until,
+        weekdays,
+        timezone,
+        delete_after_firing,
+        skip_holidays,
+        ..Default::default()
+    })
+}
+
+/// Fields of the occurrence editor: date, time and note.
+pub const OCCURRENCE_FIELD_COUNT: usize = 3;
+
+/// Checks the occurrence editor and builds the exception it describes, or
+/// reports the first field that is wrong. An empty time skips the day.
+pub fn validate_occurrence(
+    fields: &[String; OCCURRENCE_FIELD_COUNT],
+    time_format: TimeFormat,
+) -> Result<Exception, (usize, String)> {
+    let date = fields[0].trim();
+    if !validate_date_format(date) {
+        return Err((0, "Invalid date. Use YYYY-MM-DD".to_string()));
+    }
+    let note = fields[2].trim();
+    let note = (!note.is_empty()).then(|| note.to_string());
+    let time = match fields[1].trim() {
+        "" if note.is_some() => return Err((2, "A skipped day has no notification to add a note to".to_string())),
+        "" => None,
+        time => Some(time_format.parse(time).ok_or_else(|| (1, format!("Invalid time. Use {}", time_format.example())))?),
+    };
+    Ok(Exception { date: date.to_string(), time, note })
+}
+
+/// Fields of the notification settings: icon, category and whether it stays.
+pub const SETTINGS_FIELD_COUNT: usize = 3;
+
+/// Checks the notification settings and returns `reminder` with them, or
+/// reports the first field that is wrong.
+pub fn validate_settings(fields: &[String; SETTINGS_FIELD_COUNT], reminder: &Reminder) -> Result<Reminder, (usize, String)> {
+    let icon = fields[0].trim();
+    // Anything else is an icon name, looked up by the notification server.
+    if icon.contains('/') && !Path::new(icon).is_file() {
+        return Err((0, format!("No image at {}", icon)));
+    }
+    let category = fields[1].trim();
+    if category.contains(char::is_whitespace) {
+        return Err((1, "A category is one word, e.g. im or x-medication".to_string()));
+    }
+    let resident = match fields[2].trim() {
+        "" => false,
+        "keep" => true,
+        _ => return Err((2, "Type keep, or leave empty to let it close by itself".to_string())),
+    };
+    Ok(Reminder {
+        icon: (!icon.is_empty()).then(|| icon.to_string()),
+        category: (!category.is_empty()).then(|| category.to_string()),
+        resident,
+        ..reminder.clone()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(values: &[(usize, &str)]) -> [String; FORM_FIELD_COUNT] {
+        let mut fields: [String; FORM_FIELD_COUNT] = Default::default();
+        for (i, value) in values {
+            fields[*i] = value.to_string();
+        }
+        fields
+    }
+
+    fn error_fields(result: Result<NewReminder, FieldErrors>) -> Vec<usize> {
+        result.err().unwrap_or_default().into_iter().map(|(field, _)| field).collect()
+    }
+
+    #[test]
+    fn builds_a_reminder_from_valid_fields() {
+        let new = validate(&fields(&[(0, "Tea"), (1, "Green"), (2, "16:00"), (4, "5m,0"), (5, "#home")]), TimeFormat::H24).unwrap();
+        assert_eq!(new.time, "16:00");
+        assert_eq!(new.alerts, vec![5, 0]);
+        assert_eq!(new.tags, vec!["home"]);
+    }
+
+    #[test]
+    fn reports_every_bad_field() {
+        let result = validate(&fields(&[(1, "Green"), (2, "25:00"), (3, "tomorrow")]), TimeFormat::H24);
+        assert_eq!(error_fields(result), vec![0, 2, 3]);
+    }
+
+    #[test]
+    fn cron_replaces_time_and_date() {
+        let result = validate(&fields(&[(0, "Standup"), (1, "Sync"), (6, "0 9 * * MON-FRI")]), TimeFormat::H24);
+        assert!(result.is_ok());
+        let result = validate(&fields(&[(0, "Standup"), (1, "Sync"), (2, "09:00"), (6, "0 9 * * MON-FRI"), (7, "1h")]), TimeFormat::H24);
+        assert_eq!(error_fields(result), vec![2, 7]);
+    }
+
+    #[test]
+    fn weekdays_are_written_the_same_way_every_time() {
+        let new = validate(&fields(&[(0, "Standup"), (1, "Sync"), (2, "09:00"), (8, "Mon-Fri")]), TimeFormat::H24).unwrap();
+        assert_eq!(new.weekdays.as_deref(), Some("weekdays"));
+        let new = validate(&fields(&[(0, "Gym"), (1, "Legs"), (2, "18:00"), (8, "friday, mon")]), TimeFormat::H24).unwrap();
+        assert_eq!(new.weekdays.as_deref(), Some("mon,fri"));
+        let result = validate(&fields(&[(0, "Gym"), (1, "Legs"), (2, "18:00"), (3, "2026-10-16"), (8, "fri")]), TimeFormat::H24);
+        assert_eq!(error_fields(result), vec![8]);
+    }
+
+    #[test]
+    fn settings_fill_in_how_it_notifies() {
+        use reminder::{MemoryStore, ReminderStore};
+        let store = MemoryStore::new();
+        let meds = store
+            .add_reminder(NewReminder { title: "Meds".to_string(), time: "08:00".to_string(), ..Default::default() })
+            .unwrap();
+
+        let fields = ["dialog-warning".to_string(), "x-medication".to_string(), "keep".to_string()];
+        let after = validate_settings(&fields, &meds).unwrap();
+        assert_eq!((after.icon.as_deref(), after.category.as_deref(), after.resident), (Some("dialog-warning"), Some("x-medication"), true));
+
+        let cleared = validate_settings(&Default::default(), &after).unwrap();
+        assert_eq!(cleared, meds);
+        let fields = [String::new(), "pill reminders".to_string(), String::new()];
+        assert_eq!(validate_settings(&fields, &meds).unwrap_err().0, 1);
+        let fields = ["/no/such/icon.png".to_string(), String::new(), String::new()];
+        assert_eq!(validate_settings(&fields, &meds).unwrap_err().0, 0);
+    }
+}
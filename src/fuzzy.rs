@@ -0,0 +1,144 @@
+//! Matching for the search, the way fzf does it: the letters of each word
+//! only have to appear in order, so `mtg stnd` finds "Meeting: standup".
+//! Each word has to start where one of the reminder's does, which lets the
+//! full-text index pick the reminders worth matching.
+
+/// Points for each letter matched.
+const MATCH: i64 = 16;
+/// Extra for a letter starting a word, as in an abbreviation.
+const WORD_START: i64 = 8;
+/// Extra for a letter right after the one before it.
+const CONSECUTIVE: i64 = 4;
+/// Taken off for a gap between two letters, and for each letter skipped.
+const GAP_START: i64 = 3;
+const GAP: i64 = 1;
+
+/// Where a search matched a reminder, and how well.
+#[derive(Debug, Default, PartialEq)]
+pub struct FuzzyMatch {
+    pub score: i64,
+    /// Char indices of the matched letters in the title, in order.
+    pub title: Vec<usize>,
+    /// The same for the description.
+    pub description: Vec<usize>,
+}
+
+/// Matches every word of `query` against the title or the description,
+/// whichever it fits better, ignoring case. `None` if a word fits neither.
+pub fn find(query: &str, title: &str, description: &str) -> Option<FuzzyMatch> {
+    let title = lowercase(title);
+    let description = lowercase(description);
+    let mut found = FuzzyMatch::default();
+    for word in query.split_whitespace() {
+        let word = lowercase(word);
+        let in_title = find_word(&word, &title);
+        let in_description = find_word(&word, &description);
+        match (in_title, in_description) {
+            (Some((score, positions)), other) if other.as_ref().is_none_or(|(other, _)| *other <= score) => {
+                found.score += score;
+                found.title.extend(positions);
+            }
+            (_, Some((score, positions))) => {
+                found.score += score;
+                found.description.extend(positions);
+            }
+            _ => return None,
+        }
+    }
+    for positions in [&mut found.title, &mut found.description] {
+        positions.sort_unstable();
+        positions.dedup();
+    }
+    Some(found)
+}
+
+/// What to ask the full-text index for before matching: the first letter of
+/// each word of `query`, as a word prefix, since a word has to start where
+/// one in the reminder does. Words starting with punctuation aren't narrowed
+/// down by.
+pub fn index_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .filter_map(|word| word.chars().next().filter(|c| c.is_alphanumeric()))
+        .map(|c| c.to_lowercase().to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// One char for each char of `text`, so indices carry over.
+fn lowercase(text: &str) -> Vec<char> {
+    text.chars().map(|c| c.to_lowercase().next().unwrap_or(c)).collect()
+}
+
+/// The score and positions of `word` in `text`: the first place it fits,
+/// then back from its end to the latest start, so the letters sit as close
+/// together as they can.
+fn find_word(word: &[char], text: &[char]) -> Option<(i64, Vec<usize>)> {
+    let mut matched = 0;
+    let end = text.iter().position(|c| {
+        if *c == word[matched] {
+            matched += 1;
+        }
+        matched == word.len()
+    })?;
+    let mut positions = Vec::with_capacity(word.len());
+    for at in (0..=end).rev() {
+        if text[at] == word[word.len() - positions.len() - 1] {
+            positions.push(at);
+            if positions.len() == word.len() {
+                break;
+            }
+        }
+    }
+    positions.reverse();
+    Some((score(text, &positions), positions))
+}
+
+fn score(text: &[char], positions: &[usize]) -> i64 {
+    let mut score = 0;
+    for (n, &at) in positions.iter().enumerate() {
+        score += MATCH;
+        if at == 0 || !text[at - 1].is_alphanumeric() {
+            score += WORD_START;
+        }
+        if n > 0 {
+            match at - positions[n - 1] - 1 {
+                0 => score += CONSECUTIVE,
+                gap => score -= GAP_START + gap as i64 * GAP,
+            }
+        }
+    }
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_words_as_subsequences() {
+        let found = find("mtg STND", "Meeting: standup", "").unwrap();
+        assert_eq!(found.title, [0, 3, 6, 9, 10, 12, 13]);
+        assert!(found.description.is_empty());
+
+        let found = find("mtg room", "Meeting: standup", "Big room").unwrap();
+        assert_eq!((found.title, found.description), (vec![0, 3, 6], vec![4, 5, 6, 7]));
+
+        assert_eq!(find("mtg xyz", "Meeting: standup", "Big room"), None);
+        assert_eq!(find("gtm", "Meeting", ""), None);
+    }
+
+    #[test]
+    fn asks_the_index_for_each_words_first_letter() {
+        assert_eq!(index_query("mtg STND"), "m s");
+        assert_eq!(index_query("  #work tea "), "t");
+        assert_eq!(index_query(""), "");
+    }
+
+    #[test]
+    fn close_letters_at_word_starts_rank_first() {
+        let score = |title| find("tea", title, "").unwrap().score;
+        assert!(score("Tea time") > score("Take meds at eight"));
+        assert!(score("Green tea") > score("Treat the cat"));
+    }
+}
@@ -0,0 +1,100 @@
+use crate::db::Reminder;
+use reminder::store::{ReminderStore, Result};
+
+/// How many changes `u` can step back through.
+const MAX_HISTORY: usize = 100;
+
+/// One user action, as the affected reminders before and after it. A
+/// reminder only in `before` was deleted; one only in `after` was added.
+#[derive(Debug, Clone)]
+struct Change {
+    before: Vec<Reminder>,
+    after: Vec<Reminder>,
+}
+
+/// Undo and redo stacks for changes made from the TUI.
+#[derive(Debug, Default)]
+pub struct History {
+    undo: Vec<Change>,
+    redo: Vec<Change>,
+}
+
+impl History {
+    /// Remembers a change that was just written to the database.
+    pub fn record(&mut self, before: Vec<Reminder>, after: Vec<Reminder>) {
+        self.undo.push(Change { before, after });
+        if self.undo.len() > MAX_HISTORY {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    /// Reverts the last change. Returns `false` if there was nothing to undo.
+    pub fn undo(&mut self, db: &dyn ReminderStore) -> Result<bool> {
+        let Some(change) = self.undo.pop() else {
+            return Ok(false);
+        };
+        if let Err(e) = apply(db, &change.after, &change.before) {
+            self.undo.push(change);
+            return Err(e);
+        }
+        self.redo.push(change);
+        Ok(true)
+    }
+
+    /// Reapplies the last undone change. Returns `false` if there was nothing to redo.
+    pub fn redo(&mut self, db: &dyn ReminderStore) -> Result<bool> {
+        let Some(change) = self.redo.pop() else {
+            return Ok(false);
+        };
+        if let Err(e) = apply(db, &change.before, &change.after) {
+            self.redo.push(change);
+            return Err(e);
+        }
+        self.undo.push(change);
+        Ok(true)
+    }
+}
+
+/// Moves the database from the `from` state of some reminders to the `to` state.
+fn apply(db: &dyn ReminderStore, from: &[Reminder], to: &[Reminder]) -> Result<()> {
+    let removed: Vec<i32> = from
+        .iter()
+        .filter(|r| !to.iter().any(|t| t.id == r.id))
+        .map(|r| r.id)
+        .collect();
+    db.purge_reminders(&removed)?;
+    // Undoing is a change of its own as far as syncing is concerned.
+    for reminder in to {
+        let mut reminder = reminder.clone();
+        reminder.touch();
+        db.restore_reminder(&reminder)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::NewReminder;
+    use reminder::MemoryStore;
+
+    #[test]
+    fn undoes_and_redoes_a_delete() {
+        let store = MemoryStore::new();
+        let tea = store
+            .add_reminder(NewReminder { title: "Tea".to_string(), time: "16:00".to_string(), ..Default::default() })
+            .unwrap();
+        let mut history = History::default();
+
+        store.purge_reminder(tea.id).unwrap();
+        history.record(vec![tea.clone()], Vec::new());
+
+        assert!(history.undo(&store).unwrap());
+        let restored = store.get_reminder(tea.id).unwrap().unwrap();
+        assert_eq!(Reminder { updated_at: tea.updated_at.clone(), ..restored }, tea);
+        assert!(history.redo(&store).unwrap());
+        assert_eq!(store.get_reminder(tea.id).unwrap(), None);
+        assert!(!history.redo(&store).unwrap());
+    }
+}
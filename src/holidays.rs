@@ -0,0 +1,63 @@
+//! Public holidays, read from the file named by `holidays` in the config.
+
+use chrono::{Duration, NaiveDate};
+use std::{collections::BTreeSet, fs, io, path::Path};
+
+/// The holidays in the file at `path`: an iCalendar file as exported by most
+/// calendar apps, or plain text with a `YYYY-MM-DD` date starting each line.
+pub fn load(path: &Path) -> io::Result<BTreeSet<NaiveDate>> {
+    Ok(parse(&fs::read_to_string(path)?))
+}
+
+fn parse(text: &str) -> BTreeSet<NaiveDate> {
+    if text.trim_start().starts_with("BEGIN:VCALENDAR") {
+        return parse_ics(text);
+    }
+    text.lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .filter_map(|first| NaiveDate::parse_from_str(first, "%Y-%m-%d").ok())
+        .collect()
+}
+
+/// Every day covered by an event. Only the dates matter: `DTEND` is the
+/// first day after the event, as with all-day events.
+fn parse_ics(text: &str) -> BTreeSet<NaiveDate> {
+    let date = |value: &str| NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok();
+    let mut days = BTreeSet::new();
+    let (mut start, mut end) = (None, None);
+    for line in text.lines() {
+        let Some((name, value)) = line.trim_end().split_once(':') else {
+            continue;
+        };
+        // Parameters such as `;VALUE=DATE` follow the property's name.
+        match name.split(';').next().unwrap_or_default() {
+            "BEGIN" if value == "VEVENT" => (start, end) = (None, None),
+            "DTSTART" => start = date(value),
+            "DTEND" => end = date(value),
+            "END" if value == "VEVENT" => {
+                if let Some(start) = start {
+                    let end = end.filter(|end| *end > start).unwrap_or(start + Duration::days(1));
+                    days.extend(start.iter_days().take_while(|day| *day < end));
+                }
+            }
+            _ => {}
+        }
+    }
+    days
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_date_lists_and_calendars() {
+        let day = |text| NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap();
+        let list = parse("# Public holidays\n2026-12-25 Christmas Day\n2026-12-26\n");
+        assert_eq!(list, BTreeSet::from([day("2026-12-25"), day("2026-12-26")]));
+
+        let ics = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20261225\r\nDTEND;VALUE=DATE:20261227\r\n\
+                   SUMMARY:Christmas\r\nEND:VEVENT\r\nBEGIN:VEVENT\r\nDTSTART:20270101T000000Z\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        assert_eq!(parse(ics), BTreeSet::from([day("2026-12-25"), day("2026-12-26"), day("2027-01-01")]));
+    }
+}
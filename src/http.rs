@@ -0,0 +1,428 @@
+use crate::config::{HttpConfig, TimeFormat};
+use crate::db::{NewReminder, Reminder};
+use reminder::SharedStore;
+use crate::events::{publish, EventBus, ReminderEvent};
+use crate::schedule;
+use crate::{parse_alerts, parse_every, parse_tags, validate_date_format, validate_time_format};
+use axum::{
+    extract::{Path, Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::{self, Next},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Html, IntoResponse, Redirect, Response,
+    },
+    routing::{get, post},
+    Form, Json, Router,
+};
+use futures_util::stream::{self, Stream};
+use serde::Deserialize;
+use std::{
+    convert::Infallible,
+    error::Error,
+    sync::Arc,
+};
+use tokio::sync::broadcast::error::RecvError;
+
+#[derive(Clone)]
+struct AppState {
+    db: Arc<SharedStore>,
+    token: Arc<str>,
+    events: EventBus,
+}
+
+#[derive(Deserialize)]
+struct ReminderInput {
+    title: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    time: String,
+    /// `YYYY-MM-DD`; missing or empty repeats daily.
+    #[serde(default)]
+    date: Option<String>,
+    /// Same syntax as the form, e.g. `"1h,10m,0"`.
+    #[serde(default)]
+    alerts: String,
+    /// Same syntax as the form, e.g. `"work, family"`.
+    #[serde(default)]
+    tags: String,
+    /// Five-field cron expression used instead of `time` and `date`.
+    #[serde(default)]
+    cron: Option<String>,
+    /// Same syntax as the form, e.g. `"45m until 17:00"`.
+    #[serde(default)]
+    every: String,
+    /// Days a repeating reminder goes off, e.g. `"weekdays"` or `"mon,wed,fri"`.
+    #[serde(default)]
+    weekdays: Option<String>,
+    /// IANA zone such as `"Europe/Berlin"`; missing or empty means local time.
+    #[serde(default)]
+    timezone: Option<String>,
+    #[serde(default)]
+    delete_after_firing: bool,
+    /// Stay quiet on the holidays in the config's holiday file.
+    #[serde(default)]
+    skip_holidays: bool,
+}
+
+#[derive(Deserialize)]
+struct LoginInput {
+    token: String,
+}
+
+type ApiResult<T> = Result<T, (StatusCode, String)>;
+
+const INDEX_HTML: &str = include_str!("web/index.html");
+const LOGIN_HTML: &str = include_str!("web/login.html");
+const TOKEN_COOKIE: &str = "reminder_token";
+
+fn internal(e: impl std::fmt::Display) -> (StatusCode, String) {
+    tracing::error!("HTTP request failed: {}", e);
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+impl ReminderInput {
+    fn validate(self) -> ApiResult<NewReminder> {
+        if self.title.trim().is_empty() {
+            return Err((StatusCode::UNPROCESSABLE_ENTITY, "title must not be empty".into()));
+        }
+        let cron = self.cron.filter(|c| !c.trim().is_empty());
+        let date = self.date.filter(|d| !d.is_empty());
+        if let Some(cron) = &cron {
+            if let Err(e) = schedule::parse_cron(cron) {
+                return Err((StatusCode::UNPROCESSABLE_ENTITY, format!("invalid cron: {}", e)));
+            }
+            if !self.time.is_empty() || date.is_some() {
+                return Err((StatusCode::UNPROCESSABLE_ENTITY, "cron replaces time and date".into()));
+            }
+        } else if !validate_time_format(&self.time) {
+            return Err((StatusCode::UNPROCESSABLE_ENTITY, "time must be HH:MM[:SS]".into()));
+        }
+        if date.as_deref().is_some_and(|d| !validate_date_format(d)) {
+            return Err((StatusCode::UNPROCESSABLE_ENTITY, "date must be YYYY-MM-DD".into()));
+        }
+        let alerts = parse_alerts(&self.alerts)
+            .ok_or((StatusCode::UNPROCESSABLE_ENTITY, "invalid alerts".to_string()))?;
+        let timezone = self.timezone.filter(|z| !z.trim().is_empty());
+        if let Some(Err(e)) = timezone.as_deref().map(schedule::parse_timezone) {
+            return Err((StatusCode::UNPROCESSABLE_ENTITY, e));
+        }
+        let weekdays = match self.weekdays.filter(|w| !w.trim().is_empty()) {
+            Some(_) if date.is_some() => {
+                return Err((StatusCode::UNPROCESSABLE_ENTITY, "weekdays only apply to repeating reminders".into()))
+            }
+            Some(weekdays) => Some(schedule::weekdays_text(
+                schedule::parse_weekdays(&weekdays).map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e))?,
+            )),
+            None => None,
+        };
+        let (interval_minutes, until) = match self.every.trim() {
+            "" => (None, None),
+            _ if cron.is_some() => {
+                return Err((StatusCode::UNPROCESSABLE_ENTITY, "use either cron or every, not both".into()))
+            }
+            every => match parse_every(every, TimeFormat::H24) {
+                Some((_, Some(until))) if until < self.time => {
+                    return Err((StatusCode::UNPROCESSABLE_ENTITY, "every must end after time".into()))
+                }
+                Some((minutes, until)) => (Some(minutes), until),
+                None => return Err((StatusCode::UNPROCESSABLE_ENTITY, "invalid every".into())),
+            },
+        };
+
+        Ok(NewReminder {
+            uuid: None,
+            title: self.title,
+            description: self.description,
+            time: self.time,
+            date,
+            alerts,
+            tags: parse_tags(&self.tags),
+            cron,
+            interval_minutes,
+            until,
+            weekdays,
+            timezone,
+            delete_after_firing: self.delete_after_firing,
+            skip_holidays: self.skip_holidays,
+            ..Default::default()
+        })
+    }
+}
+
+/// Serves the REST API from a task on the current runtime, publishing
+/// `Changed` whenever a request modifies reminders and streaming every event
+/// on the bus to `/events` subscribers.
+pub fn spawn_server(config: HttpConfig, db: Arc<SharedStore>, events: EventBus) -> Result<(), Box<dyn Error>> {
+    // A blank token would let any request in.
+    if config.token.trim().is_empty() {
+        return Err("http.token must not be empty".into());
+    }
+    let state = AppState {
+        db,
+        token: config.token.into(),
+        events,
+    };
+    // Bound here so a taken port is reported straight away.
+    let listener = std::net::TcpListener::bind(&config.bind)?;
+    listener.set_nonblocking(true)?;
+    let listener = tokio::net::TcpListener::from_std(listener)?;
+
+    tokio::spawn(async move {
+        let _ = axum::serve(listener, router(state)).await;
+    });
+    Ok(())
+}
+
+fn router(state: AppState) -> Router {
+    let api = Router::new()
+        .route("/reminders", get(list_reminders).post(create_reminder))
+        .route(
+            "/reminders/{id}",
+            get(get_reminder).put(update_reminder).delete(delete_reminder),
+        )
+        .route("/events", get(event_stream))
+        .layer(middleware::from_fn_with_state(state.clone(), require_token));
+
+    let web = Router::new()
+        .route("/ui/reminders", post(web_add))
+        .route("/ui/reminders/{id}/delete", post(web_delete))
+        .layer(middleware::from_fn_with_state(state.clone(), require_session));
+
+    Router::new()
+        .route("/", get(index))
+        .route("/login", post(login))
+        .merge(api)
+        .merge(web)
+        .with_state(state)
+}
+
+/// The cookie holds the token hex-encoded so any configured token is a valid cookie value.
+fn cookie_value(token: &str) -> String {
+    token.bytes().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Accepts either `Authorization: Bearer <token>` (scripts) or the cookie set
+/// by the login form (browsers).
+fn is_authorized(headers: &HeaderMap, token: &str) -> bool {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if bearer == Some(token) {
+        return true;
+    }
+
+    let expected = cookie_value(token);
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .any(|(name, value)| name == TOKEN_COOKIE && value == expected)
+}
+
+async fn require_token(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    if is_authorized(request.headers(), &state.token) {
+        next.run(request).await
+    } else {
+        StatusCode::UNAUTHORIZED.into_response()
+    }
+}
+
+async fn require_session(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    if is_authorized(request.headers(), &state.token) {
+        next.run(request).await
+    } else {
+        Redirect::to("/").into_response()
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn error_html(error: Option<&str>) -> String {
+    error
+        .map(|e| format!("<p class=\"error\">{}</p>", escape_html(e)))
+        .unwrap_or_default()
+}
+
+fn render_index(state: &AppState, error: Option<&str>) -> Response {
+    let reminders = match state.db.lock().get_all_reminders() {
+        Ok(reminders) => reminders,
+        Err(e) => return internal(e).into_response(),
+    };
+
+    let today = chrono::Local::now().date_naive();
+    let mut todays: Vec<(String, &Reminder)> = reminders
+        .iter()
+        .filter_map(|r| {
+            let at = schedule::next_fire(r, today.and_hms_opt(0, 0, 0)?)?;
+            (at.date() == today).then(|| (at.format("%H:%M").to_string(), r))
+        })
+        .collect();
+    todays.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let rows = if todays.is_empty() {
+        "<tr><td colspan=\"3\">Nothing scheduled.</td></tr>".to_string()
+    } else {
+        todays
+            .iter()
+            .map(|(time, r)| {
+                let tags: String = r.tags.iter().map(|t| format!(" #{}", escape_html(t))).collect();
+                format!(
+                    "<tr><td class=\"time\">{}</td><td><strong>{}</strong>{}<br>{}</td>\
+                     <td><form method=\"post\" action=\"/ui/reminders/{}/delete\">\
+                     <button>Delete</button></form></td></tr>",
+                    time,
+                    escape_html(&r.title),
+                    tags,
+                    escape_html(&r.description),
+                    r.id
+                )
+            })
+            .collect()
+    };
+
+    Html(
+        INDEX_HTML
+            .replace("{{error}}", &error_html(error))
+            .replace("{{rows}}", &rows),
+    )
+    .into_response()
+}
+
+async fn index(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    if is_authorized(&headers, &state.token) {
+        render_index(&state, None)
+    } else {
+        Html(LOGIN_HTML.replace("{{error}}", "")).into_response()
+    }
+}
+
+async fn login(State(state): State<AppState>, Form(input): Form<LoginInput>) -> Response {
+    if input.token != *state.token {
+        return Html(LOGIN_HTML.replace("{{error}}", &error_html(Some("Wrong token")))).into_response();
+    }
+
+    let cookie = format!(
+        "{}={}; Path=/; HttpOnly; SameSite=Strict",
+        TOKEN_COOKIE,
+        cookie_value(&state.token)
+    );
+    ([(header::SET_COOKIE, cookie)], Redirect::to("/")).into_response()
+}
+
+async fn web_add(State(state): State<AppState>, Form(input): Form<ReminderInput>) -> Response {
+    let new = match input.validate() {
+        Ok(new) => new,
+        Err((_, message)) => return render_index(&state, Some(&message)),
+    };
+
+    let added = state.db.lock().add_reminder(new);
+    match added {
+        Ok(_) => {
+            publish(&state.events, ReminderEvent::Changed);
+            Redirect::to("/").into_response()
+        }
+        Err(e) => render_index(&state, Some(&e.to_string())),
+    }
+}
+
+async fn web_delete(State(state): State<AppState>, Path(id): Path<i32>) -> Response {
+    let deleted = state.db.lock().delete_reminder(id);
+    match deleted {
+        Ok(()) => {
+            publish(&state.events, ReminderEvent::Changed);
+            Redirect::to("/").into_response()
+        }
+        Err(e) => render_index(&state, Some(&e.to_string())),
+    }
+}
+
+fn event_name(event: &ReminderEvent) -> &'static str {
+    match event {
+        ReminderEvent::Fired { .. } => "fired",
+        ReminderEvent::Changed => "changed",
+        ReminderEvent::ProfileSwitched { .. } => "profile_switched",
+        ReminderEvent::Missed { .. } => "missed",
+        ReminderEvent::Muted { .. } => "muted",
+        ReminderEvent::SnoozeAll => "snooze_all",
+    }
+}
+
+/// Server-sent events named after the event type, with the event as JSON data.
+async fn event_stream(State(state): State<AppState>) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let events = stream::unfold(state.events.subscribe(), |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(event) => {
+                    let sse = Event::default()
+                        .event(event_name(&event))
+                        .json_data(&event)
+                        .unwrap_or_default();
+                    return Some((Ok(sse), rx));
+                }
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+async fn list_reminders(State(state): State<AppState>) -> ApiResult<Json<Vec<Reminder>>> {
+    let db = state.db.lock();
+    db.get_all_reminders().map(Json).map_err(internal)
+}
+
+async fn get_reminder(State(state): State<AppState>, Path(id): Path<i32>) -> ApiResult<Json<Reminder>> {
+    let db = state.db.lock();
+    match db.get_reminder(id).map_err(internal)? {
+        Some(reminder) => Ok(Json(reminder)),
+        None => Err((StatusCode::NOT_FOUND, format!("no reminder with id {}", id))),
+    }
+}
+
+async fn create_reminder(
+    State(state): State<AppState>,
+    Json(input): Json<ReminderInput>,
+) -> ApiResult<(StatusCode, Json<Reminder>)> {
+    let new = input.validate()?;
+    let db = state.db.lock();
+    let reminder = db.add_reminder(new).map_err(internal)?;
+    publish(&state.events, ReminderEvent::Changed);
+    Ok((StatusCode::CREATED, Json(reminder)))
+}
+
+async fn update_reminder(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Json(input): Json<ReminderInput>,
+) -> ApiResult<Json<Reminder>> {
+    let new = input.validate()?;
+    let db = state.db.lock();
+    let Some(mut reminder) = db.get_reminder(id).map_err(internal)? else {
+        return Err((StatusCode::NOT_FOUND, format!("no reminder with id {}", id)));
+    };
+    reminder.apply(new.with_settings_of(&reminder));
+    db.update_reminder(&reminder).map_err(internal)?;
+    publish(&state.events, ReminderEvent::Changed);
+    Ok(Json(reminder))
+}
+
+async fn delete_reminder(State(state): State<AppState>, Path(id): Path<i32>) -> ApiResult<StatusCode> {
+    let db = state.db.lock();
+    if db.get_reminder(id).map_err(internal)?.is_none() {
+        return Err((StatusCode::NOT_FOUND, format!("no reminder with id {}", id)));
+    }
+    db.delete_reminder(id).map_err(internal)?;
+    publish(&state.events, ReminderEvent::Changed);
+    Ok(StatusCode::NO_CONTENT)
+}
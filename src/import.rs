@@ -0,0 +1,212 @@
+use crate::cli::OnDuplicate;
+use crate::config::Config;
+use crate::db::{NewReminder, Reminder};
+use crate::{open_store, schedule, validate_date_format, validate_time_format};
+use reminder::ReminderStore;
+use serde::Deserialize;
+use std::{
+    error::Error,
+    fs,
+    io::{self, BufRead, IsTerminal, Write},
+    path::Path,
+};
+
+/// What `reminder import` reads: a list of reminders, as served by the HTTP
+/// API, or a whole file from the JSON backend.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ImportFile {
+    List(Vec<NewReminder>),
+    Store { reminders: Vec<NewReminder> },
+}
+
+/// Reads reminders from a JSON file, checking each one before anything is added.
+fn parse(text: &str) -> Result<Vec<NewReminder>, String> {
+    let reminders = match serde_json::from_str(text).map_err(|e| e.to_string())? {
+        ImportFile::List(reminders) | ImportFile::Store { reminders } => reminders,
+    };
+    for (i, reminder) in reminders.iter().enumerate() {
+        let problem = if reminder.title.trim().is_empty() {
+            Some("missing title".to_string())
+        } else if let Some(Err(e)) = reminder.cron.as_deref().map(schedule::parse_cron) {
+            Some(format!("invalid cron expression: {}", e))
+        } else if reminder.cron.is_none() && !validate_time_format(&reminder.time) {
+            Some(format!("invalid time {:?}", reminder.time))
+        } else if reminder.date.as_deref().is_some_and(|date| !validate_date_format(date)) {
+            Some(format!("invalid date {:?}", reminder.date.as_deref().unwrap_or_default()))
+        } else if let Some(Err(e)) = reminder.weekdays.as_deref().map(schedule::parse_weekdays) {
+            Some(format!("invalid weekdays: {}", e))
+        } else {
+            None
+        };
+        if let Some(problem) = problem {
+            return Err(format!("reminder {} ({:?}): {}", i + 1, reminder.title, problem));
+        }
+    }
+    Ok(reminders)
+}
+
+/// How many reminders were added, replaced and skipped.
+#[derive(Debug, Default, PartialEq)]
+struct Outcome {
+    added: usize,
+    replaced: usize,
+    skipped: usize,
+}
+
+/// Adds `reminders` in one batch, asking `choose` about each that duplicates
+/// one already in `store`. `choose` answers with what to do and whether to do
+/// the same for the rest.
+fn add_all(
+    store: &dyn ReminderStore,
+    reminders: Vec<NewReminder>,
+    mut choose: impl FnMut(&NewReminder, &Reminder) -> io::Result<(OnDuplicate, bool)>,
+) -> Result<Outcome, Box<dyn Error>> {
+    let existing: Vec<Reminder> =
+        store.reminders()?.into_iter().filter(|r| r.deleted_at.is_none()).collect();
+    let mut for_all = None;
+    let mut outcome = Outcome::default();
+    let mut to_add = Vec::new();
+    for new in reminders {
+        let Some(duplicate) = existing.iter().find(|r| new.duplicates(r)) else {
+            to_add.push(new);
+            continue;
+        };
+        let choice = match for_all {
+            Some(choice) => choice,
+            None => {
+                let (choice, all) = choose(&new, duplicate)?;
+                if all {
+                    for_all = Some(choice);
+                }
+                choice
+            }
+        };
+        match choice {
+            OnDuplicate::Skip | OnDuplicate::Ask => outcome.skipped += 1,
+            // A second copy needs an identity of its own.
+            OnDuplicate::Keep => to_add.push(NewReminder { uuid: None, ..new }),
+            OnDuplicate::Replace => {
+                let mut replaced = duplicate.clone();
+                replaced.apply(new);
+                store.update_reminder(&replaced)?;
+                outcome.replaced += 1;
+            }
+        }
+    }
+    outcome.added = store.add_reminders_batch(to_add)?.len();
+    Ok(outcome)
+}
+
+/// Asks on the terminal what to do with a duplicate; a capital letter
+/// answers for the rest too.
+fn ask(new: &NewReminder, existing: &Reminder) -> io::Result<(OnDuplicate, bool)> {
+    let when = new.cron.as_deref().unwrap_or(&new.time);
+    let mut line = String::new();
+    loop {
+        print!(
+            "{:?} at {} is already reminder {}. [s]kip, [r]eplace, [k]eep both (capital for all the rest): ",
+            new.title, when, existing.id
+        );
+        io::stdout().flush()?;
+        line.clear();
+        if io::stdin().lock().read_line(&mut line)? == 0 {
+            return Ok((OnDuplicate::Skip, true));
+        }
+        let answer = line.trim();
+        let choice = match answer.to_lowercase().as_str() {
+            "s" => OnDuplicate::Skip,
+            "r" => OnDuplicate::Replace,
+            "k" => OnDuplicate::Keep,
+            _ => continue,
+        };
+        return Ok((choice, answer.chars().all(char::is_uppercase)));
+    }
+}
+
+/// Adds every reminder in the file at `path`, handling ones already here as
+/// `duplicates` says.
+pub fn run(config: &Config, path: &Path, duplicates: OnDuplicate) -> Result<(), Box<dyn Error>> {
+    let reminders = parse(&fs::read_to_string(path)?).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let duplicates = match duplicates {
+        OnDuplicate::Ask if !io::stdin().is_terminal() => OnDuplicate::Skip,
+        duplicates => duplicates,
+    };
+    let store = open_store(config)?;
+    let outcome = add_all(&*store, reminders, |new, existing| match duplicates {
+        OnDuplicate::Ask => ask(new, existing),
+        duplicates => Ok((duplicates, true)),
+    })?;
+    println!(
+        "Imported {} reminder(s), replaced {}, skipped {} duplicate(s)",
+        outcome.added, outcome.replaced, outcome.skipped
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Priority;
+
+    #[test]
+    fn reads_lists_and_json_store_files() {
+        let list = parse(r#"[{"title": "Tea", "time": "16:00", "alerts": [5, 0], "id": 7}]"#).unwrap();
+        assert_eq!(list[0].alerts, vec![5, 0]);
+
+        let store = parse(r#"{"next_id": 1, "reminders": [{"title": "Standup", "cron": "0 9 * * MON-FRI"}]}"#).unwrap();
+        assert_eq!(store[0].cron.as_deref(), Some("0 9 * * MON-FRI"));
+    }
+
+    #[test]
+    fn asks_about_duplicates_until_told_to_do_the_same_for_the_rest() {
+        let store = reminder::MemoryStore::new();
+        let file = r#"[{"title": "Tea", "time": "16:00"}, {"title": "Nap", "time": "14:00"}]"#;
+        store.add_reminders_batch(parse(file).unwrap()).unwrap();
+
+        let again = r#"[{"title": "tea", "time": "16:00", "description": "Green"}, {"title": "Nap", "time": "14:00"},
+            {"title": "Walk", "time": "18:00"}]"#;
+        let mut asked = 0;
+        let outcome = add_all(&store, parse(again).unwrap(), |_, _| {
+            asked += 1;
+            Ok((OnDuplicate::Replace, true))
+        })
+        .unwrap();
+        assert_eq!(asked, 1);
+        assert_eq!(outcome, Outcome { added: 1, replaced: 2, skipped: 0 });
+        let reminders = store.get_all_reminders().unwrap();
+        assert_eq!(reminders.len(), 3);
+        assert!(reminders.iter().any(|r| r.title == "tea" && r.description == "Green"));
+    }
+
+    #[test]
+    fn keeps_notification_settings_and_priority_through_an_export() {
+        let exported = reminder::MemoryStore::new();
+        let mut tea = exported
+            .add_reminder(NewReminder { title: "Tea".to_string(), time: "16:00".to_string(), ..Default::default() })
+            .unwrap();
+        tea.icon = Some("dialog-warning".to_string());
+        tea.category = Some("x-tea".to_string());
+        tea.resident = true;
+        tea.priority = Priority::High;
+        let file = serde_json::to_string(&[&tea]).unwrap();
+
+        let stores: [Box<dyn ReminderStore>; 2] =
+            [Box::new(reminder::Database::new(":memory:").unwrap()), Box::new(reminder::MemoryStore::new())];
+        for store in stores {
+            add_all(&*store, parse(&file).unwrap(), |_, _| Ok((OnDuplicate::Skip, true))).unwrap();
+            let imported = &store.get_all_reminders().unwrap()[0];
+            assert_eq!(imported.icon.as_deref(), Some("dialog-warning"));
+            assert_eq!(imported.category.as_deref(), Some("x-tea"));
+            assert!(imported.resident);
+            assert_eq!(imported.priority, Priority::High);
+            assert_eq!(store.get_reminder(imported.id).unwrap().as_ref(), Some(imported));
+        }
+    }
+
+    #[test]
+    fn rejects_the_whole_file_over_one_bad_reminder() {
+        let err = parse(r#"[{"title": "Tea", "time": "16:00"}, {"title": "Nap", "time": "25:00"}]"#).unwrap_err();
+        assert!(err.starts_with("reminder 2 (\"Nap\")"));
+    }
+}
@@ -0,0 +1,141 @@
+//! A line-based control socket so other tools can talk to a running instance.
+//!
+//! Each connection sends one command and reads the reply until EOF:
+//!
+//! ```text
+//! add 09:30 Standup | Daily sync with the team
+//! list
+//! snooze 3 10
+//! ```
+
+use crate::config::MAX_SNOOZE_MINUTES;
+use crate::db::NewReminder;
+use reminder::{ReminderStore, SharedStore};
+use crate::events::{publish, EventBus, ReminderEvent};
+use crate::validate_time_format;
+use chrono::{Duration, Local};
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    os::unix::net::{UnixListener, UnixStream},
+    path::PathBuf,
+    sync::Arc,
+};
+
+pub fn socket_path() -> PathBuf {
+    dirs::runtime_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("reminder.sock")
+}
+
+/// Starts serving the control socket on a background thread, publishing
+/// `Changed` whenever a command modifies reminders. Returns `false` without
+/// starting if another instance is already listening.
+pub fn spawn_server(db: Arc<SharedStore>, events: EventBus) -> io::Result<bool> {
+    let path = socket_path();
+    if path.exists() {
+        if UnixStream::connect(&path).is_ok() {
+            return Ok(false);
+        }
+        // Left behind by an instance that didn't shut down cleanly.
+        std::fs::remove_file(&path)?;
+    }
+
+    let listener = UnixListener::bind(&path)?;
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let _ = handle_client(stream, &*db, &events);
+        }
+    });
+    Ok(true)
+}
+
+fn handle_client(stream: UnixStream, db: &dyn ReminderStore, events: &EventBus) -> io::Result<()> {
+    let mut line = String::new();
+    BufReader::new(&stream).read_line(&mut line)?;
+
+    let reply = execute(db, line.trim(), events);
+    writeln!(&stream, "{}", reply)
+}
+
+fn execute(db: &dyn ReminderStore, command: &str, events: &EventBus) -> String {
+    let (name, args) = command.split_once(' ').unwrap_or((command, ""));
+    match name {
+        "add" => {
+            let (time, rest) = args.trim().split_once(' ').unwrap_or((args.trim(), ""));
+            let (title, description) = rest.split_once('|').unwrap_or((rest, ""));
+            let (title, description) = (title.trim(), description.trim());
+
+            if !validate_time_format(time) || title.is_empty() {
+                return "error usage: add HH:MM[:SS] <title> [| <description>]".to_string();
+            }
+            let new = NewReminder {
+                title: title.to_string(),
+                description: description.to_string(),
+                time: time.to_string(),
+                alerts: vec![0],
+                ..Default::default()
+            };
+            match db.add_reminder(new) {
+                Ok(reminder) => {
+                    publish(events, ReminderEvent::Changed);
+                    format!("ok {}", reminder.id)
+                }
+                Err(e) => format!("error {}", e),
+            }
+        }
+        "list" => match db.get_all_reminders() {
+            Ok(reminders) => reminders
+                .iter()
+                .map(|r| format!("{}\t{}\t{}", r.id, r.time, r.title))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            Err(e) => format!("error {}", e),
+        },
+        "snooze" => {
+            let mut parts = args.split_whitespace();
+            let id = parts.next().and_then(|id| id.parse::<i32>().ok());
+            let minutes = parts.next().map_or(Some(10), |m| m.parse::<i64>().ok());
+            let until = minutes
+                .filter(|minutes| (1..=MAX_SNOOZE_MINUTES).contains(minutes))
+                .and_then(Duration::try_minutes)
+                .and_then(|minutes| Local::now().checked_add_signed(minutes));
+            let (Some(id), Some(until)) = (id, until) else {
+                return format!("error usage: snooze <id> [minutes, 1 to {}]", MAX_SNOOZE_MINUTES);
+            };
+
+            match db.get_reminder(id) {
+                Ok(Some(_)) => {
+                    match db.snooze_reminder(id, &until.format("%Y-%m-%d %H:%M").to_string()) {
+                        Ok(()) => {
+                            publish(events, ReminderEvent::Changed);
+                            format!("ok snoozed until {}", until.format("%H:%M"))
+                        }
+                        Err(e) => format!("error {}", e),
+                    }
+                }
+                Ok(None) => format!("error no reminder with id {}", id),
+                Err(e) => format!("error {}", e),
+            }
+        }
+        _ => "error unknown command (expected add, list or snooze)".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reminder::MemoryStore;
+
+    #[test]
+    fn snoozes_only_for_a_sensible_number_of_minutes() {
+        let store = MemoryStore::new();
+        let events = crate::events::new_bus();
+        assert!(execute(&store, "add 16:00 Tea", &events).starts_with("ok "));
+
+        assert!(execute(&store, "snooze 1 10", &events).starts_with("ok snoozed until"));
+        for minutes in ["0", "-5", "99999999999999", "ten"] {
+            let reply = execute(&store, &format!("snooze 1 {}", minutes), &events);
+            assert!(reply.starts_with("error usage"), "{}: {}", minutes, reply);
+        }
+    }
+}
@@ -0,0 +1,305 @@
+//! Reminders kept in a readable JSON file that can be edited by hand or
+//! committed to git.
+
+use crate::db::{new_uuid, timestamp, LoggedNotification, NewReminder, Reminder, Vacation};
+use crate::store::{self, ReminderStore};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Reminders stored in a JSON file.
+///
+/// The file is read before and rewritten after every change, so the TUI and a
+/// daemon can share it. Writes go to a temporary file that is then renamed
+/// over the old one, so a crash never leaves it half-written.
+pub struct JsonStore {
+    path: PathBuf,
+    // Serializes read-modify-write cycles within this process.
+    lock: Mutex<()>,
+}
+
+/// What the file holds.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+struct Contents {
+    next_id: i32,
+    reminders: Vec<Reminder>,
+    /// Pending snoozes, by reminder id.
+    snoozes: BTreeMap<i32, String>,
+    /// Alerts already delivered, as (reminder id, offset, occurrence).
+    notified: Vec<(i32, i64, String)>,
+    /// Every notification shown or tried, oldest first.
+    notification_log: Vec<LoggedNotification>,
+    vacations: Vec<Vacation>,
+}
+
+impl JsonStore {
+    /// Uses the file at `path`, which is created on the first change.
+    pub fn open(path: impl Into<PathBuf>) -> store::Result<Self> {
+        let store = JsonStore { path: path.into(), lock: Mutex::new(()) };
+        // Fail now rather than on the first change if the file is unreadable.
+        store.load()?;
+        Ok(store)
+    }
+
+    fn load(&self) -> io::Result<Contents> {
+        match fs::read_to_string(&self.path) {
+            Ok(text) => {
+                let mut contents: Contents = serde_json::from_str(&text).map_err(|e| {
+                    io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", self.path.display(), e))
+                })?;
+                // Reminders from older files or added by hand get a UUID,
+                // written straight back so it doesn't change between reads.
+                let mut missing = contents.reminders.iter_mut().filter(|r| r.uuid.is_empty()).peekable();
+                if missing.peek().is_some() {
+                    missing.for_each(|reminder| reminder.uuid = new_uuid());
+                    self.save(&contents)?;
+                }
+                Ok(contents)
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Contents::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn save(&self, contents: &Contents) -> io::Result<()> {
+        let mut text = serde_json::to_string_pretty(contents)?;
+        text.push('\n');
+        write_atomically(&self.path, text.as_bytes())
+    }
+
+    /// Where the last check is kept: beside the file rather than in it, so a
+    /// running worker doesn't rewrite the reminders every minute.
+    fn check_path(&self) -> PathBuf {
+        let mut path = self.path.as_os_str().to_owned();
+        path.push(".last-check");
+        PathBuf::from(path)
+    }
+
+    /// Loads the file, applies `change` and writes it back if anything changed.
+    fn update<T>(&self, change: impl FnOnce(&mut Contents) -> T) -> store::Result<T> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let before = self.load()?;
+        let mut contents = before.clone();
+        let result = change(&mut contents);
+        if contents != before {
+            self.save(&contents)?;
+        }
+        Ok(result)
+    }
+}
+
+impl Contents {
+    fn add(&mut self, new: NewReminder) -> Reminder {
+        self.next_id += 1;
+        let reminder = Reminder {
+            id: self.next_id,
+            uuid: new.uuid.unwrap_or_else(new_uuid),
+            title: new.title,
+            description: new.description,
+            time: new.time,
+            created_at: Local::now().to_rfc3339(),
+            updated_at: timestamp(),
+            alerts: new.alerts,
+            date: new.date,
+            tags: new.tags,
+            cron: new.cron,
+            interval_minutes: new.interval_minutes,
+            until: new.until,
+            weekdays: new.weekdays,
+            last_fired_at: None,
+            timezone: new.timezone,
+            completed_at: None,
+            deleted_at: None,
+            archived: false,
+            delete_after_firing: new.delete_after_firing,
+            skip_holidays: new.skip_holidays,
+            exceptions: Vec::new(),
+            icon: new.icon,
+            category: new.category,
+            resident: new.resident,
+            priority: new.priority,
+        };
+        self.reminders.push(reminder.clone());
+        reminder
+    }
+}
+
+/// Writes `data` next to `path` and renames it into place.
+fn write_atomically(path: &Path, data: &[u8]) -> io::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+
+    let mut file = fs::File::create(&tmp)?;
+    file.write_all(data)?;
+    file.sync_all()?;
+    fs::rename(&tmp, path)
+}
+
+impl ReminderStore for JsonStore {
+    fn add_reminder(&self, new: NewReminder) -> store::Result<Reminder> {
+        self.update(|contents| contents.add(new))
+    }
+
+    /// Rewrites the file once for the whole batch.
+    fn add_reminders_batch(&self, new: Vec<NewReminder>) -> store::Result<Vec<Reminder>> {
+        self.update(|contents| new.into_iter().map(|new| contents.add(new)).collect())
+    }
+
+    fn reminders(&self) -> store::Result<Vec<Reminder>> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut reminders = self.load()?.reminders;
+        reminders.sort_by_key(|r| r.id);
+        Ok(reminders)
+    }
+
+    fn restore_reminder(&self, reminder: &Reminder) -> store::Result<()> {
+        self.update(|contents| {
+            contents.next_id = contents.next_id.max(reminder.id);
+            match contents.reminders.iter_mut().find(|r| r.id == reminder.id) {
+                Some(stored) => *stored = reminder.clone(),
+                None => {
+                    contents.reminders.push(reminder.clone());
+                    contents.reminders.sort_by_key(|r| r.id);
+                }
+            }
+        })
+    }
+
+    fn purge_reminder(&self, id: i32) -> store::Result<()> {
+        self.update(|contents| {
+            contents.reminders.retain(|r| r.id != id);
+            contents.snoozes.remove(&id);
+            contents.notified.retain(|(reminder_id, _, _)| *reminder_id != id);
+        })
+    }
+
+    fn snooze_reminder(&self, id: i32, until: &str) -> store::Result<()> {
+        self.update(|contents| {
+            contents.snoozes.insert(id, until.to_string());
+        })
+    }
+
+    fn unsnooze_reminder(&self, id: i32) -> store::Result<()> {
+        self.update(|contents| {
+            contents.snoozes.remove(&id);
+        })
+    }
+
+    fn claim_due_snoozes(&self, now: &str) -> store::Result<Vec<i32>> {
+        self.update(|contents| {
+            let due: Vec<i32> =
+                contents.snoozes.iter().filter(|(_, until)| until.as_str() <= now).map(|(id, _)| *id).collect();
+            contents.snoozes.retain(|id, _| !due.contains(id));
+            due
+        })
+    }
+
+    fn next_snooze(&self) -> store::Result<Option<String>> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(self.load()?.snoozes.into_values().min())
+    }
+
+    fn claim_notification(&self, reminder_id: i32, offset_minutes: i64, occurrence: &str) -> store::Result<bool> {
+        self.update(|contents| {
+            // Claims from before they were per occurrence only name the day.
+            contents.notified.retain(|(_, _, claimed)| claimed.len() > "YYYY-MM-DD".len());
+            let claim = (reminder_id, offset_minutes, occurrence.to_string());
+            if contents.notified.contains(&claim) {
+                return false;
+            }
+            contents.notified.push(claim);
+            true
+        })
+    }
+
+    fn release_notification(&self, reminder_id: i32, offset_minutes: i64, occurrence: &str) -> store::Result<()> {
+        self.update(|contents| {
+            contents
+                .notified
+                .retain(|claim| *claim != (reminder_id, offset_minutes, occurrence.to_string()));
+        })
+    }
+
+    fn log_notification(&self, notification: &LoggedNotification) -> store::Result<()> {
+        self.update(|contents| contents.notification_log.push(notification.clone()))
+    }
+
+    fn notification_log(&self, limit: usize) -> store::Result<Vec<LoggedNotification>> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(self.load()?.notification_log.into_iter().rev().take(limit).collect())
+    }
+
+    fn last_check(&self) -> store::Result<Option<String>> {
+        match fs::read_to_string(self.check_path()) {
+            Ok(text) => Ok(Some(text.trim().to_string())),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn record_check(&self, at: &str) -> store::Result<()> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(write_atomically(&self.check_path(), format!("{}\n", at).as_bytes())?)
+    }
+
+    fn add_vacation(&self, start: &str, end: &str, tags: &[String]) -> store::Result<Vacation> {
+        self.update(|contents| {
+            let vacation = Vacation {
+                id: contents.vacations.iter().map(|v| v.id).max().unwrap_or(0) + 1,
+                start: start.to_string(),
+                end: end.to_string(),
+                tags: tags.to_vec(),
+            };
+            contents.vacations.push(vacation.clone());
+            vacation
+        })
+    }
+
+    fn vacations(&self) -> store::Result<Vec<Vacation>> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut vacations = self.load()?.vacations;
+        vacations.sort_by(|a, b| (&a.start, a.id).cmp(&(&b.start, b.id)));
+        Ok(vacations)
+    }
+
+    fn delete_vacation(&self, id: i32) -> store::Result<()> {
+        self.update(|contents| contents.vacations.retain(|v| v.id != id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_reminders_across_opens() {
+        let path = std::env::temp_dir().join(format!("reminder-json-test-{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let store = JsonStore::open(&path).unwrap();
+        let tea = store
+            .add_reminder(NewReminder {
+                title: "Tea".to_string(),
+                time: "16:00".to_string(),
+                alerts: vec![0],
+                tags: vec!["home".to_string()],
+                ..Default::default()
+            })
+            .unwrap();
+        store.snooze_reminder(tea.id, "2026-10-15 16:10").unwrap();
+        assert!(store.claim_notification(tea.id, 0, "2026-10-15 16:00").unwrap());
+
+        let reopened = JsonStore::open(&path).unwrap();
+        assert_eq!(reopened.get_all_reminders().unwrap(), vec![tea.clone()]);
+        assert!(!reopened.claim_notification(tea.id, 0, "2026-10-15 16:00").unwrap());
+        assert_eq!(reopened.claim_due_snoozes("2026-10-15 16:10").unwrap(), vec![tea.id]);
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,631 @@
+//! What each key does in each mode. The key handlers look keys up here and
+//! the `?` help is drawn from the same tables, so the two can't disagree.
+
+use crate::config::KeyPreset;
+use crate::ui::QuickFilter;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::sync::LazyLock;
+
+/// What a key asks for; the mode decides what that means, e.g. `Up` moves
+/// the selection in the list but steps the time in the form.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    PageUp,
+    PageDown,
+    First,
+    Last,
+    NextDue,
+    NextField,
+    PrevField,
+    Confirm,
+    Back,
+    DeleteBack,
+    DeleteForward,
+    Today,
+    /// A numbered choice, counting from 0.
+    Pick(usize),
+    Quit,
+    Add,
+    QuickAdd,
+    Edit,
+    Copy,
+    Delete,
+    Done,
+    Mark,
+    Undo,
+    Redo,
+    Restore,
+    Skip,
+    Occurrence,
+    NotifySettings,
+    RaisePriority,
+    LowerPriority,
+    View(usize),
+    NextView,
+    PrevView,
+    Sort,
+    ReverseSort,
+    Calendar,
+    Week,
+    Filter(QuickFilter),
+    Tags,
+    Lock,
+    Mute,
+    Snooze,
+    SnoozeDue,
+    Log,
+    History,
+    Profiles,
+    Search,
+    Help,
+    Save,
+    CompleteNext,
+    CompletePrev,
+    Editor,
+    Replace,
+    KeepBoth,
+}
+
+/// A key, with Ctrl held or not, or two keys one after the other like vim's
+/// `gg`. Shift is left out: it only changes which character arrives.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Key {
+    pub code: KeyCode,
+    pub ctrl: bool,
+    pub then: Option<KeyCode>,
+}
+
+impl Key {
+    /// A key press as the tables write it.
+    pub fn of(key: &KeyEvent) -> Key {
+        Key { code: key.code, ctrl: key.modifiers.contains(KeyModifiers::CONTROL), then: None }
+    }
+
+    fn starts(&self, key: &Key) -> bool {
+        self.code == key.code && self.ctrl == key.ctrl
+    }
+
+    /// How the help writes it.
+    pub fn name(&self) -> String {
+        let name = match self.code {
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char(c) if self.ctrl => c.to_ascii_uppercase().to_string(),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::Up => "↑".to_string(),
+            KeyCode::Down => "↓".to_string(),
+            KeyCode::Left => "←".to_string(),
+            KeyCode::Right => "→".to_string(),
+            KeyCode::PageUp => "PgUp".to_string(),
+            KeyCode::PageDown => "PgDn".to_string(),
+            KeyCode::Home => "Home".to_string(),
+            KeyCode::End => "End".to_string(),
+            KeyCode::Enter => "Enter".to_string(),
+            KeyCode::Esc => "Esc".to_string(),
+            KeyCode::Tab => "Tab".to_string(),
+            KeyCode::BackTab => "Shift+Tab".to_string(),
+            KeyCode::Backspace => "Backspace".to_string(),
+            KeyCode::Delete => "Del".to_string(),
+            other => format!("{:?}", other),
+        };
+        let name = if self.ctrl { format!("Ctrl+{}", name) } else { name };
+        match self.then {
+            Some(then) => name + &Key { code: then, ctrl: false, then: None }.name(),
+            None => name,
+        }
+    }
+}
+
+/// What a key press comes to, given the one before it.
+#[derive(Debug, PartialEq)]
+pub enum Resolved {
+    Action(Action),
+    /// The first key of a sequence, waiting for the next.
+    Pending(Key),
+    Nothing,
+}
+
+#[derive(Clone, Copy)]
+pub struct Binding {
+    pub keys: &'static [Key],
+    pub action: Action,
+    pub help: &'static str,
+}
+
+/// The keys of one mode.
+pub struct Keymap {
+    pub title: &'static str,
+    /// Whether keys without a binding type text.
+    pub typing: bool,
+    pub bindings: &'static [Binding],
+}
+
+impl Keymap {
+    /// What `key` does here, if anything.
+    pub fn action(&self, key: &KeyEvent) -> Option<Action> {
+        match self.resolve(None, key) {
+            Resolved::Action(action) => Some(action),
+            _ => None,
+        }
+    }
+
+    /// What `key` does after `pending`, the start of a sequence if it was
+    /// one. A sequence that doesn't go on as any binding does does nothing,
+    /// as in vim.
+    pub fn resolve(&self, pending: Option<Key>, key: &KeyEvent) -> Resolved {
+        let pressed = Key::of(key);
+        let keys = || self.bindings.iter().flat_map(|b| b.keys.iter().map(move |k| (k, b.action)));
+        if let Some(first) = pending {
+            return keys()
+                .find(|(k, _)| k.starts(&first) && k.then == Some(pressed.code))
+                .map_or(Resolved::Nothing, |(_, action)| Resolved::Action(action));
+        }
+        if keys().any(|(k, _)| k.then.is_some() && k.starts(&pressed)) {
+            return Resolved::Pending(pressed);
+        }
+        keys()
+            .find(|(k, _)| k.then.is_none() && k.starts(&pressed))
+            .map_or(Resolved::Nothing, |(_, action)| Resolved::Action(action))
+    }
+
+    /// The bindings as the help lists them: keys and what they do, with
+    /// neighbours that share a description on one line, e.g. `←/→` or `1-6`.
+    pub fn help_lines(&self) -> Vec<(String, &'static str)> {
+        let mut lines: Vec<(Vec<Key>, &'static str)> = Vec::new();
+        for binding in self.bindings {
+            match lines.last_mut() {
+                Some((keys, help)) if *help == binding.help => keys.extend_from_slice(binding.keys),
+                _ => lines.push((binding.keys.to_vec(), binding.help)),
+            }
+        }
+        lines.into_iter().map(|(keys, help)| (keys_text(&keys), help)).collect()
+    }
+}
+
+/// `1-9` for a run of digits, otherwise the names joined with `/`.
+fn keys_text(keys: &[Key]) -> String {
+    let digits: Option<Vec<u32>> = keys
+        .iter()
+        .map(|k| match k.code {
+            KeyCode::Char(c) if !k.ctrl => c.to_digit(10),
+            _ => None,
+        })
+        .collect();
+    match digits {
+        Some(digits) if digits.len() > 2 && digits.windows(2).all(|w| w[1] == w[0] + 1) => {
+            format!("{}-{}", digits[0], digits[digits.len() - 1])
+        }
+        _ => keys.iter().map(Key::name).collect::<Vec<_>>().join("/"),
+    }
+}
+
+const fn ch(c: char) -> Key {
+    Key { code: KeyCode::Char(c), ctrl: false, then: None }
+}
+
+const fn ctrl(c: char) -> Key {
+    Key { code: KeyCode::Char(c), ctrl: true, then: None }
+}
+
+const fn code(code: KeyCode) -> Key {
+    Key { code, ctrl: false, then: None }
+}
+
+const fn seq(first: char, then: char) -> Key {
+    Key { code: KeyCode::Char(first), ctrl: false, then: Some(KeyCode::Char(then)) }
+}
+
+const fn bind(keys: &'static [Key], action: Action, help: &'static str) -> Binding {
+    Binding { keys, action, help }
+}
+
+const UP: Key = code(KeyCode::Up);
+const DOWN: Key = code(KeyCode::Down);
+const LEFT: Key = code(KeyCode::Left);
+const RIGHT: Key = code(KeyCode::Right);
+const PAGE_UP: Key = code(KeyCode::PageUp);
+const PAGE_DOWN: Key = code(KeyCode::PageDown);
+const HOME: Key = code(KeyCode::Home);
+const END: Key = code(KeyCode::End);
+const ENTER: Key = code(KeyCode::Enter);
+const ESC: Key = code(KeyCode::Esc);
+const TAB: Key = code(KeyCode::Tab);
+const BACK_TAB: Key = code(KeyCode::BackTab);
+const BACKSPACE: Key = code(KeyCode::Backspace);
+const DELETE: Key = code(KeyCode::Delete);
+
+pub static LIST: Keymap = Keymap {
+    title: "List",
+    typing: false,
+    bindings: &[
+        bind(&[UP], Action::Up, "Move up"),
+        bind(&[DOWN], Action::Down, "Move down"),
+        bind(&[PAGE_UP], Action::PageUp, "Move a screen up"),
+        bind(&[PAGE_DOWN], Action::PageDown, "Move a screen down"),
+        bind(&[HOME], Action::First, "Jump to the first reminder"),
+        bind(&[END], Action::Last, "Jump to the last reminder"),
+        bind(&[ch('f')], Action::NextDue, "Jump to the reminder that goes off next"),
+        bind(&[ch('1')], Action::View(0), "Today, Upcoming, All, Done, Archive or Trash"),
+        bind(&[ch('2')], Action::View(1), "Today, Upcoming, All, Done, Archive or Trash"),
+        bind(&[ch('3')], Action::View(2), "Today, Upcoming, All, Done, Archive or Trash"),
+        bind(&[ch('4')], Action::View(3), "Today, Upcoming, All, Done, Archive or Trash"),
+        bind(&[ch('5')], Action::View(4), "Today, Upcoming, All, Done, Archive or Trash"),
+        bind(&[ch('6')], Action::View(5), "Today, Upcoming, All, Done, Archive or Trash"),
+        bind(&[TAB], Action::NextView, "Next view"),
+        bind(&[BACK_TAB], Action::PrevView, "Previous view"),
+        bind(&[ch('a')], Action::Add, "Add a reminder"),
+        bind(&[ch('n')], Action::QuickAdd, "Add one from a line like \"Call mom tomorrow 6pm\""),
+        bind(&[ch('e')], Action::Edit, "Edit the selected reminder"),
+        bind(&[ch('c')], Action::Copy, "Copy the selected reminder into a new one"),
+        bind(&[ch('d')], Action::Delete, "Delete the selected or marked reminders"),
+        bind(&[ch('x')], Action::Done, "Mark done, or reopen"),
+        bind(&[ch(' ')], Action::Mark, "Mark or unmark, for x, d, z, + and -"),
+        bind(&[ch('u')], Action::Undo, "Undo"),
+        bind(&[ctrl('r')], Action::Redo, "Redo"),
+        bind(&[ch('r')], Action::Restore, "Restore from the trash"),
+        bind(&[ch('S')], Action::Skip, "Skip the next occurrence"),
+        bind(&[ch('E')], Action::Occurrence, "Move, skip or add a note to the next occurrence"),
+        bind(&[ch('N')], Action::NotifySettings, "Change how the reminder notifies"),
+        bind(&[ch('+')], Action::RaisePriority, "Raise the priority"),
+        bind(&[ch('-')], Action::LowerPriority, "Lower the priority"),
+        bind(&[ch('z')], Action::Snooze, "Snooze for a preset"),
+        bind(&[ch('Z')], Action::SnoozeDue, "Snooze everything that just went off"),
+        bind(&[ch('o')], Action::Sort, "Sort by time, title, when added or priority"),
+        bind(&[ch('O')], Action::ReverseSort, "Reverse the sort"),
+        bind(&[ch('/')], Action::Search, "Search"),
+        bind(&[ch('t')], Action::Tags, "Show only one tag"),
+        bind(&[ch('H')], Action::Filter(QuickFilter::NextHour), "Show only what's due in the next hour"),
+        bind(&[ch('!')], Action::Filter(QuickFilter::Overdue), "Show only what's overdue"),
+        bind(&[ch('T')], Action::Filter(QuickFilter::Today), "Show only what's due today"),
+        bind(&[ch('C')], Action::Calendar, "Open the calendar"),
+        bind(&[ch('w')], Action::Week, "Open the week agenda"),
+        bind(&[ch('h')], Action::History, "Show the notification history"),
+        bind(&[ch('l')], Action::Log, "Show the log"),
+        bind(&[ch('P')], Action::Profiles, "Switch profile"),
+        bind(&[ch('m')], Action::Mute, "Mute or unmute notifications"),
+        bind(&[ch('L')], Action::Lock, "Lock the screen"),
+        bind(&[ch('?')], Action::Help, "Show these keys"),
+        bind(&[ESC], Action::Back, "Clear the marks, or show everything again"),
+        bind(&[ch('q')], Action::Quit, "Quit"),
+    ],
+};
+
+/// What `keymap = "vim"` adds to the list; `/` already searches.
+const VIM: &[(Key, Action)] = &[
+    (ch('k'), Action::Up),
+    (ch('j'), Action::Down),
+    (seq('g', 'g'), Action::First),
+    (ch('G'), Action::Last),
+    (seq('d', 'd'), Action::Delete),
+];
+
+static VIM_LIST: LazyLock<Keymap> = LazyLock::new(|| Keymap {
+    title: "List (vim keys)",
+    typing: false,
+    bindings: layered(&LIST, VIM),
+});
+
+/// `base`'s bindings with `extra`'s keys added to those for the same
+/// action, leaving out any key of `base` that now starts a sequence.
+fn layered(base: &Keymap, extra: &[(Key, Action)]) -> &'static [Binding] {
+    let bindings: Vec<Binding> = base
+        .bindings
+        .iter()
+        .map(|binding| {
+            let mut keys: Vec<Key> = binding
+                .keys
+                .iter()
+                .filter(|key| !extra.iter().any(|(k, _)| k.then.is_some() && k.starts(key)))
+                .copied()
+                .collect();
+            keys.extend(extra.iter().filter(|(_, action)| *action == binding.action).map(|(k, _)| *k));
+            Binding { keys: keys.leak(), ..*binding }
+        })
+        .collect();
+    // Built once, for as long as the app runs.
+    bindings.leak()
+}
+
+/// The list's keys with `preset`'s.
+pub fn list(preset: KeyPreset) -> &'static Keymap {
+    match preset {
+        KeyPreset::Default => &LIST,
+        KeyPreset::Vim => &VIM_LIST,
+    }
+}
+
+pub static SEARCH: Keymap = Keymap {
+    title: "Search",
+    typing: true,
+    bindings: &[
+        bind(&[ENTER], Action::Confirm, "Keep the results"),
+        bind(&[ESC], Action::Back, "Cancel"),
+        bind(&[UP], Action::Up, "Move through the results"),
+        bind(&[DOWN], Action::Down, "Move through the results"),
+        bind(&[LEFT], Action::Left, "Move the cursor"),
+        bind(&[RIGHT], Action::Right, "Move the cursor"),
+        bind(&[HOME], Action::First, "Jump to the start or end"),
+        bind(&[END], Action::Last, "Jump to the start or end"),
+        bind(&[BACKSPACE], Action::DeleteBack, "Delete before or after the cursor"),
+        bind(&[DELETE], Action::DeleteForward, "Delete before or after the cursor"),
+    ],
+};
+
+pub static FORM: Keymap = Keymap {
+    title: "Add and edit",
+    typing: true,
+    bindings: &[
+        bind(&[TAB], Action::NextField, "Next field"),
+        bind(&[BACK_TAB], Action::PrevField, "Previous field"),
+        bind(&[UP], Action::Up, "Step the time up"),
+        bind(&[DOWN], Action::Down, "Step the time down, or pick the date from a calendar"),
+        bind(&[LEFT], Action::Left, "Move the cursor, or between the parts of the time"),
+        bind(&[RIGHT], Action::Right, "Move the cursor, or between the parts of the time"),
+        bind(&[HOME], Action::First, "Jump to the start or end"),
+        bind(&[END], Action::Last, "Jump to the start or end"),
+        bind(&[BACKSPACE], Action::DeleteBack, "Delete before or after the cursor"),
+        bind(&[DELETE], Action::DeleteForward, "Delete before or after the cursor"),
+        bind(&[ctrl('n')], Action::CompleteNext, "Cycle through earlier titles or tags"),
+        bind(&[ctrl('p')], Action::CompletePrev, "Cycle through earlier titles or tags"),
+        bind(&[ctrl('e')], Action::Editor, "Write the description in $EDITOR"),
+        bind(&[ctrl('s')], Action::Save, "Save"),
+        bind(&[ENTER], Action::Confirm, "Save, or start a new line in the description"),
+        bind(&[ESC], Action::Back, "Cancel, asking first if anything was changed"),
+    ],
+};
+
+pub static DATE_PICKER: Keymap = Keymap {
+    title: "Date picker",
+    typing: false,
+    bindings: &[
+        bind(&[LEFT], Action::Left, "A day back or on"),
+        bind(&[RIGHT], Action::Right, "A day back or on"),
+        bind(&[UP], Action::Up, "A week back or on"),
+        bind(&[DOWN], Action::Down, "A week back or on"),
+        bind(&[PAGE_UP], Action::PageUp, "A month back or on"),
+        bind(&[PAGE_DOWN], Action::PageDown, "A month back or on"),
+        bind(&[ch('t')], Action::Today, "Jump to today"),
+        bind(&[ENTER], Action::Confirm, "Pick the day"),
+        bind(&[ESC], Action::Back, "Close"),
+    ],
+};
+
+pub static QUICK_ADD: Keymap = Keymap {
+    title: "Quick add",
+    typing: true,
+    bindings: &[
+        bind(&[ENTER], Action::Confirm, "Add"),
+        bind(&[ESC], Action::Back, "Cancel"),
+        bind(&[LEFT], Action::Left, "Move the cursor"),
+        bind(&[RIGHT], Action::Right, "Move the cursor"),
+        bind(&[HOME], Action::First, "Jump to the start or end"),
+        bind(&[END], Action::Last, "Jump to the start or end"),
+        bind(&[BACKSPACE], Action::DeleteBack, "Delete before or after the cursor"),
+        bind(&[DELETE], Action::DeleteForward, "Delete before or after the cursor"),
+    ],
+};
+
+pub static DELETE_CONFIRM: Keymap = Keymap {
+    title: "Delete",
+    typing: false,
+    bindings: &[bind(&[ch('y')], Action::Confirm, "Delete"), bind(&[ch('n'), ESC], Action::Back, "Keep")],
+};
+
+pub static DISCARD_CONFIRM: Keymap = Keymap {
+    title: "Discard changes",
+    typing: false,
+    bindings: &[bind(&[ch('y')], Action::Confirm, "Discard them"), bind(&[ch('n'), ESC], Action::Back, "Keep editing")],
+};
+
+pub static DUPLICATE: Keymap = Keymap {
+    title: "Possible duplicate",
+    typing: false,
+    bindings: &[
+        bind(&[ch('r')], Action::Replace, "Replace the reminder already there"),
+        bind(&[ch('k')], Action::KeepBoth, "Keep both"),
+        bind(&[ch('s'), ESC], Action::Back, "Skip the new one"),
+    ],
+};
+
+pub static CALENDAR: Keymap = Keymap {
+    title: "Calendar",
+    typing: false,
+    bindings: &[
+        bind(&[LEFT], Action::Left, "A day back or on"),
+        bind(&[RIGHT], Action::Right, "A day back or on"),
+        bind(&[UP], Action::Up, "A week back or on"),
+        bind(&[DOWN], Action::Down, "A week back or on"),
+        bind(&[PAGE_UP], Action::PageUp, "A month back or on"),
+        bind(&[PAGE_DOWN], Action::PageDown, "A month back or on"),
+        bind(&[ch('t')], Action::Today, "Jump to today"),
+        bind(&[ENTER], Action::Confirm, "Show the reminders on that day"),
+        bind(&[ESC, ch('C')], Action::Back, "Close"),
+    ],
+};
+
+pub static AGENDA: Keymap = Keymap {
+    title: "Week agenda",
+    typing: false,
+    bindings: &[
+        bind(&[LEFT], Action::Left, "A week back or on"),
+        bind(&[RIGHT], Action::Right, "A week back or on"),
+        bind(&[ch('t')], Action::Today, "Back to this week"),
+        bind(&[ESC, ch('w')], Action::Back, "Close"),
+    ],
+};
+
+pub static PROFILES: Keymap = Keymap {
+    title: "Profiles",
+    typing: false,
+    bindings: &[
+        bind(&[UP, ch('k')], Action::Up, "Move up"),
+        bind(&[DOWN, ch('j')], Action::Down, "Move down"),
+        bind(&[ENTER], Action::Confirm, "Switch to it"),
+        bind(&[ESC], Action::Back, "Close"),
+    ],
+};
+
+pub static TAGS: Keymap = Keymap {
+    title: "Tags",
+    typing: false,
+    bindings: &[
+        bind(&[UP, ch('k')], Action::Up, "Move up"),
+        bind(&[DOWN, ch('j')], Action::Down, "Move down"),
+        bind(&[ENTER], Action::Confirm, "Show only that tag"),
+        bind(&[ESC], Action::Back, "Close"),
+    ],
+};
+
+pub static SNOOZE: Keymap = Keymap {
+    title: "Snooze",
+    typing: false,
+    bindings: &[
+        bind(&[UP, ch('k')], Action::Up, "Move up"),
+        bind(&[DOWN, ch('j')], Action::Down, "Move down"),
+        bind(&[ENTER], Action::Confirm, "Snooze"),
+        bind(&[ch('1')], Action::Pick(0), "Snooze for that preset"),
+        bind(&[ch('2')], Action::Pick(1), "Snooze for that preset"),
+        bind(&[ch('3')], Action::Pick(2), "Snooze for that preset"),
+        bind(&[ch('4')], Action::Pick(3), "Snooze for that preset"),
+        bind(&[ch('5')], Action::Pick(4), "Snooze for that preset"),
+        bind(&[ch('6')], Action::Pick(5), "Snooze for that preset"),
+        bind(&[ch('7')], Action::Pick(6), "Snooze for that preset"),
+        bind(&[ch('8')], Action::Pick(7), "Snooze for that preset"),
+        bind(&[ch('9')], Action::Pick(8), "Snooze for that preset"),
+        bind(&[ESC], Action::Back, "Close"),
+    ],
+};
+
+/// The occurrence editor and the notification settings, which are both a
+/// few fields in a popup.
+pub static POPUP_FORM: Keymap = Keymap {
+    title: "Occurrence and notification settings",
+    typing: true,
+    bindings: &[
+        bind(&[TAB, DOWN], Action::NextField, "Next field"),
+        bind(&[BACK_TAB, UP], Action::PrevField, "Previous field"),
+        bind(&[LEFT], Action::Left, "Move the cursor"),
+        bind(&[RIGHT], Action::Right, "Move the cursor"),
+        bind(&[HOME], Action::First, "Jump to the start or end"),
+        bind(&[END], Action::Last, "Jump to the start or end"),
+        bind(&[BACKSPACE], Action::DeleteBack, "Delete before or after the cursor"),
+        bind(&[DELETE], Action::DeleteForward, "Delete before or after the cursor"),
+        bind(&[ENTER], Action::Confirm, "Save"),
+        bind(&[ESC], Action::Back, "Cancel"),
+    ],
+};
+
+pub static MISSED: Keymap = Keymap {
+    title: "Missed reminders",
+    typing: false,
+    bindings: &[bind(&[ENTER, ESC], Action::Back, "Dismiss")],
+};
+
+pub static LOGS: Keymap = Keymap {
+    title: "Log",
+    typing: false,
+    bindings: &[
+        bind(&[UP], Action::Up, "Scroll a line"),
+        bind(&[DOWN], Action::Down, "Scroll a line"),
+        bind(&[PAGE_UP], Action::PageUp, "Scroll a screen"),
+        bind(&[PAGE_DOWN], Action::PageDown, "Scroll a screen"),
+        bind(&[HOME], Action::First, "Jump to the oldest or newest line"),
+        bind(&[END], Action::Last, "Jump to the oldest or newest line"),
+        bind(&[ESC, ch('l')], Action::Back, "Close"),
+    ],
+};
+
+pub static NOTIFICATIONS: Keymap = Keymap {
+    title: "Notification history",
+    typing: false,
+    bindings: &[
+        bind(&[UP], Action::Up, "Scroll a line"),
+        bind(&[DOWN], Action::Down, "Scroll a line"),
+        bind(&[PAGE_UP], Action::PageUp, "Scroll a screen"),
+        bind(&[PAGE_DOWN], Action::PageDown, "Scroll a screen"),
+        bind(&[HOME], Action::First, "Jump to the newest or oldest"),
+        bind(&[END], Action::Last, "Jump to the newest or oldest"),
+        bind(&[ESC, ch('h')], Action::Back, "Close"),
+    ],
+};
+
+pub static HELP: Keymap = Keymap {
+    title: "Help",
+    typing: false,
+    bindings: &[
+        bind(&[UP, ch('k')], Action::Up, "Scroll a line"),
+        bind(&[DOWN, ch('j')], Action::Down, "Scroll a line"),
+        bind(&[PAGE_UP], Action::PageUp, "Scroll a screen"),
+        bind(&[PAGE_DOWN], Action::PageDown, "Scroll a screen"),
+        bind(&[HOME], Action::First, "Jump to the top or bottom"),
+        bind(&[END], Action::Last, "Jump to the top or bottom"),
+        bind(&[ESC, ch('?'), ch('q')], Action::Back, "Close"),
+    ],
+};
+
+/// Every keymap, with the list's for `preset`, in the order the help
+/// lists them.
+pub fn all(preset: KeyPreset) -> impl Iterator<Item = &'static Keymap> {
+    std::iter::once(list(preset)).chain(OTHERS)
+}
+
+/// Every keymap but the list's.
+static OTHERS: [&Keymap; 17] = [
+    &SEARCH,
+    &FORM,
+    &DATE_PICKER,
+    &QUICK_ADD,
+    &DELETE_CONFIRM,
+    &DISCARD_CONFIRM,
+    &DUPLICATE,
+    &CALENDAR,
+    &AGENDA,
+    &PROFILES,
+    &TAGS,
+    &SNOOZE,
+    &POPUP_FORM,
+    &MISSED,
+    &LOGS,
+    &NOTIFICATIONS,
+    &HELP,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_key_is_bound_twice_in_a_mode() {
+        for keymap in all(KeyPreset::Default).chain(all(KeyPreset::Vim)) {
+            let keys: Vec<Key> = keymap.bindings.iter().flat_map(|b| b.keys.iter().copied()).collect();
+            for (i, key) in keys.iter().enumerate() {
+                assert!(!keys[..i].contains(key), "{} binds {} twice", keymap.title, key.name());
+            }
+        }
+    }
+
+    #[test]
+    fn help_merges_keys_that_do_the_same() {
+        let lines = LIST.help_lines();
+        assert!(lines.contains(&("1-6".to_string(), "Today, Upcoming, All, Done, Archive or Trash")));
+        assert!(lines.contains(&("Ctrl+R".to_string(), "Redo")));
+        assert!(SEARCH.help_lines().contains(&("←/→".to_string(), "Move the cursor")));
+        assert!(DUPLICATE.help_lines().contains(&("s/Esc".to_string(), "Skip the new one")));
+    }
+
+    #[test]
+    fn vim_keys_wait_for_the_second_of_a_pair() {
+        let press = |c| KeyEvent::from(KeyCode::Char(c));
+        let vim = list(KeyPreset::Vim);
+        assert_eq!(vim.resolve(None, &press('j')), Resolved::Action(Action::Down));
+        assert_eq!(vim.resolve(None, &press('d')), Resolved::Pending(ch('d')));
+        assert_eq!(vim.resolve(Some(ch('d')), &press('d')), Resolved::Action(Action::Delete));
+        assert_eq!(vim.resolve(Some(ch('g')), &press('x')), Resolved::Nothing);
+        assert!(vim.help_lines().contains(&("End/G".to_string(), "Jump to the last reminder")));
+        assert_eq!(list(KeyPreset::Default).action(&press('d')), Some(Action::Delete));
+        assert_eq!(list(KeyPreset::Default).action(&press('j')), None);
+    }
+}
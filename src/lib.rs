@@ -0,0 +1,38 @@
+//! Reminder storage and scheduling, as used by the `reminder` TUI and daemon.
+//!
+//! [`Database`] keeps reminders in an SQLite file, [`MemoryStore`] keeps them
+//! in memory, and both are used through the [`ReminderStore`] trait.
+//! [`schedule`] works out when they go off:
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use reminder::{schedule, Database, NewReminder, ReminderStore};
+//!
+//! let db = Database::new(":memory:")?;
+//! let tea = db.add_reminder(NewReminder {
+//!     title: "Tea".to_string(),
+//!     description: "Put the kettle on".to_string(),
+//!     time: "16:00".to_string(),
+//!     alerts: vec![5, 0],
+//!     ..Default::default()
+//! })?;
+//!
+//! let morning = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap().and_hms_opt(9, 0, 0).unwrap();
+//! let next = schedule::next_fire(&tea, morning).unwrap();
+//! assert_eq!(next.format("%H:%M").to_string(), "16:00");
+//! assert_eq!(schedule::due_alerts(&tea, next - chrono::Duration::minutes(5)), vec![5]);
+//! # Ok::<(), reminder::ReminderError>(())
+//! ```
+
+pub mod db;
+pub mod error;
+pub mod json;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod schedule;
+pub mod store;
+
+pub use db::{Database, Delivery, Exception, LoggedNotification, NewReminder, Reminder, Vacation};
+pub use error::ReminderError;
+pub use json::JsonStore;
+pub use store::{MemoryStore, ReminderStore, SharedStore};
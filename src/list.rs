@@ -0,0 +1,150 @@
+//! `reminder list`: the open reminders, for scripts and status bars.
+
+use crate::config::{Config, TimeFormat};
+use crate::open_store;
+use chrono::{Local, NaiveDateTime};
+use reminder::db::Reminder;
+use reminder::schedule;
+use serde::Serialize;
+use std::error::Error;
+use std::io::{self, Write};
+
+/// What each line looks like without `--format`: the same as the control
+/// socket's `list`.
+const DEFAULT_FORMAT: &str = "{id}\t{time}\t{title}";
+
+/// The fields a `--format` template can use.
+const FIELDS: [&str; 8] = ["id", "title", "description", "time", "date", "tags", "priority", "next"];
+
+/// A reminder as `--json` prints it: every stored field, and when it next
+/// goes off.
+#[derive(Serialize)]
+struct Listed<'a> {
+    #[serde(flatten)]
+    reminder: &'a Reminder,
+    next_fire: Option<String>,
+}
+
+/// A `--format` template, split into text and fields.
+#[derive(Debug, PartialEq)]
+enum Piece {
+    Text(String),
+    Field(&'static str),
+}
+
+pub fn run(config: &Config, json: bool, format: Option<&str>) -> Result<(), Box<dyn Error>> {
+    // A bad template fails before the store is opened.
+    let template = parse(format.unwrap_or(DEFAULT_FORMAT))?;
+    let reminders: Vec<Reminder> =
+        open_store(config)?.get_all_reminders()?.into_iter().filter(|r| r.completed_at.is_none()).collect();
+    let now = Local::now().naive_local();
+    let next_fire = |reminder: &Reminder| schedule::next_fire(reminder, now);
+
+    let mut out = io::stdout().lock();
+    if json {
+        let listed: Vec<Listed> = reminders
+            .iter()
+            .map(|reminder| Listed {
+                reminder,
+                next_fire: next_fire(reminder).map(|at| at.format("%Y-%m-%d %H:%M:%S").to_string()),
+            })
+            .collect();
+        serde_json::to_writer_pretty(&mut out, &listed)?;
+        writeln!(out)?;
+    } else {
+        for reminder in &reminders {
+            writeln!(out, "{}", render(&template, reminder, next_fire(reminder), config.time_format))?;
+        }
+    }
+    Ok(())
+}
+
+/// Reads a template like `{time} {title}`; `{{` and `}}` stand for braces.
+fn parse(format: &str) -> Result<Vec<Piece>, String> {
+    let mut pieces = Vec::new();
+    let mut text = String::new();
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => name.push(c),
+                        None => return Err(format!("unclosed {{{} in the format", name)),
+                    }
+                }
+                let Some(field) = FIELDS.iter().find(|field| **field == name) else {
+                    return Err(format!("unknown field {{{}}} in the format; use {}", name, FIELDS.join(", ")));
+                };
+                if !text.is_empty() {
+                    pieces.push(Piece::Text(std::mem::take(&mut text)));
+                }
+                pieces.push(Piece::Field(field));
+            }
+            c => text.push(c),
+        }
+    }
+    if !text.is_empty() {
+        pieces.push(Piece::Text(text));
+    }
+    Ok(pieces)
+}
+
+/// One reminder as a line of `template`. Times follow the config's
+/// `time_format`; a description is kept to one line.
+fn render(template: &[Piece], reminder: &Reminder, next_fire: Option<NaiveDateTime>, time_format: TimeFormat) -> String {
+    template
+        .iter()
+        .map(|piece| match piece {
+            Piece::Text(text) => text.clone(),
+            Piece::Field("id") => reminder.id.to_string(),
+            Piece::Field("title") => reminder.title.clone(),
+            Piece::Field("description") => reminder.description.lines().collect::<Vec<_>>().join(" "),
+            Piece::Field("time") => time_format.render(&reminder.time),
+            Piece::Field("date") => reminder.date.clone().unwrap_or_default(),
+            Piece::Field("tags") => reminder.tags.iter().map(|tag| format!("#{}", tag)).collect::<Vec<_>>().join(" "),
+            Piece::Field("priority") => reminder.priority.as_str().to_string(),
+            Piece::Field("next") => next_fire
+                .map(|at| format!("{} {}", at.format("%Y-%m-%d"), time_format.format(at.time())))
+                .unwrap_or_default(),
+            Piece::Field(_) => String::new(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use reminder::store::ReminderStore;
+    use reminder::{MemoryStore, NewReminder};
+
+    #[test]
+    fn formats_each_reminder_from_the_template() {
+        let reminder = MemoryStore::new()
+            .add_reminder(NewReminder {
+                title: "Standup".to_string(),
+                time: "09:30".to_string(),
+                tags: vec!["work".to_string(), "daily".to_string()],
+                ..Default::default()
+            })
+            .unwrap();
+        let template = parse("{{{id}}} {time} {title} {tags}|{next}").unwrap();
+        let next = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap().and_hms_opt(9, 30, 0);
+        assert_eq!(render(&template, &reminder, next, TimeFormat::H24), "{1} 09:30 Standup #work #daily|2026-10-16 09:30");
+        assert_eq!(render(&template, &reminder, None, TimeFormat::H12), "{1} 9:30 AM Standup #work #daily|");
+
+        assert!(parse("{time} {when}").unwrap_err().contains("{when}"));
+        assert!(parse("{time").is_err());
+    }
+}
@@ -0,0 +1,88 @@
+//! What the worker and servers did, written to a file since printing would
+//! land on top of the TUI.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+use tracing_appender::{
+    non_blocking::WorkerGuard,
+    rolling::{Builder, Rotation},
+};
+use tracing_subscriber::{fmt::time::ChronoLocal, EnvFilter};
+
+/// Days of logs kept; each day gets a file of its own.
+const KEEP_DAYS: usize = 7;
+
+/// `$XDG_DATA_HOME/reminder/logs`, or the platform equivalent.
+pub fn dir() -> PathBuf {
+    dirs::data_dir()
+        .map(|dir| dir.join("reminder").join("logs"))
+        .unwrap_or_else(|| PathBuf::from("logs"))
+}
+
+/// Sends `tracing` events to `reminder.DATE.log` in [`dir`], at the level
+/// set by `REMINDER_LOG` (`info` by default). Lines are written on a thread
+/// of their own until the returned guard is dropped.
+pub fn init() -> io::Result<WorkerGuard> {
+    let appender = Builder::new()
+        .rotation(Rotation::DAILY)
+        .filename_prefix("reminder")
+        .filename_suffix("log")
+        .max_log_files(KEEP_DAYS)
+        .build(dir())
+        .map_err(io::Error::other)?;
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+    // The PostgreSQL driver logs a notice for every `IF NOT EXISTS` on connect.
+    let filter = EnvFilter::try_from_env("REMINDER_LOG").unwrap_or_else(|_| EnvFilter::new("info,postgres=warn"));
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(writer)
+        // Local time, to match the reminders' own.
+        .with_timer(ChronoLocal::rfc_3339())
+        .with_ansi(false)
+        .try_init()
+        .map_err(io::Error::other)?;
+    Ok(guard)
+}
+
+/// The newest log file, if anything has been logged yet. Their names sort by
+/// date.
+pub fn latest() -> io::Result<Option<PathBuf>> {
+    let entries = match fs::read_dir(dir()) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let mut logs: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("reminder.") && name.ends_with(".log"))
+        })
+        .collect();
+    logs.sort();
+    Ok(logs.pop())
+}
+
+/// The last `count` lines of the log at `path`.
+pub fn tail(path: &Path, count: usize) -> io::Result<Vec<String>> {
+    let text = String::from_utf8_lossy(&fs::read(path)?).into_owned();
+    let lines: Vec<&str> = text.lines().collect();
+    Ok(lines[lines.len().saturating_sub(count)..].iter().map(|line| line.to_string()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tails_the_last_lines() {
+        let path = std::env::temp_dir().join(format!("reminder-log-test-{}.log", std::process::id()));
+        fs::write(&path, "one\ntwo\nthree\n").unwrap();
+        assert_eq!(tail(&path, 2).unwrap(), ["two", "three"]);
+        assert_eq!(tail(&path, 5).unwrap(), ["one", "two", "three"]);
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,50 +1,178 @@
-mod db;
+mod backup;
+mod cli;
+mod config;
+mod daemon;
+mod editor;
+mod events;
+mod form;
+mod fuzzy;
+mod history;
+mod holidays;
+#[cfg(feature = "http")]
+mod http;
+mod import;
+mod ipc;
+mod keymap;
+mod list;
+mod logging;
+mod markdown;
+mod merge;
+mod quick_add;
+mod status;
+mod sync;
 mod ui;
+mod vacation;
+mod worker;
 
-use chrono::Local;
+use chrono::{Duration, Local, Months, NaiveDate};
+use clap::{CommandFactory, Parser};
+use cli::{Cli, Command, DaemonAction, DbAction, SyncAction, VacationAction};
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent},
+    cursor,
+    event::{
+        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent,
+        MouseEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use db::Database;
-use notify_rust::Notification;
+use config::{snooze_until, Config, Storage, TimeFormat};
+use reminder::{db, schedule, store};
+use db::{Database, Exception, NewReminder, Priority, Reminder};
+use store::{ReminderStore, SharedStore};
+use events::{AppEvent, EventBus, ReminderEvent};
 use ratatui::{backend::CrosstermBackend, Terminal};
-use std::collections::HashSet;
 use std::{
     error::Error,
     io,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Instant,
 };
-use ui::{draw_ui, AppState, Mode};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use keymap::Action;
+use ui::{draw_ui, AppState, Discard, Mode, QuickFilter, Tab};
+use worker::{notification_worker, WorkerEvent};
+
+/// How many reminders the All and Done tabs load at a time.
+const PAGE_SIZE: usize = 200;
+
+/// How much of the log the log view reads.
+const LOG_LINES: usize = 1000;
+
+/// How many notifications the notification history shows.
+const NOTIFICATION_HISTORY: usize = 500;
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let db = Database::new("reminders.db")?;
-    let reminders = db.get_all_reminders()?;
-    let mut app = AppState::new(reminders);
+    let cli = Cli::parse();
+
+    match cli.command {
+        Some(Command::Daemon { action: DaemonAction::Install }) => {
+            return daemon::install(cli.profile.as_deref(), cli.db.as_deref())
+        }
+        Some(Command::Daemon { action: DaemonAction::Uninstall }) => return daemon::uninstall(cli.profile.as_deref()),
+        Some(Command::Completions { shell }) => {
+            let mut command = Cli::command();
+            let name = command.get_name().to_string();
+            // Written out here rather than by clap_complete, which panics
+            // if the output is closed early.
+            let mut script = Vec::new();
+            clap_complete::generate(shell, &mut command, name, &mut script);
+            return Ok(io::Write::write_all(&mut io::stdout(), &script)?);
+        }
+        _ => {}
+    }
+
+    let mut config = Config::load(cli.profile.as_deref())?;
+    config.db_path = cli.db.clone();
+
+    match &cli.command {
+        Some(Command::Import { path, duplicates }) => return import::run(&config, path, *duplicates),
+        Some(Command::Merge { path }) => return merge::run(&config, path),
+        Some(Command::Sync { action: SyncAction::Export { dir } }) => return sync::export(&config, dir.as_deref()),
+        Some(Command::Sync { action: SyncAction::Import { dir } }) => return sync::import(&config, dir.as_deref()),
+        Some(Command::List { json, format }) => return list::run(&config, *json, format.as_deref()),
+        Some(Command::Status { waybar }) => return status::run(&config, *waybar),
+        Some(Command::Next { short, within }) => return status::next(&config, *short, *within),
+        Some(Command::Backup { path }) => return backup::backup(&config, path),
+        Some(Command::Restore { path }) => return backup::restore(&config, path),
+        Some(Command::Db { action: DbAction::Check }) => return backup::check(&config),
+        Some(Command::Vacation { action: VacationAction::Add { from, to, tags } }) => {
+            return vacation::add(&config, from, to, tags)
+        }
+        Some(Command::Vacation { action: VacationAction::List }) => return vacation::list(&config),
+        Some(Command::Vacation { action: VacationAction::Remove { id } }) => return vacation::remove(&config, *id),
+        _ => {}
+    }
 
-    let notified_ids = Arc::new(Mutex::new(HashSet::new()));
-    let notified_ids_clone = Arc::clone(&notified_ids);
+    // Only the TUI and daemon run long enough to have anything to log.
+    let _log = logging::init()
+        .inspect_err(|e| eprintln!("Logging to {} unavailable: {}", logging::dir().display(), e))
+        .ok();
 
-    std::thread::spawn(move || {
-        notification_worker(notified_ids_clone);
+    if cli.daemon {
+        return daemon::run(config);
+    }
+
+    let db = Arc::new(SharedStore::new(open_store(&config)?));
+    let mut app = AppState::new(config.time_format);
+    app.lock = config.lock.clone();
+    app.muted = config.muted;
+    app.snooze_minutes = config.snooze_all_minutes();
+    app.snooze_presets = config.snooze_choices();
+    app.overdue_first = config.overdue_first;
+    app.tag_colors = config.tag_colors();
+    app.key_preset = config.keymap;
+    app.sections = config.sections;
+    app.sort = config.sort;
+    app.sort_desc = config.sort_descending;
+    app.profile = config.profile.clone();
+    app.profiles = config.profiles.clone();
+    load_reminders(&mut app, &*db);
+
+    // The worker, HTTP server and event sources run as tasks on the runtime;
+    // the TUI itself stays on this thread and waits for their events.
+    let runtime = tokio::runtime::Runtime::new()?;
+    let _runtime = runtime.enter();
+    let events = events::new_bus();
+    let (tx, mut inbox) = mpsc::unbounded_channel();
+    events::forward(&events, tx.clone(), AppEvent::Reminder);
+    events::tick(tx.clone(), std::time::Duration::from_secs(1));
+    events::quit_on_signals(tx.clone())?;
+    let input = TerminalInput::spawn(tx);
+    let owns_socket = ipc::spawn_server(Arc::clone(&db), events.clone()).unwrap_or_else(|e| {
+        tracing::warn!("control socket unavailable: {}", e);
+        false
     });
+    start_http(&config, Arc::clone(&db), events.clone());
 
+    let (worker_tx, mut worker_inbox) = mpsc::unbounded_channel();
+    events::forward(&events, worker_tx.clone(), WorkerEvent::Reminder);
+    let worker_events = events.clone();
+    let worker_db = Arc::clone(&db);
+    let worker = tokio::spawn(async move {
+        notification_worker(config, worker_db, &mut worker_inbox, worker_events).await;
+    });
+
+    install_panic_hook();
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture, EnableBracketedPaste)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let res = run_app(&mut terminal, &db, &mut app, Arc::clone(&notified_ids));
+    let res = run_app(&mut terminal, &db, &mut app, &events, &mut inbox, &input);
 
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    restore_terminal()?;
+
+    // Let the worker finish the check it may be in the middle of.
+    let _ = worker_tx.send(WorkerEvent::Stop);
+    let _ = runtime.block_on(worker);
+    if owns_socket {
+        let _ = std::fs::remove_file(ipc::socket_path());
+    }
 
     if let Err(err) = res {
         println!("{:?}", err);
@@ -53,172 +181,1641 @@ fn main() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+/// Takes the terminal out of raw mode and the alternate screen.
+fn restore_terminal() -> io::Result<()> {
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture, DisableBracketedPaste, cursor::Show)
+}
+
+/// Restores the terminal before a panic on this thread is reported, so the
+/// message can be read and the shell still works. Panics on other threads
+/// leave the TUI running and the terminal alone.
+fn install_panic_hook() {
+    let tui = std::thread::current().id();
+    let report = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if std::thread::current().id() == tui {
+            let _ = restore_terminal();
+        }
+        report(info);
+    }));
+}
+
+/// Opens the store the config asks for.
+fn open_store(config: &Config) -> Result<Box<dyn ReminderStore + Send>, Box<dyn Error>> {
+    match &config.storage {
+        Storage::Sqlite => Ok(Box::new(Database::new(config.database_path()?)?)),
+        Storage::Json { path } => Ok(Box::new(reminder::JsonStore::open(path)?)),
+        #[cfg(feature = "postgres")]
+        Storage::Postgres { url } => Ok(Box::new(reminder::postgres::PgStore::connect(url)?)),
+        #[cfg(not(feature = "postgres"))]
+        Storage::Postgres { .. } => Err("[storage] uses postgres, but this build lacks the `postgres` feature".into()),
+        #[cfg(feature = "sqlcipher")]
+        Storage::Sqlcipher => {
+            let path = config.database_path()?;
+            let passphrase = match std::env::var("REMINDER_PASSPHRASE") {
+                Ok(passphrase) => passphrase,
+                Err(_) => read_passphrase(&format!("Passphrase for {}: ", path.display()))?,
+            };
+            Ok(Box::new(Database::new_encrypted(path, &passphrase)?))
+        }
+        #[cfg(not(feature = "sqlcipher"))]
+        Storage::Sqlcipher => Err("[storage] uses sqlcipher, but this build lacks the `sqlcipher` feature".into()),
+    }
+}
+
+/// Reads a line from the terminal without echoing it.
+#[cfg(feature = "sqlcipher")]
+fn read_passphrase(prompt: &str) -> io::Result<String> {
+    use std::io::{IsTerminal, Write};
+    if !io::stdin().is_terminal() {
+        return Err(io::Error::other("no terminal to ask for the passphrase; set REMINDER_PASSPHRASE"));
+    }
+    eprint!("{}", prompt);
+    io::stderr().flush()?;
+
+    enable_raw_mode()?;
+    let mut passphrase = String::new();
+    let result = loop {
+        match event::read() {
+            Ok(Event::Key(key)) => match key.code {
+                KeyCode::Enter => break Ok(()),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    break Err(io::Error::new(io::ErrorKind::Interrupted, "cancelled"))
+                }
+                KeyCode::Char(c) => passphrase.push(c),
+                KeyCode::Backspace => {
+                    passphrase.pop();
+                }
+                _ => {}
+            },
+            Ok(_) => {}
+            Err(e) => break Err(e),
+        }
+    };
+    disable_raw_mode()?;
+    eprintln!();
+    result.map(|_| passphrase)
+}
+
+#[cfg(feature = "http")]
+fn start_http(config: &Config, db: Arc<SharedStore>, events: EventBus) {
+    if let Some(http) = config.http.clone() {
+        let bind = http.bind.clone();
+        if let Err(e) = http::spawn_server(http, db, events) {
+            tracing::warn!("HTTP server on {} unavailable: {}", bind, e);
+        }
+    }
+}
+
+#[cfg(not(feature = "http"))]
+fn start_http(config: &Config, _db: Arc<SharedStore>, _events: EventBus) {
+    if config.http.is_some() {
+        tracing::warn!("ignoring [http] in the config: built without the `http` feature");
+    }
+}
+
+/// Reads terminal input on a thread of its own and passes it on as
+/// [`AppEvent::Input`], pausing while something else has the terminal.
+struct TerminalInput {
+    paused: AtomicBool,
+    // Held around each read, so pausing waits for one in progress.
+    reading: Mutex<()>,
+}
+
+impl TerminalInput {
+    fn spawn(tx: UnboundedSender<AppEvent>) -> Arc<Self> {
+        let input = Arc::new(TerminalInput { paused: AtomicBool::new(false), reading: Mutex::new(()) });
+        let reader = Arc::clone(&input);
+        std::thread::spawn(move || loop {
+            if reader.paused.load(Ordering::Relaxed) {
+                std::thread::sleep(std::time::Duration::from_millis(50));
+                continue;
+            }
+            let _reading = reader.reading.lock().unwrap_or_else(|e| e.into_inner());
+            // Polling first gives `paused` a chance to be seen between reads.
+            let event = match event::poll(std::time::Duration::from_millis(100)) {
+                Ok(true) => event::read(),
+                Ok(false) => continue,
+                Err(e) => Err(e),
+            };
+            let Ok(event) = event else { return };
+            if tx.send(AppEvent::Input(event)).is_err() {
+                return;
+            }
+        });
+        input
+    }
+
+    /// Runs `f` with no input being read, e.g. while an editor has the terminal.
+    fn paused<T>(&self, f: impl FnOnce() -> T) -> T {
+        self.paused.store(true, Ordering::Relaxed);
+        let result = {
+            let _reading = self.reading.lock().unwrap_or_else(|e| e.into_inner());
+            f()
+        };
+        self.paused.store(false, Ordering::Relaxed);
+        result
+    }
+}
+
+/// Redraws and handles the next event until the user quits or the input
+/// reader goes away.
 fn run_app<B: ratatui::backend::Backend>(
     terminal: &mut Terminal<B>,
-    db: &Database,
+    db: &SharedStore,
     app: &mut AppState,
-    _notified_ids: Arc<Mutex<HashSet<i32>>>,
+    events: &EventBus,
+    inbox: &mut UnboundedReceiver<AppEvent>,
+    input: &TerminalInput,
 ) -> io::Result<()> {
+    // The minute the due-soon count was last worked out for.
+    let mut counted_at = Local::now().format("%H:%M").to_string();
     loop {
         terminal.draw(|f| draw_ui(f, app))?;
 
-        if crossterm::event::poll(std::time::Duration::from_millis(250))? {
-            if let Event::Key(key) = event::read()? {
+        let Some(event) = inbox.blocking_recv() else {
+            return Ok(());
+        };
+        match event {
+            AppEvent::Tick => {
+                app.lock_if_idle();
+                app.expire_status();
                 match app.mode {
-                    Mode::List => handle_list_input(key, app),
-                    Mode::Add => handle_form_input(key, app, db, true),
-                    Mode::Edit => handle_form_input(key, app, db, false),
-                    Mode::Delete => handle_delete_input(key, app, db),
+                    Mode::Logs => load_log(app),
+                    Mode::Notifications => load_notifications(app, db),
+                    _ => {}
                 }
+                let minute = Local::now().format("%H:%M").to_string();
+                if minute != counted_at {
+                    counted_at = minute;
+                    count_due_soon(app, db);
+                    find_overdue(app, db);
+                }
+            }
+            AppEvent::Reminder(ReminderEvent::Fired { title, offset_minutes, .. }) => {
+                if offset_minutes == 0 {
+                    app.notify(format!("\"{}\" is due", title));
+                } else {
+                    app.notify(format!("\"{}\" is due in {} min", title, offset_minutes));
+                }
+                // Firing can move reminders to the archive or trash, or
+                // record an interval's last run.
+                load_reminders(app, db);
             }
+            AppEvent::Reminder(ReminderEvent::Changed) => {
+                load_reminders(app, db);
+            }
+            AppEvent::Reminder(ReminderEvent::Missed { reminders, while_muted }) => {
+                app.missed_while_muted = while_muted && (app.missed.is_empty() || app.missed_while_muted);
+                app.missed.extend(reminders);
+            }
+            AppEvent::Reminder(ReminderEvent::Muted { .. } | ReminderEvent::SnoozeAll) => {}
+            AppEvent::Reminder(ReminderEvent::ProfileSwitched { .. }) => {}
+            AppEvent::Quit => app.quit = true,
+            AppEvent::Input(event) => handle_input(terminal, event, app, db, events, input)?,
+        }
+        if app.quit {
+            return Ok(());
+        }
+        // Wait for anything in progress to be finished before popping up.
+        if app.mode == Mode::List && !app.missed.is_empty() {
+            app.mode = Mode::Missed;
         }
     }
 }
 
-fn handle_list_input(key: KeyEvent, app: &mut AppState) {
-    match key.code {
-        KeyCode::Char('q') => std::process::exit(0),
-        KeyCode::Char('a') => {
-            app.mode = Mode::Add;
-            app.input.clear();
-            app.input_field = 0;
-            app.form_fields = [String::new(), String::new(), String::new()];
+fn handle_input<B: ratatui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    event: Event,
+    app: &mut AppState,
+    db: &SharedStore,
+    events: &EventBus,
+    input: &TerminalInput,
+) -> io::Result<()> {
+    if matches!(event, Event::Key(_) | Event::Mouse(_) | Event::Paste(_)) {
+        app.last_input = Instant::now();
+    }
+    match event {
+        // Raw mode turns Ctrl+C into a key press instead of SIGINT.
+        Event::Key(key) if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) => {
+            // Asks before throwing away a form's changes; a second Ctrl+C
+            // quits anyway.
+            if matches!(app.mode, Mode::Add | Mode::Edit) && !app.locked && app.discarding.is_none() && app.form_changed() {
+                app.discarding = Some(Discard::Quit);
+            } else {
+                app.quit = true
+            }
+        }
+        Event::Key(key) if app.locked => handle_lock_input(key, app),
+        Event::Mouse(_) | Event::Paste(_) if app.locked => {}
+        Event::Key(key) if matches!(app.mode, Mode::Add | Mode::Edit) && app.discarding.is_none() && keymap::FORM.action(&key) == Some(Action::Editor) => {
+            input.paused(|| edit_description(terminal, app))?
+        }
+        Event::Key(key) => match app.mode {
+            Mode::List => handle_list_input(key, app, db, events),
+            Mode::Add => handle_form_input(key, app, db, events, true),
+            Mode::Edit => handle_form_input(key, app, db, events, false),
+            Mode::Delete => handle_delete_input(key, app, db, events),
+            Mode::QuickAdd => handle_quick_add_input(key, app, db, events),
+            Mode::Calendar => handle_calendar_input(key, app, db),
+            Mode::Agenda => handle_agenda_input(key, app),
+            Mode::Search => handle_search_input(key, app, db),
+            Mode::Profiles => handle_profiles_input(key, app, db, events),
+            Mode::Duplicate => handle_duplicate_input(key, app, db, events),
+            Mode::Logs => handle_logs_input(key, app),
+            Mode::Missed => handle_missed_input(key, app),
+            Mode::Notifications => handle_notifications_input(key, app),
+            Mode::Occurrence => handle_occurrence_input(key, app, db, events),
+            Mode::Snooze => handle_snooze_input(key, app, db, events),
+            Mode::Settings => handle_settings_input(key, app, db, events),
+            Mode::Tags => handle_tags_input(key, app, db),
+            Mode::Help => handle_help_input(key, app),
+        },
+        Event::Mouse(mouse) => handle_mouse(mouse, app, db),
+        Event::Paste(text) if matches!(app.mode, Mode::Add | Mode::Edit | Mode::QuickAdd | Mode::Search | Mode::Occurrence | Mode::Settings) && app.discarding.is_none() => {
+            app.paste(&text);
+            if app.mode == Mode::Search {
+                search(app, db);
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Suspends the TUI to edit the form's description in the user's editor.
+fn edit_description<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, app: &mut AppState) -> io::Result<()> {
+    app.focus_field(1);
+
+    restore_terminal()?;
+    let edited = editor::edit(&app.input);
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture, EnableBracketedPaste)?;
+    terminal.clear()?;
+
+    match edited {
+        Ok(Some(description)) => {
+            app.set_input(description);
             app.error_msg = None;
         }
-        KeyCode::Char('e') if !app.reminders.is_empty() => {
-            app.mode = Mode::Edit;
-            app.input.clear();
+        Ok(None) => app.error_msg = Some("Editor exited with an error; description unchanged".to_string()),
+        Err(e) => app.error_msg = Some(format!("Editing failed: {}", e)),
+    }
+    Ok(())
+}
+
+/// Whether the list is loaded a page at a time. Sorting, day filters and the
+/// calendar views need every reminder, so they load the whole tab.
+fn is_paged(app: &AppState) -> bool {
+    matches!(app.tab, Tab::All | Tab::Done) && app.mode == Mode::List && app.day_filter.is_none() && app.search.is_empty() && app.tag_filter.is_none() && app.quick_filter.is_none() && app.sort.is_none()
+}
+
+fn fetch_page(app: &AppState, db: &dyn ReminderStore, offset: usize, limit: usize) -> store::Result<Vec<Reminder>> {
+    match app.tab {
+        Tab::Done => db.get_done_reminders_page(offset, limit),
+        _ => db.get_reminders_page(offset, limit),
+    }
+}
+
+/// Reloads the list with the query behind the current tab, and what's coming
+/// up with it.
+fn load_reminders(app: &mut AppState, db: &dyn ReminderStore) {
+    let now = Local::now().naive_local();
+    count_due_soon(app, db);
+    find_overdue(app, db);
+    if !app.search.is_empty() {
+        // The full-text index finds the reminders with a word starting like
+        // each searched one; those are matched fuzzily, best matches first.
+        match db.search_reminders(&fuzzy::index_query(&app.search)) {
+            Ok(reminders) => {
+                let mut found: Vec<_> = reminders
+                    .into_iter()
+                    .filter_map(|r| Some((fuzzy::find(&app.search, &r.title, &r.description)?.score, r)))
+                    .collect();
+                found.sort_by(|(a, ra), (b, rb)| b.cmp(a).then(ra.time.cmp(&rb.time)).then(ra.id.cmp(&rb.id)));
+                app.set_reminders(found.into_iter().map(|(_, r)| r).collect());
+                app.total = app.reminders.len();
+            }
+            Err(e) => show_error(app, "search", e),
+        }
+        return;
+    }
+    if let Some(tag) = &app.tag_filter {
+        match db.get_tagged_reminders(tag) {
+            Ok(tagged) => {
+                app.set_reminders(tagged);
+                app.total = app.reminders.len();
+            }
+            Err(e) => show_error(app, "load tagged reminders", e),
+        }
+        return;
+    }
+    if let Some(filter) = app.quick_filter {
+        let filtered = match filter {
+            QuickFilter::NextHour => db.get_reminders_due_by(now, now + Duration::hours(1)),
+            QuickFilter::Overdue => db.get_overdue_reminders(now),
+            QuickFilter::Today => db.get_today_reminders(now.date()),
+        };
+        match filtered {
+            Ok(filtered) => {
+                app.set_reminders(filtered);
+                app.total = app.reminders.len();
+            }
+            Err(e) => show_error(app, "load reminders", e),
+        }
+        return;
+    }
+    if is_paged(app) {
+        // Keep as many rows as were already loaded so the selection stays put.
+        let limit = app.reminders.len().max(PAGE_SIZE);
+        let total = match app.tab {
+            Tab::Done => db.count_done_reminders(),
+            _ => db.count_reminders(),
+        };
+        match total.and_then(|total| Ok((total, fetch_page(app, db, 0, limit)?))) {
+            Ok((total, page)) => {
+                app.total = total;
+                app.set_reminders(page);
+            }
+            Err(e) => show_error(app, "load reminders", e),
+        }
+        return;
+    }
+
+    let reminders = match app.tab {
+        Tab::Today => db.get_today_reminders(now.date()),
+        Tab::Upcoming => db.get_upcoming_reminders(now),
+        Tab::All => db.get_all_reminders(),
+        Tab::Done => db.count_done_reminders().and_then(|total| db.get_done_reminders_page(0, total)),
+        Tab::Archive => db.get_archived_reminders(),
+        Tab::Trash => db.get_trash_reminders(),
+    };
+    match reminders {
+        Ok(reminders) => {
+            app.set_reminders(reminders);
+            app.total = app.reminders.len();
+        }
+        Err(e) => show_error(app, "load reminders", e),
+    }
+}
+
+/// Tells the user a store call failed instead of carrying on as if nothing
+/// happened.
+fn show_error(app: &mut AppState, action: &str, e: store::Error) {
+    tracing::error!("couldn't {}: {}", action, e);
+    app.notify_error(format!("Couldn't {}: {}", action, e));
+}
+
+/// Counts the open reminders going off within the next hour, leaving out
+/// those on vacation, for the status bar; notes the vacations for the header
+/// and the soonest reminder for the banner above the list.
+fn count_due_soon(app: &mut AppState, db: &dyn ReminderStore) {
+    let now = Local::now().naive_local();
+    match db.vacations_on(now.date()).and_then(|vacations| Ok((vacations, db.get_upcoming_reminders(now)?))) {
+        Ok((vacations, upcoming)) => {
+            let upcoming: Vec<_> = upcoming
+                .into_iter()
+                .filter(|r| !vacations.iter().any(|v| v.covers(r, now.date())))
+                .filter_map(|r| Some((schedule::next_fire(&r, now)?, r)))
+                .collect();
+            app.due_soon = upcoming.iter().take_while(|(at, _)| *at <= now + Duration::hours(1)).count();
+            app.next_up = upcoming.into_iter().next().map(|(at, r)| (r, at));
+            app.vacations = vacations;
+        }
+        Err(e) => show_error(app, "count upcoming reminders", e),
+    }
+}
+
+/// Notes the open reminders that were due earlier without a notification
+/// being shown for them since, counting from their earliest alert.
+fn find_overdue(app: &mut AppState, db: &dyn ReminderStore) {
+    match db.get_overdue_reminders(Local::now().naive_local()) {
+        Ok(overdue) => app.set_overdue(overdue.iter().map(|r| r.id).collect()),
+        Err(e) => show_error(app, "look for overdue reminders", e),
+    }
+}
+
+/// Loads up to `limit` more reminders of a paged tab.
+fn load_more(app: &mut AppState, db: &dyn ReminderStore, limit: usize) {
+    if app.reminders.len() >= app.total {
+        return;
+    }
+    match fetch_page(app, db, app.reminders.len(), limit) {
+        Ok(page) => app.reminders.extend(page),
+        Err(e) => show_error(app, "load reminders", e),
+    }
+}
+
+fn switch_tab(app: &mut AppState, db: &dyn ReminderStore, tab: Tab) {
+    app.tab = tab;
+    app.marked.clear();
+    app.day_filter = None;
+    app.search.clear();
+    app.tag_filter = None;
+    app.quick_filter = None;
+    app.selected_idx = 0;
+    app.reminders.clear();
+    load_reminders(app, db);
+}
+
+/// Raises or lowers the priority of `before`, one step each.
+fn change_priority(app: &mut AppState, db: &dyn ReminderStore, events: &EventBus, before: Vec<Reminder>, raise: bool) {
+    let after: Vec<Reminder> = before
+        .iter()
+        .cloned()
+        .map(|mut reminder| {
+            reminder.priority = if raise { reminder.priority.raised() } else { reminder.priority.lowered() };
+            reminder.touch();
+            reminder
+        })
+        .collect();
+    // Only the priority is written, so nothing changed elsewhere since they
+    // were read is undone.
+    let written = [Priority::Low, Priority::Normal, Priority::High].into_iter().try_for_each(|priority| {
+        let ids: Vec<i32> = after.iter().filter(|r| r.priority == priority).map(|r| r.id).collect();
+        if ids.is_empty() { Ok(()) } else { db.set_priority(&ids, priority) }
+    });
+    match written {
+        Ok(()) => {
+            match after.as_slice() {
+                [] => return,
+                [one] => app.notify(format!("\"{}\" is now {} priority", one.title, one.priority.as_str())),
+                many => app.notify(format!("{} priority of {} reminder(s)", if raise { "Raised" } else { "Lowered" }, many.len())),
+            }
+            app.history.record(before, after);
+            app.marked.clear();
+            events::publish(events, ReminderEvent::Changed);
+        }
+        Err(e) => show_error(app, "change the priority", e),
+    }
+}
+
+/// Leaves the next occurrence of a repeating reminder out of its schedule;
+/// `u` puts it back.
+fn skip_next(app: &mut AppState, db: &dyn ReminderStore, events: &EventBus, reminder: Reminder) {
+    if reminder.date.is_some() {
+        app.notify("Only repeating reminders have occurrences to skip");
+        return;
+    }
+    let Some((day, at)) = schedule::next_occurrence(&reminder, Local::now().naive_local()) else {
+        app.notify(format!("\"{}\" doesn't go off again", reminder.title));
+        return;
+    };
+    let exception = Exception { date: day.format("%Y-%m-%d").to_string(), time: None, note: None };
+    match db.add_exception(reminder.id, &exception) {
+        Ok(()) => {
+            app.notify(format!("Skipped \"{}\" on {}", reminder.title, at.format("%a %Y-%m-%d")));
+            let mut after = reminder.clone();
+            after.set_exception(exception);
+            app.history.record(vec![reminder], vec![after]);
+            events::publish(events, ReminderEvent::Changed);
+        }
+        Err(e) => show_error(app, "skip the reminder", e),
+    }
+}
+
+/// Shows the reminders tagged `tag`, or all of them for `None`.
+fn filter_by_tag(app: &mut AppState, db: &dyn ReminderStore, tag: Option<String>) {
+    app.tab = Tab::All;
+    app.tag_filter = tag;
+    app.quick_filter = None;
+    app.day_filter = None;
+    app.search.clear();
+    app.selected_idx = 0;
+    app.reminders.clear();
+    load_reminders(app, db);
+}
+
+/// Shows only the reminders `filter` picks, or all of them for `None`.
+fn filter_quickly(app: &mut AppState, db: &dyn ReminderStore, filter: Option<QuickFilter>) {
+    app.tab = Tab::All;
+    app.quick_filter = filter;
+    app.tag_filter = None;
+    app.day_filter = None;
+    app.search.clear();
+    app.selected_idx = 0;
+    app.reminders.clear();
+    load_reminders(app, db);
+}
+
+/// Shows all reminders going off on `day`, or all of them for `None`.
+fn filter_by_day(app: &mut AppState, db: &dyn ReminderStore, day: Option<NaiveDate>) {
+    app.tab = Tab::All;
+    app.day_filter = day;
+    app.tag_filter = None;
+    app.quick_filter = None;
+    app.selected_idx = 0;
+    load_reminders(app, db);
+}
+
+fn handle_list_input(key: KeyEvent, app: &mut AppState, db: &dyn ReminderStore, events: &EventBus) {
+    let action = match keymap::list(app.key_preset).resolve(app.pending_key.take(), &key) {
+        keymap::Resolved::Action(action) => action,
+        keymap::Resolved::Pending(first) => {
+            app.pending_key = Some(first);
+            return;
+        }
+        keymap::Resolved::Nothing => return,
+    };
+    // Trashed reminders can only be restored or deleted for good.
+    let changes = matches!(
+        action,
+        Action::Edit
+            | Action::Done
+            | Action::Skip
+            | Action::Occurrence
+            | Action::NotifySettings
+            | Action::Snooze
+            | Action::RaisePriority
+            | Action::LowerPriority
+    );
+    if changes && app.tab == Tab::Trash {
+        return;
+    }
+    match action {
+        Action::Quit => app.quit = true,
+        Action::Add => {
+            app.mode = Mode::Add;
+            app.copying = None;
+            app.set_input(String::new());
             app.input_field = 0;
-            app.form_fields = [String::new(), String::new(), String::new()];
+            app.form_fields = Default::default();
+            app.form_opened = Default::default();
+            app.touched = Default::default();
             app.error_msg = None;
         }
-        KeyCode::Char('d') if !app.reminders.is_empty() => {
+        Action::Edit => {
+            if let Some(reminder) = app.reminders.get(app.selected_idx).cloned() {
+                app.mode = Mode::Edit;
+                app.fill_form(&reminder);
+            }
+        }
+        Action::Back if !app.marked.is_empty() => app.marked.clear(),
+        Action::Delete if !app.reminders.is_empty() => {
             app.mode = Mode::Delete;
         }
-        KeyCode::Up => app.prev(),
-        KeyCode::Down => app.next(),
+        Action::QuickAdd => {
+            app.mode = Mode::QuickAdd;
+            app.copying = None;
+            app.set_input(String::new());
+            app.error_msg = None;
+        }
+        Action::Undo => match app.history.undo(db) {
+            Ok(true) => {
+                app.notify("Undone");
+                events::publish(events, ReminderEvent::Changed);
+            }
+            Ok(false) => app.notify("Nothing to undo"),
+            Err(e) => show_error(app, "undo", e),
+        },
+        Action::Redo => match app.history.redo(db) {
+            Ok(true) => {
+                app.notify("Redone");
+                events::publish(events, ReminderEvent::Changed);
+            }
+            Ok(false) => app.notify("Nothing to redo"),
+            Err(e) => show_error(app, "redo", e),
+        },
+        Action::Restore if app.tab == Tab::Trash => {
+            let before: Vec<Reminder> = if app.marked.is_empty() {
+                app.reminders.get(app.selected_idx).cloned().into_iter().collect()
+            } else {
+                app.reminders.iter().filter(|r| app.marked.contains(&r.id)).cloned().collect()
+            };
+            match before.iter().try_for_each(|r| db.restore_from_trash(r.id)) {
+                Ok(()) if !before.is_empty() => {
+                    app.notify(format!("Restored {} reminder(s)", before.len()));
+                    let after = before.iter().cloned().map(|r| Reminder { deleted_at: None, ..r }).collect();
+                    app.history.record(before, after);
+                    app.marked.clear();
+                    events::publish(events, ReminderEvent::Changed);
+                }
+                Ok(()) => {}
+                Err(e) => {
+                    show_error(app, "restore", e);
+                    events::publish(events, ReminderEvent::Changed);
+                }
+            }
+        }
+        Action::Mark => app.toggle_mark(),
+        Action::Done if !app.marked.is_empty() => {
+            let ids: Vec<i32> = app.marked.iter().copied().collect();
+            let before: Vec<Reminder> = app.reminders.iter().filter(|r| app.marked.contains(&r.id)).cloned().collect();
+            // Reopen them if they're all done already, otherwise finish them all.
+            let all_done = before.iter().all(|r| r.completed_at.is_some());
+            let done = (!all_done).then(|| Local::now().format("%Y-%m-%d %H:%M").to_string());
+            match db.set_completed_many(&ids, done.as_deref()) {
+                Ok(()) => {
+                    let verb = if done.is_some() { "Completed" } else { "Reopened" };
+                    app.notify(format!("{} {} reminder(s)", verb, before.len()));
+                    let after = before.iter().cloned().map(|r| Reminder { completed_at: done.clone(), ..r }).collect();
+                    app.history.record(before, after);
+                    app.marked.clear();
+                    events::publish(events, ReminderEvent::Changed);
+                }
+                Err(e) => show_error(app, "update the marked reminders", e),
+            }
+        }
+        Action::Done => {
+            if let Some(reminder) = app.reminders.get(app.selected_idx).cloned() {
+                let done = reminder
+                    .completed_at
+                    .is_none()
+                    .then(|| Local::now().format("%Y-%m-%d %H:%M").to_string());
+                match db.set_completed(reminder.id, done.as_deref()) {
+                    Ok(()) => {
+                        let verb = if done.is_some() { "Completed" } else { "Reopened" };
+                        app.notify(format!("{} \"{}\"", verb, reminder.title));
+                        let after = Reminder { completed_at: done, ..reminder.clone() };
+                        app.history.record(vec![reminder], vec![after]);
+                        events::publish(events, ReminderEvent::Changed);
+                    }
+                    Err(e) => show_error(app, "update the reminder", e),
+                }
+            }
+        }
+        Action::Skip => {
+            if let Some(reminder) = app.reminders.get(app.selected_idx).cloned() {
+                skip_next(app, db, events, reminder);
+            }
+        }
+        Action::Occurrence => {
+            if let Some(reminder) = app.reminders.get(app.selected_idx).cloned() {
+                if reminder.date.is_some() {
+                    app.notify("Only repeating reminders have occurrences to change");
+                } else if let Some((day, _)) = schedule::next_occurrence(&reminder, Local::now().naive_local()) {
+                    app.edit_occurrence(reminder, day);
+                } else {
+                    app.notify(format!("\"{}\" doesn't go off again", reminder.title));
+                }
+            }
+        }
+        Action::NotifySettings => {
+            if let Some(reminder) = app.reminders.get(app.selected_idx).cloned() {
+                app.edit_settings(reminder);
+            }
+        }
+        Action::RaisePriority | Action::LowerPriority => {
+            let before: Vec<Reminder> = if app.marked.is_empty() {
+                app.reminders.get(app.selected_idx).cloned().into_iter().collect()
+            } else {
+                app.reminders.iter().filter(|r| app.marked.contains(&r.id)).cloned().collect()
+            };
+            change_priority(app, db, events, before, action == Action::RaisePriority);
+        }
+        Action::View(index) => switch_tab(app, db, Tab::ALL[index]),
+        Action::NextView => switch_tab(app, db, Tab::ALL[(app.tab.index() + 1) % Tab::ALL.len()]),
+        Action::PrevView => {
+            switch_tab(app, db, Tab::ALL[(app.tab.index() + Tab::ALL.len() - 1) % Tab::ALL.len()])
+        }
+        Action::Sort | Action::ReverseSort => {
+            // Sorting needs the whole tab, not just the pages seen so far.
+            load_more(app, db, app.total);
+            if action == Action::Sort {
+                app.cycle_sort();
+            } else {
+                app.reverse_sort();
+            }
+            if let Err(e) = config::save_sort(app.profile.as_deref(), app.sort, app.sort_desc) {
+                app.notify_error(format!("Couldn't save the sort order: {}", e));
+            }
+        }
+        Action::Copy => {
+            if let Some(reminder) = app.reminders.get(app.selected_idx).cloned() {
+                app.mode = Mode::Add;
+                app.fill_form(&reminder);
+                app.copying = Some(reminder);
+            }
+        }
+        Action::NextDue => select_next_due(app, db),
+        Action::Calendar => {
+            if let Some(day) = app.day_filter {
+                app.calendar_day = day;
+            }
+            app.mode = Mode::Calendar;
+            filter_by_day(app, db, None);
+        }
+        Action::Week => {
+            app.mode = Mode::Agenda;
+            filter_by_day(app, db, None);
+            app.calendar_day = Local::now().date_naive();
+        }
+        Action::Back if app.day_filter.is_some() => filter_by_day(app, db, None),
+        Action::Back if app.tag_filter.is_some() => filter_by_tag(app, db, None),
+        Action::Back if app.quick_filter.is_some() => filter_quickly(app, db, None),
+        // Pressing it again shows everything.
+        Action::Filter(filter) => filter_quickly(app, db, (app.quick_filter != Some(filter)).then_some(filter)),
+        Action::Tags => match db.tag_counts() {
+            Ok(counts) if counts.is_empty() => app.notify("No reminder has a tag yet"),
+            Ok(counts) => {
+                app.tag_idx = counts.iter().position(|(tag, _)| Some(tag) == app.tag_filter.as_ref()).unwrap_or(0);
+                app.tag_counts = counts;
+                app.mode = Mode::Tags;
+            }
+            Err(e) => show_error(app, "count tags", e),
+        },
+        Action::Lock => app.lock(),
+        Action::Mute => {
+            app.muted = !app.muted;
+            app.notify(if app.muted { "Notifications muted" } else { "Notifications unmuted" });
+            events::publish(events, ReminderEvent::Muted { muted: app.muted });
+        }
+        Action::Snooze if !app.reminders.is_empty() => {
+            app.snooze_idx = 0;
+            app.mode = Mode::Snooze;
+        }
+        Action::SnoozeDue => match worker::snooze_due(db, Local::now(), app.snooze_minutes) {
+            Ok(titles) if titles.is_empty() => app.notify("Nothing is due to snooze"),
+            Ok(titles) => {
+                app.notify(format!("Snoozed {} reminder(s) for {} min", titles.len(), app.snooze_minutes));
+                events::publish(events, ReminderEvent::Changed);
+            }
+            Err(e) => show_error(app, "snooze the due reminders", e),
+        },
+        Action::Log => {
+            app.mode = Mode::Logs;
+            app.log_scroll = 0;
+            load_log(app);
+        }
+        Action::History => {
+            app.mode = Mode::Notifications;
+            app.notifications_scroll = 0;
+            load_notifications(app, db);
+        }
+        Action::Profiles => {
+            app.profile_idx = app.profile_choices().iter().position(|p| *p == app.profile).unwrap_or(0);
+            app.error_msg = None;
+            app.mode = Mode::Profiles;
+        }
+        Action::Search => {
+            app.mode = Mode::Search;
+            app.set_input(app.search.clone());
+        }
+        Action::Back if !app.search.is_empty() => {
+            app.set_input(String::new());
+            search(app, db);
+        }
+        Action::Help => {
+            app.help_scroll = 0;
+            app.mode = Mode::Help;
+        }
+        Action::Up => app.prev(),
+        Action::Down => app.next(),
+        Action::PageUp => app.page_up(),
+        Action::PageDown => app.page_down(),
+        Action::First => app.first(),
+        Action::Last => {
+            load_more(app, db, app.total);
+            app.last();
+        }
         _ => {}
     }
+
+    load_ahead(app, db);
 }
 
-fn validate_time_format(time: &str) -> bool {
-    if time.len() != 5 || !time.contains(':') {
-        return false;
+/// Selects the reminder that goes off next: in the list as it is if it's
+/// there, loading the rest of a paged tab if need be, or else in Upcoming.
+fn select_next_due(app: &mut AppState, db: &dyn ReminderStore) {
+    count_due_soon(app, db);
+    let Some(id) = app.next_up.as_ref().map(|(next, _)| next.id) else {
+        app.notify("Nothing is coming up");
+        return;
+    };
+    if is_paged(app) && !app.reminders.iter().any(|r| r.id == id) {
+        load_more(app, db, app.total);
+    }
+    if !app.reminders.iter().any(|r| r.id == id) {
+        switch_tab(app, db, Tab::Upcoming);
     }
+    if let Some(idx) = app.reminders.iter().position(|r| r.id == id) {
+        app.selected_idx = idx;
+    }
+}
 
-    let parts: Vec<&str> = time.split(':').collect();
-    if parts.len() != 2 {
-        return false;
+/// Fetches the next page before the selection reaches the end of what's loaded.
+fn load_ahead(app: &mut AppState, db: &dyn ReminderStore) {
+    if is_paged(app) && app.selected_idx + app.page_size >= app.reminders.len() {
+        load_more(app, db, PAGE_SIZE);
+    }
+}
+
+fn handle_mouse(mouse: MouseEvent, app: &mut AppState, db: &dyn ReminderStore) {
+    match (app.mode, mouse.kind) {
+        (Mode::List, MouseEventKind::Down(MouseButton::Left)) => {
+            if let Some(idx) = app.row_at(mouse.column, mouse.row) {
+                app.selected_idx = idx;
+            }
+        }
+        (Mode::List, MouseEventKind::ScrollDown) => {
+            app.scroll(1);
+            load_ahead(app, db);
+        }
+        (Mode::List, MouseEventKind::ScrollUp) => app.scroll(-1),
+        (Mode::Add | Mode::Edit, MouseEventKind::Down(MouseButton::Left)) if app.discarding.is_none() => {
+            if let Some(field) = app.field_at(mouse.column, mouse.row) {
+                app.focus_field(field);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Shows the reminders matching what's typed so far, across every tab but
+/// the trash; an empty search goes back to the whole list.
+fn search(app: &mut AppState, db: &dyn ReminderStore) {
+    app.search = app.input.trim().to_string();
+    app.tab = Tab::All;
+    app.day_filter = None;
+    app.tag_filter = None;
+    app.quick_filter = None;
+    app.selected_idx = 0;
+    app.reminders.clear();
+    load_reminders(app, db);
+}
+
+fn handle_profiles_input(key: KeyEvent, app: &mut AppState, db: &SharedStore, events: &EventBus) {
+    let choices = app.profile_choices();
+    match keymap::PROFILES.action(&key) {
+        Some(Action::Up) => app.profile_idx = (app.profile_idx + choices.len() - 1) % choices.len(),
+        Some(Action::Down) => app.profile_idx = (app.profile_idx + 1) % choices.len(),
+        Some(Action::Confirm) => {
+            let profile = choices[app.profile_idx].clone();
+            if profile == app.profile {
+                app.mode = Mode::List;
+            } else {
+                match switch_profile(app, db, profile) {
+                    Ok(()) => {
+                        app.mode = Mode::List;
+                        app.notify(format!("Switched to the {} profile", app.profile.as_deref().unwrap_or("default")));
+                        events::publish(events, ReminderEvent::ProfileSwitched { profile: app.profile.clone() });
+                    }
+                    Err(e) => app.error_msg = Some(e.to_string()),
+                }
+            }
+        }
+        Some(Action::Back) => {
+            app.error_msg = None;
+            app.mode = Mode::List;
+        }
+        _ => {}
     }
+}
 
-    if let (Ok(hour), Ok(minute)) = (parts[0].parse::<u32>(), parts[1].parse::<u32>()) {
-        hour < 24 && minute < 60
+fn handle_tags_input(key: KeyEvent, app: &mut AppState, db: &dyn ReminderStore) {
+    let count = app.tag_counts.len();
+    match keymap::TAGS.action(&key) {
+        Some(Action::Up) => app.tag_idx = (app.tag_idx + count - 1) % count,
+        Some(Action::Down) => app.tag_idx = (app.tag_idx + 1) % count,
+        Some(Action::Confirm) => {
+            app.mode = Mode::List;
+            let tag = app.tag_counts[app.tag_idx].0.clone();
+            filter_by_tag(app, db, Some(tag));
+        }
+        Some(Action::Back) => app.mode = Mode::List,
+        _ => {}
+    }
+}
+
+/// Scrolls the key help; `draw_help` keeps it from scrolling past the end.
+fn handle_help_input(key: KeyEvent, app: &mut AppState) {
+    match keymap::HELP.action(&key) {
+        Some(Action::Up) => app.help_scroll = app.help_scroll.saturating_sub(1),
+        Some(Action::Down) => app.help_scroll += 1,
+        Some(Action::PageUp) => app.help_scroll = app.help_scroll.saturating_sub(app.page_size),
+        Some(Action::PageDown) => app.help_scroll += app.page_size,
+        Some(Action::First) => app.help_scroll = 0,
+        Some(Action::Last) => app.help_scroll = usize::MAX,
+        Some(Action::Back) => app.mode = Mode::List,
+        _ => {}
+    }
+}
+
+fn handle_snooze_input(key: KeyEvent, app: &mut AppState, db: &dyn ReminderStore, events: &EventBus) {
+    let count = app.snooze_presets.len();
+    let pick = match keymap::SNOOZE.action(&key) {
+        Some(Action::Up) => {
+            app.snooze_idx = (app.snooze_idx + count - 1) % count;
+            None
+        }
+        Some(Action::Down) => {
+            app.snooze_idx = (app.snooze_idx + 1) % count;
+            None
+        }
+        Some(Action::Confirm) => Some(app.snooze_idx),
+        Some(Action::Pick(i)) => Some(i).filter(|&i| i < count),
+        Some(Action::Back) => {
+            app.mode = Mode::List;
+            None
+        }
+        _ => None,
+    };
+    let Some(pick) = pick else {
+        return;
+    };
+    app.mode = Mode::List;
+
+    let now = Local::now().naive_local();
+    let Some(until) = snooze_until(&app.snooze_presets[pick], now) else {
+        app.notify_error(format!("Can't snooze for {:?}", app.snooze_presets[pick]));
+        return;
+    };
+    let targets: Vec<Reminder> = if app.marked.is_empty() {
+        app.reminders.get(app.selected_idx).cloned().into_iter().collect()
     } else {
-        false
+        app.reminders.iter().filter(|r| app.marked.contains(&r.id)).cloned().collect()
+    };
+    let stored = until.format("%Y-%m-%d %H:%M").to_string();
+    match targets.iter().try_for_each(|r| db.snooze_reminder(r.id, &stored)) {
+        Ok(()) => {
+            let when = if until.date() == now.date() {
+                app.time_format.format(until.time())
+            } else {
+                format!("{} {}", until.format("%a %b %-d"), app.time_format.format(until.time()))
+            };
+            match targets.as_slice() {
+                [reminder] => app.notify(format!("Snoozed \"{}\" until {}", reminder.title, when)),
+                _ => app.notify(format!("Snoozed {} reminder(s) until {}", targets.len(), when)),
+            }
+            app.marked.clear();
+        }
+        Err(e) => show_error(app, "snooze", e),
     }
+    events::publish(events, ReminderEvent::Changed);
 }
 
-fn handle_form_input(key: KeyEvent, app: &mut AppState, db: &Database, is_add: bool) {
+/// Opens `profile`'s store in place of the current one, so the worker and
+/// servers sharing `db` follow along, and starts the list afresh.
+fn switch_profile(app: &mut AppState, db: &SharedStore, profile: Option<String>) -> Result<(), Box<dyn Error>> {
+    let config = Config::load(profile.as_deref())?;
+    if matches!(config.storage, Storage::Sqlcipher) && std::env::var_os("REMINDER_PASSPHRASE").is_none() {
+        return Err("set REMINDER_PASSPHRASE to switch to an encrypted profile".into());
+    }
+    db.replace(open_store(&config)?);
+
+    app.snooze_minutes = config.snooze_all_minutes();
+    app.snooze_presets = config.snooze_choices();
+    app.overdue_first = config.overdue_first;
+    app.tag_colors = config.tag_colors();
+    app.key_preset = config.keymap;
+    app.sections = config.sections;
+    app.sort = config.sort;
+    app.sort_desc = config.sort_descending;
+    app.profile = config.profile;
+    app.profiles = config.profiles;
+    app.time_format = config.time_format;
+    app.lock = config.lock;
+    // Undoing would replay the old profile's changes on this one.
+    app.history = Default::default();
+    app.marked.clear();
+    app.search.clear();
+    app.day_filter = None;
+    app.tag_filter = None;
+    app.quick_filter = None;
+    app.selected_idx = 0;
+    app.error_msg = None;
+    load_reminders(app, db);
+    Ok(())
+}
+
+fn handle_lock_input(key: KeyEvent, app: &mut AppState) {
     match key.code {
-        KeyCode::Char(c) => app.input.push(c),
+        KeyCode::Enter => app.try_unlock(),
+        KeyCode::Char(c) => app.unlock_input.push(c),
         KeyCode::Backspace => {
-            app.input.pop();
-        }
-        KeyCode::Tab => app.next_field(),
-        KeyCode::BackTab => app.prev_field(),
-        KeyCode::Esc => app.mode = Mode::List,
-        KeyCode::Enter => {
-            app.form_fields[app.input_field] = app.input.clone();
-
-            if app.form_fields[0].is_empty()
-                || app.form_fields[1].is_empty()
-                || app.form_fields[2].is_empty()
-            {
-                app.error_msg = Some("All fields must be filled".to_string());
-                return;
+            app.unlock_input.pop();
+        }
+        KeyCode::Esc => app.unlock_input.clear(),
+        _ => {}
+    }
+}
+
+fn handle_search_input(key: KeyEvent, app: &mut AppState, db: &dyn ReminderStore) {
+    match keymap::SEARCH.action(&key) {
+        Some(Action::Confirm) => app.mode = Mode::List,
+        Some(Action::Back) => {
+            app.mode = Mode::List;
+            app.set_input(String::new());
+            search(app, db);
+        }
+        Some(Action::Up) => app.prev(),
+        Some(Action::Down) => app.next(),
+        Some(action) => {
+            if edit_text(app, action) {
+                search(app, db);
             }
+        }
+        None => {
+            if type_text(app, &key) {
+                search(app, db);
+            }
+        }
+    }
+}
 
-            if !validate_time_format(&app.form_fields[2]) {
-                app.error_msg = Some("Invalid time format. Use HH:MM (e.g., 06:59)".to_string());
-                return;
+/// Moves the cursor or deletes for the text-editing actions, saying whether
+/// the text changed.
+fn edit_text(app: &mut AppState, action: Action) -> bool {
+    match action {
+        Action::Left => app.cursor_left(),
+        Action::Right => app.cursor_right(),
+        Action::First => app.cursor_home(),
+        Action::Last => app.cursor_end(),
+        Action::DeleteBack => {
+            app.backspace();
+            return true;
+        }
+        Action::DeleteForward => {
+            app.delete_char();
+            return true;
+        }
+        _ => {}
+    }
+    false
+}
+
+/// Types the character of a key with no binding, saying whether it did.
+fn type_text(app: &mut AppState, key: &KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Char(c) => {
+            app.insert_char(c);
+            true
+        }
+        _ => false,
+    }
+}
+
+fn handle_agenda_input(key: KeyEvent, app: &mut AppState) {
+    match keymap::AGENDA.action(&key) {
+        Some(Action::Left) => app.calendar_day -= Duration::days(7),
+        Some(Action::Right) => app.calendar_day += Duration::days(7),
+        Some(Action::Today) => app.calendar_day = Local::now().date_naive(),
+        Some(Action::Back) => app.mode = Mode::List,
+        _ => {}
+    }
+}
+
+/// Scrolls the notification history; the newest are at the top.
+fn handle_notifications_input(key: KeyEvent, app: &mut AppState) {
+    match keymap::NOTIFICATIONS.action(&key) {
+        Some(Action::Up) => app.notifications_scroll = app.notifications_scroll.saturating_sub(1),
+        Some(Action::Down) => app.notifications_scroll += 1,
+        Some(Action::PageUp) => app.notifications_scroll = app.notifications_scroll.saturating_sub(app.page_size),
+        Some(Action::PageDown) => app.notifications_scroll += app.page_size,
+        Some(Action::First) => app.notifications_scroll = 0,
+        Some(Action::Last) => app.notifications_scroll = app.notifications.len(),
+        Some(Action::Back) => app.mode = Mode::List,
+        _ => {}
+    }
+}
+
+fn load_notifications(app: &mut AppState, db: &dyn ReminderStore) {
+    match db.notification_log(NOTIFICATION_HISTORY) {
+        Ok(notifications) => app.notifications = notifications,
+        Err(e) => show_error(app, "load the notification history", e),
+    }
+}
+
+fn handle_missed_input(key: KeyEvent, app: &mut AppState) {
+    if keymap::MISSED.action(&key) == Some(Action::Back) {
+        app.missed.clear();
+        app.mode = Mode::List;
+    }
+}
+
+/// Scrolls the log; the newest lines are at the bottom.
+fn handle_logs_input(key: KeyEvent, app: &mut AppState) {
+    match keymap::LOGS.action(&key) {
+        Some(Action::Up) => app.log_scroll += 1,
+        Some(Action::Down) => app.log_scroll = app.log_scroll.saturating_sub(1),
+        Some(Action::PageUp) => app.log_scroll += app.page_size,
+        Some(Action::PageDown) => app.log_scroll = app.log_scroll.saturating_sub(app.page_size),
+        Some(Action::First) => app.log_scroll = app.log.len(),
+        Some(Action::Last) => app.log_scroll = 0,
+        Some(Action::Back) => app.mode = Mode::List,
+        _ => {}
+    }
+}
+
+/// Reads the end of the newest log file into the log view.
+fn load_log(app: &mut AppState) {
+    match logging::latest() {
+        Ok(Some(path)) => match logging::tail(&path, LOG_LINES) {
+            Ok(lines) => {
+                app.log = lines;
+                app.log_path = Some(path);
+            }
+            Err(e) => app.notify_error(format!("Couldn't read {}: {}", path.display(), e)),
+        },
+        Ok(None) => {
+            app.log.clear();
+            app.log_path = None;
+        }
+        Err(e) => app.notify_error(format!("Couldn't read the logs: {}", e)),
+    }
+}
+
+fn handle_calendar_input(key: KeyEvent, app: &mut AppState, db: &dyn ReminderStore) {
+    let day = app.calendar_day;
+    match keymap::CALENDAR.action(&key) {
+        Some(Action::Left) => app.calendar_day = day - Duration::days(1),
+        Some(Action::Right) => app.calendar_day = day + Duration::days(1),
+        Some(Action::Up) => app.calendar_day = day - Duration::days(7),
+        Some(Action::Down) => app.calendar_day = day + Duration::days(7),
+        Some(Action::PageUp) => app.calendar_day = day.checked_sub_months(Months::new(1)).unwrap_or(day),
+        Some(Action::PageDown) => app.calendar_day = day.checked_add_months(Months::new(1)).unwrap_or(day),
+        Some(Action::Today) => app.calendar_day = Local::now().date_naive(),
+        Some(Action::Confirm) => {
+            filter_by_day(app, db, Some(day));
+            app.mode = Mode::List;
+        }
+        Some(Action::Back) => app.mode = Mode::List,
+        _ => {}
+    }
+}
+
+/// Whether `time` is `HH:MM`, or `HH:MM:SS` for a reminder that should go
+/// off on the second.
+fn validate_time_format(time: &str) -> bool {
+    let parts: Vec<&str> = time.split(':').collect();
+    if !matches!(parts.len(), 2 | 3) || parts.iter().any(|part| part.len() != 2) {
+        return false;
+    }
+
+    match parts.iter().map(|part| part.parse::<u32>()).collect::<Result<Vec<u32>, _>>() {
+        Ok(numbers) => numbers[0] < 24 && numbers[1..].iter().all(|&n| n < 60),
+        Err(_) => false,
+    }
+}
+
+fn validate_date_format(date: &str) -> bool {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok()
+}
+
+/// Splits `work, #family home` into lowercase tags without the `#`.
+fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in input.split(|c: char| c == ',' || c.is_whitespace()) {
+        let tag = tag.trim_start_matches('#').to_lowercase();
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// Parses a comma-separated list of alert offsets such as `1h,10m,0` into
+/// minutes before the reminder time. Bare numbers are minutes; an empty
+/// string means a single alert at the time itself.
+fn parse_alerts(input: &str) -> Option<Vec<i64>> {
+    let mut alerts = Vec::new();
+    for part in input.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let minutes = if let Some(hours) = part.strip_suffix('h') {
+            hours.trim().parse::<i64>().ok()? * 60
+        } else {
+            part.trim_end_matches('m').trim().parse::<i64>().ok()?
+        };
+        if !(0..24 * 60).contains(&minutes) {
+            return None;
+        }
+        alerts.push(minutes);
+    }
+
+    if alerts.is_empty() {
+        alerts.push(0);
+    }
+    alerts.sort_unstable_by(|a, b| b.cmp(a));
+    alerts.dedup();
+    Some(alerts)
+}
+
+/// Parses an interval such as `45m`, `2h` or `90m until 17:00` into minutes
+/// and an optional end of the window, stored as `HH:MM`.
+fn parse_every(input: &str, time_format: TimeFormat) -> Option<(i64, Option<String>)> {
+    let (every, until) = match input.split_once("until") {
+        Some((every, until)) => (every.trim(), Some(until.trim())),
+        None => (input.trim(), None),
+    };
+    let minutes = if let Some(hours) = every.strip_suffix('h') {
+        hours.trim().parse::<i64>().ok()? * 60
+    } else {
+        every.trim_end_matches('m').trim().parse::<i64>().ok()?
+    };
+    if !(1..=24 * 60).contains(&minutes) {
+        return None;
+    }
+    match until {
+        Some(until) => Some((minutes, Some(time_format.parse(until)?))),
+        None => Some((minutes, None)),
+    }
+}
+
+fn handle_form_input(
+    key: KeyEvent,
+    app: &mut AppState,
+    db: &dyn ReminderStore,
+    events: &EventBus,
+    is_add: bool,
+) {
+    if let Some(discard) = app.discarding {
+        match keymap::DISCARD_CONFIRM.action(&key) {
+            Some(Action::Confirm) if discard == Discard::Quit => app.quit = true,
+            Some(Action::Confirm) => app.mode = Mode::List,
+            Some(Action::Back) => {}
+            _ => return,
+        }
+        app.discarding = None;
+        return;
+    }
+    if app.date_picker.is_some() {
+        handle_date_picker_input(key, app);
+        return;
+    }
+
+    let action = keymap::FORM.action(&key);
+    if !matches!(action, Some(Action::CompleteNext | Action::CompletePrev)) {
+        app.clear_completions();
+    }
+
+    match action {
+        Some(Action::Save) => save_form(app, db, events, is_add),
+        Some(Action::CompleteNext) => complete(app, db, true),
+        Some(Action::CompletePrev) => complete(app, db, false),
+        // The time field doubles as a picker.
+        Some(Action::Up) if app.input_field == 2 => app.step_time(true),
+        Some(Action::Down) if app.input_field == 2 => app.step_time(false),
+        Some(Action::Left) if app.input_field == 2 && app.typed_time().is_some() => app.select_time_part(false),
+        Some(Action::Right) if app.input_field == 2 && app.typed_time().is_some() => app.select_time_part(true),
+        Some(Action::Down) if app.input_field == 3 => app.open_date_picker(),
+        Some(Action::NextField) => app.next_field(),
+        Some(Action::PrevField) => app.prev_field(),
+        Some(Action::Back) if app.form_changed() => app.discarding = Some(Discard::Cancel),
+        Some(Action::Back) => app.mode = Mode::List,
+        // The description takes several lines; Ctrl+S saves from it.
+        Some(Action::Confirm) if app.input_field == 1 => app.insert_char('\n'),
+        Some(Action::Confirm) => save_form(app, db, events, is_add),
+        Some(action) => {
+            edit_text(app, action);
+        }
+        None => {
+            type_text(app, &key);
+        }
+    }
+}
+
+fn handle_date_picker_input(key: KeyEvent, app: &mut AppState) {
+    let Some(day) = app.date_picker else {
+        return;
+    };
+    let moved = match keymap::DATE_PICKER.action(&key) {
+        Some(Action::Left) => day - Duration::days(1),
+        Some(Action::Right) => day + Duration::days(1),
+        Some(Action::Up) => day - Duration::days(7),
+        Some(Action::Down) => day + Duration::days(7),
+        Some(Action::PageUp) => day.checked_sub_months(Months::new(1)).unwrap_or(day),
+        Some(Action::PageDown) => day.checked_add_months(Months::new(1)).unwrap_or(day),
+        Some(Action::Today) => Local::now().date_naive(),
+        Some(Action::Confirm) => {
+            app.set_input(day.format("%Y-%m-%d").to_string());
+            app.date_picker = None;
+            return;
+        }
+        Some(Action::Back) => {
+            app.date_picker = None;
+            return;
+        }
+        _ => day,
+    };
+    app.date_picker = Some(moved);
+}
+
+/// Fills the title or tags field with earlier ones starting with what has
+/// been typed, cycling through them on each press.
+fn complete(app: &mut AppState, db: &dyn ReminderStore, forward: bool) {
+    if app.completions.is_empty() {
+        let Some(prefix) = app.completion_prefix().map(str::to_string) else {
+            return;
+        };
+        let suggestions = if app.input_field == 0 {
+            db.title_suggestions(&prefix, 8)
+        } else {
+            // Don't offer tags the field already has.
+            let taken = parse_tags(&app.input);
+            db.tag_suggestions(&prefix, 8 + taken.len())
+                .map(|tags| tags.into_iter().filter(|t| !taken.contains(t)).take(8).collect())
+        };
+        app.start_completion(suggestions.unwrap_or_default());
+    }
+    app.cycle_completion(forward);
+}
+
+fn save_form(app: &mut AppState, db: &dyn ReminderStore, events: &EventBus, is_add: bool) {
+    app.form_fields[app.input_field] = app.input.clone();
+
+    let new = match form::validate(&app.form_fields, app.time_format) {
+        Ok(new) => new,
+        Err(errors) => {
+            // Show what's wrong under every field, not just the ones visited.
+            app.touched = [true; ui::FORM_FIELD_COUNT];
+            app.error_msg = errors.first().map(|(_, message)| message.clone());
+            return;
+        }
+    };
+
+    if is_add {
+        let new = match &app.copying {
+            Some(source) => new.with_settings_of(source),
+            None => new,
+        };
+        add_or_ask(app, db, events, new);
+    } else if let Some(selected) = app.reminders.get(app.selected_idx).cloned() {
+        let mut updated = selected.clone();
+        updated.apply(new.with_settings_of(&selected));
+
+        // Saving an untouched form leaves the reminder (and the undo history) alone.
+        if updated == selected {
+            app.mode = Mode::List;
+            app.error_msg = None;
+            return;
+        }
+        // Only what was changed in the form is written over the stored
+        // reminder, and the history gets it as it was and is stored.
+        let saved = db.get_reminder(selected.id).and_then(|stored| {
+            let before = stored.unwrap_or_else(|| selected.clone());
+            let mut after = before.clone();
+            after.apply_changes(&selected, &updated);
+            db.update_reminder(&after)?;
+            Ok((before, db.get_reminder(selected.id)?.unwrap_or(after)))
+        });
+        match saved {
+            Ok((before, after)) => {
+                app.history.record(vec![before], vec![after.clone()]);
+                app.reminders[app.selected_idx] = after;
+                app.notify("Reminder saved");
+                app.mode = Mode::List;
+                app.error_msg = None;
+                events::publish(events, ReminderEvent::Changed);
             }
+            Err(e) => show_error(app, "save the reminder", e),
+        }
+    }
+}
 
-            let title = app.form_fields[0].clone();
-            let description = app.form_fields[1].clone();
-            let time = app.form_fields[2].clone();
-
-            if is_add {
-                if let Ok(reminder) = db.add_reminder(title, description, time) {
-                    app.reminders.push(reminder);
-                    app.mode = Mode::List;
-                    app.error_msg = None;
-                }
-            } else if let Some(selected) = app.reminders.get(app.selected_idx) {
-                let id = selected.id;
-                if db
-                    .update_reminder(id, title.clone(), description.clone(), time.clone())
-                    .is_ok()
-                {
-                    if let Some(reminder) = app.reminders.get_mut(app.selected_idx) {
-                        reminder.title = title;
-                        reminder.description = description;
-                        reminder.time = time;
+/// Adds `new`, unless it looks like a reminder already there, in which case
+/// asks whether to skip it, replace that one or keep both.
+fn add_or_ask(app: &mut AppState, db: &dyn ReminderStore, events: &EventBus, new: NewReminder) {
+    match db.find_duplicate(&new) {
+        Ok(Some(existing)) => {
+            app.duplicate = Some((new, existing));
+            app.mode = Mode::Duplicate;
+            app.error_msg = None;
+        }
+        Ok(None) => add(app, db, events, new),
+        Err(e) => show_error(app, "check for duplicates", e),
+    }
+}
+
+fn add(app: &mut AppState, db: &dyn ReminderStore, events: &EventBus, new: NewReminder) {
+    // A copy keeps the days its original goes off differently on.
+    let added = db.add_reminder(new).and_then(|reminder| {
+        let exceptions = app.copying.take().map(|source| source.exceptions).unwrap_or_default();
+        if exceptions.is_empty() {
+            return Ok(reminder);
+        }
+        for exception in &exceptions {
+            db.add_exception(reminder.id, exception)?;
+        }
+        Ok(db.get_reminder(reminder.id)?.unwrap_or(reminder))
+    });
+    match added {
+        Ok(reminder) => {
+            app.notify(format!("Added \"{}\"", reminder.title));
+            app.history.record(Vec::new(), vec![reminder.clone()]);
+            app.reminders.push(reminder);
+            app.mode = Mode::List;
+            app.error_msg = None;
+            events::publish(events, ReminderEvent::Changed);
+        }
+        Err(e) => show_error(app, "add the reminder", e),
+    }
+}
+
+fn handle_duplicate_input(key: KeyEvent, app: &mut AppState, db: &dyn ReminderStore, events: &EventBus) {
+    let Some((new, existing)) = app.duplicate.take() else {
+        app.mode = Mode::List;
+        return;
+    };
+    match keymap::DUPLICATE.action(&key) {
+        Some(Action::Back) => app.mode = Mode::List,
+        Some(Action::Replace) => {
+            let mut replaced = existing.clone();
+            replaced.apply(new.with_settings_of(&existing));
+            match db.update_reminder(&replaced) {
+                Ok(()) => {
+                    app.notify(format!("Replaced \"{}\"", existing.title));
+                    app.history.record(vec![existing], vec![replaced.clone()]);
+                    if let Some(shown) = app.reminders.iter_mut().find(|r| r.id == replaced.id) {
+                        *shown = replaced;
                     }
-                    app.mode = Mode::List;
-                    app.error_msg = None;
+                    events::publish(events, ReminderEvent::Changed);
                 }
+                Err(e) => show_error(app, "replace the reminder", e),
             }
+            app.mode = Mode::List;
         }
-        _ => {}
+        Some(Action::KeepBoth) => add(app, db, events, new),
+        _ => app.duplicate = Some((new, existing)),
     }
 }
 
-fn handle_delete_input(key: KeyEvent, app: &mut AppState, db: &Database) {
-    match key.code {
-        KeyCode::Char('y') => {
-            if let Some(reminder) = app.reminders.get(app.selected_idx) {
-                let id = reminder.id;
-                if db.delete_reminder(id).is_ok() {
-                    app.reminders.remove(app.selected_idx);
-                    if app.selected_idx > 0 && app.selected_idx >= app.reminders.len() {
-                        app.selected_idx -= 1;
+fn handle_quick_add_input(key: KeyEvent, app: &mut AppState, db: &dyn ReminderStore, events: &EventBus) {
+    match keymap::QUICK_ADD.action(&key) {
+        Some(Action::Back) => app.mode = Mode::List,
+        Some(Action::Confirm) => match quick_add::parse(&app.input, Local::now().naive_local()) {
+            Ok(parsed) => {
+                let new = NewReminder {
+                    title: parsed.title,
+                    time: schedule::time_text(parsed.time),
+                    date: parsed.date.map(|d| d.format("%Y-%m-%d").to_string()),
+                    alerts: vec![0],
+                    tags: parsed.tags,
+                    ..Default::default()
+                };
+                app.set_input(String::new());
+                add_or_ask(app, db, events, new);
+            }
+            Err(e) => app.error_msg = Some(e),
+        },
+        Some(action) => {
+            edit_text(app, action);
+        }
+        None => {
+            type_text(app, &key);
+        }
+    }
+}
+
+fn handle_occurrence_input(key: KeyEvent, app: &mut AppState, db: &dyn ReminderStore, events: &EventBus) {
+    let field = app.occurrence.as_ref().map_or(0, |edit| edit.field);
+    match keymap::POPUP_FORM.action(&key) {
+        Some(Action::NextField) => app.focus_occurrence_field(field + 1),
+        Some(Action::PrevField) => app.focus_occurrence_field(field + form::OCCURRENCE_FIELD_COUNT - 1),
+        Some(Action::Back) => {
+            app.occurrence = None;
+            app.mode = Mode::List;
+        }
+        Some(Action::Confirm) => {
+            app.focus_occurrence_field(field);
+            if let Some(edit) = &app.occurrence {
+                match form::validate_occurrence(&edit.fields, app.time_format) {
+                    Ok(exception) => {
+                        let reminder = edit.reminder.clone();
+                        save_occurrence(app, db, events, reminder, exception);
+                    }
+                    Err((field, message)) => {
+                        app.error_msg = Some(message);
+                        app.focus_occurrence_field(field);
                     }
-                    app.mode = Mode::List;
                 }
             }
         }
-        KeyCode::Char('n') | KeyCode::Esc => app.mode = Mode::List,
-        _ => {}
+        Some(action) => {
+            edit_text(app, action);
+        }
+        None => {
+            type_text(app, &key);
+        }
     }
 }
 
-fn notification_worker(notified_ids: Arc<Mutex<HashSet<i32>>>) {
-    loop {
-        std::thread::sleep(std::time::Duration::from_secs(30));
-
-        if let Ok(db) = Database::new("reminders.db") {
-            if let Ok(reminders) = db.get_all_reminders() {
-                let now = Local::now();
-                let current_time = now.format("%H:%M").to_string();
-
-                for reminder in reminders {
-                    let mut notified = notified_ids.lock().unwrap();
-
-                    if reminder.time == current_time && !notified.contains(&reminder.id) {
-                        match Notification::new()
-                            .summary(&reminder.title)
-                            .body(&reminder.description)
-                            .timeout(5000)
-                            .show()
-                        {
-                            Ok(_) => {
-                                notified.insert(reminder.id);
+/// Writes what the occurrence editor describes. A day set back to its usual
+/// time without a note loses its exception instead.
+fn save_occurrence(app: &mut AppState, db: &dyn ReminderStore, events: &EventBus, reminder: Reminder, exception: Exception) {
+    let day = NaiveDate::parse_from_str(&exception.date, "%Y-%m-%d").unwrap_or_default();
+    let usual = schedule::usual_time(&reminder, day).map(schedule::time_text);
+    let mut after = reminder.clone();
+    let (result, message) = if exception.note.is_none() && exception.time.is_some() && exception.time == usual {
+        after.remove_exception(&exception.date);
+        let result = db.remove_exception(reminder.id, &exception.date);
+        (result, format!("\"{}\" goes off as usual on {}", reminder.title, exception.date))
+    } else {
+        let message = match &exception.time {
+            None => format!("Skipped \"{}\" on {}", reminder.title, exception.date),
+            Some(time) => format!("\"{}\" goes off at {} on {}", reminder.title, app.time_format.render(time), exception.date),
+        };
+        after.set_exception(exception.clone());
+        (db.add_exception(reminder.id, &exception), message)
+    };
+    match result {
+        Ok(()) => {
+            app.notify(message);
+            if after.exceptions != reminder.exceptions {
+                app.history.record(vec![reminder], vec![after]);
+            }
+            app.occurrence = None;
+            app.mode = Mode::List;
+            events::publish(events, ReminderEvent::Changed);
+        }
+        Err(e) => show_error(app, "change the occurrence", e),
+    }
+}
+
+fn handle_settings_input(key: KeyEvent, app: &mut AppState, db: &dyn ReminderStore, events: &EventBus) {
+    let field = app.settings.as_ref().map_or(0, |edit| edit.field);
+    match keymap::POPUP_FORM.action(&key) {
+        Some(Action::NextField) => app.focus_settings_field(field + 1),
+        Some(Action::PrevField) => app.focus_settings_field(field + form::SETTINGS_FIELD_COUNT - 1),
+        Some(Action::Back) => {
+            app.settings = None;
+            app.mode = Mode::List;
+        }
+        Some(Action::Confirm) => {
+            app.focus_settings_field(field);
+            let Some(edit) = &app.settings else {
+                return;
+            };
+            match form::validate_settings(&edit.fields, &edit.reminder) {
+                Ok(after) => {
+                    let before = edit.reminder.clone();
+                    match db.set_notification_settings(after.id, after.icon.as_deref(), after.category.as_deref(), after.resident) {
+                        Ok(()) => {
+                            app.notify(format!("Saved how \"{}\" notifies", after.title));
+                            if after != before {
+                                app.history.record(vec![before], vec![after]);
                             }
-                            Err(e) => println!("Failed to send notification: {}", e),
+                            app.settings = None;
+                            app.mode = Mode::List;
+                            events::publish(events, ReminderEvent::Changed);
                         }
+                        Err(e) => show_error(app, "save the notification settings", e),
                     }
                 }
+                Err((field, message)) => {
+                    app.error_msg = Some(message);
+                    app.focus_settings_field(field);
+                }
             }
         }
+        Some(action) => {
+            edit_text(app, action);
+        }
+        None => {
+            type_text(app, &key);
+        }
+    }
+}
+
+fn handle_delete_input(key: KeyEvent, app: &mut AppState, db: &dyn ReminderStore, events: &EventBus) {
+    // Deleting from the trash removes reminders for good; elsewhere it moves them to the trash.
+    let purge = app.tab == Tab::Trash;
+    let remove = |ids: &[i32]| if purge { db.purge_reminders(ids) } else { db.delete_reminders(ids) };
+    let removed = |what: String| if purge { format!("Deleted {}", what) } else { format!("Moved {} to the trash", what) };
+    let deleted_at = Local::now().format("%Y-%m-%d %H:%M").to_string();
+    let after = |before: &[Reminder]| -> Vec<Reminder> {
+        if purge {
+            Vec::new()
+        } else {
+            before.iter().cloned().map(|r| Reminder { deleted_at: Some(deleted_at.clone()), ..r }).collect()
+        }
+    };
+
+    match keymap::DELETE_CONFIRM.action(&key) {
+        Some(Action::Confirm) if !app.marked.is_empty() => {
+            let ids: Vec<i32> = app.marked.iter().copied().collect();
+            match remove(&ids) {
+                Ok(()) => {
+                    app.notify(removed(format!("{} reminder(s)", ids.len())));
+                    let before: Vec<Reminder> =
+                        app.reminders.iter().filter(|r| app.marked.contains(&r.id)).cloned().collect();
+                    app.history.record(before.clone(), after(&before));
+                    app.marked.clear();
+                    events::publish(events, ReminderEvent::Changed);
+                }
+                Err(e) => show_error(app, "delete the marked reminders", e),
+            }
+            app.mode = Mode::List;
+        }
+        Some(Action::Confirm) => {
+            if let Some(reminder) = app.reminders.get(app.selected_idx) {
+                let id = reminder.id;
+                match remove(&[id]) {
+                    Ok(()) => {
+                        let deleted = app.reminders.remove(app.selected_idx);
+                        app.notify(removed(format!("\"{}\"", deleted.title)));
+                        let after = after(std::slice::from_ref(&deleted));
+                        app.history.record(vec![deleted], after);
+                        if app.selected_idx > 0 && app.selected_idx >= app.reminders.len() {
+                            app.selected_idx -= 1;
+                        }
+                        events::publish(events, ReminderEvent::Changed);
+                    }
+                    Err(e) => show_error(app, "delete the reminder", e),
+                }
+                app.mode = Mode::List;
+            }
+        }
+        Some(Action::Back) => app.mode = Mode::List,
+        _ => {}
     }
 }
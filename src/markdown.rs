@@ -0,0 +1,109 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+
+/// Renders the bits of markdown people use in notes: `**bold**`, `` `code` ``,
+/// `[links](url)` and `-`/`*`/`1.` lists. Anything else is shown as written.
+pub fn render(text: &str) -> Vec<Line<'static>> {
+    text.lines().map(render_line).collect()
+}
+
+fn render_line(line: &str) -> Line<'static> {
+    let indent = line.len() - line.trim_start().len();
+    let rest = &line[indent..];
+
+    let mut spans = Vec::new();
+    let body = if let Some(item) = ["- ", "* ", "+ "].iter().find_map(|bullet| rest.strip_prefix(bullet)) {
+        spans.push(Span::raw(format!("{}• ", " ".repeat(indent))));
+        item
+    } else if let Some((number, item)) = rest.split_once(". ").filter(|(n, _)| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit())) {
+        spans.push(Span::raw(format!("{}{}. ", " ".repeat(indent), number)));
+        item
+    } else {
+        line
+    };
+
+    spans.extend(render_inline(body));
+    Line::from(spans)
+}
+
+fn render_inline(text: &str) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        let styled = if let Some((bold, after)) = rest.strip_prefix("**").and_then(|r| r.split_once("**")).filter(|(b, _)| !b.is_empty()) {
+            Some((vec![Span::styled(bold.to_string(), Style::default().add_modifier(Modifier::BOLD))], after))
+        } else if let Some((code, after)) = rest.strip_prefix('`').and_then(|r| r.split_once('`')).filter(|(c, _)| !c.is_empty()) {
+            Some((vec![Span::styled(code.to_string(), Style::default().fg(Color::Yellow))], after))
+        } else if let Some((label, url, after)) = rest
+            .strip_prefix('[')
+            .and_then(|r| r.split_once("]("))
+            .and_then(|(label, r)| r.split_once(')').map(|(url, after)| (label, url, after)))
+        {
+            Some((
+                vec![
+                    Span::styled(label.to_string(), Style::default().fg(Color::Blue).add_modifier(Modifier::UNDERLINED)),
+                    Span::styled(format!(" ({})", url), Style::default().fg(Color::DarkGray)),
+                ],
+                after,
+            ))
+        } else {
+            None
+        };
+
+        match styled {
+            Some((styled, after)) => {
+                if !plain.is_empty() {
+                    spans.push(Span::raw(std::mem::take(&mut plain)));
+                }
+                spans.extend(styled);
+                rest = after;
+            }
+            None => {
+                plain.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+
+    if !plain.is_empty() {
+        spans.push(Span::raw(plain));
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contents(line: &Line) -> Vec<String> {
+        line.spans.iter().map(|s| s.content.to_string()).collect()
+    }
+
+    #[test]
+    fn styles_bold_code_and_links() {
+        let lines = render("Bring **passport** and `ticket.pdf`, see [itinerary](https://example.com)");
+        assert_eq!(
+            contents(&lines[0]),
+            ["Bring ", "passport", " and ", "ticket.pdf", ", see ", "itinerary", " (https://example.com)"]
+        );
+        assert!(lines[0].spans[1].style.add_modifier.contains(Modifier::BOLD));
+    }
+
+    #[test]
+    fn renders_list_items() {
+        let lines = render("- milk\n  * eggs\n2. bread");
+        assert_eq!(contents(&lines[0]), ["• ", "milk"]);
+        assert_eq!(contents(&lines[1]), ["  • ", "eggs"]);
+        assert_eq!(contents(&lines[2]), ["2. ", "bread"]);
+    }
+
+    #[test]
+    fn leaves_unclosed_markers_alone() {
+        let lines = render("5 * 3 = 15, **not bold");
+        assert_eq!(contents(&lines[0]), ["5 * 3 = 15, **not bold"]);
+    }
+}
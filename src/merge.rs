@@ -0,0 +1,153 @@
+use crate::config::{Config, Storage};
+use crate::db::{Database, Reminder};
+use crate::open_store;
+use crate::sync::{report, resolve, Conflict};
+use reminder::ReminderStore;
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fs,
+    path::Path,
+};
+
+/// What makes two reminders the same for merging: everything the user
+/// typed, ignoring ids, timestamps and state.
+type Content<'a> = (
+    &'a str,
+    &'a str,
+    &'a str,
+    Option<&'a str>,
+    &'a [String],
+    &'a [i64],
+    Option<&'a str>,
+    Option<i64>,
+    Option<&'a str>,
+    Option<&'a str>,
+    Option<&'a str>,
+    bool,
+);
+
+fn content(reminder: &Reminder) -> Content<'_> {
+    (
+        &reminder.title,
+        &reminder.description,
+        &reminder.time,
+        reminder.date.as_deref(),
+        &reminder.tags,
+        &reminder.alerts,
+        reminder.cron.as_deref(),
+        reminder.interval_minutes,
+        reminder.until.as_deref(),
+        reminder.timezone.as_deref(),
+        reminder.weekdays.as_deref(),
+        reminder.skip_holidays,
+    )
+}
+
+/// What merging `incoming` into `existing` writes: reminders with a UUID
+/// already here replace our copy if they were changed later, and the rest
+/// are added with ids following on from the largest existing one unless
+/// their content is already here. Also returns those passed over because our
+/// copy changed later.
+fn plan(existing: &[Reminder], incoming: Vec<Reminder>) -> (Vec<Reminder>, Vec<Conflict>) {
+    let by_uuid: HashMap<&str, &Reminder> = existing.iter().map(|r| (r.uuid.as_str(), r)).collect();
+    let mut seen: HashSet<Content> = existing.iter().map(content).collect();
+    let mut uuids: HashSet<&str> = by_uuid.keys().copied().collect();
+    let mut next_id = existing.iter().map(|r| r.id).max().unwrap_or(0);
+    let mut write = Vec::new();
+    let mut conflicts = Vec::new();
+    for reminder in &incoming {
+        if let Some(stored) = by_uuid.get(reminder.uuid.as_str()) {
+            match resolve(stored, reminder) {
+                Some(conflict) if conflict.kept_theirs => write.push(Reminder { id: stored.id, ..reminder.clone() }),
+                Some(conflict) => conflicts.push(conflict),
+                None => {}
+            }
+        } else if uuids.insert(&reminder.uuid) && seen.insert(content(reminder)) {
+            next_id += 1;
+            write.push(Reminder { id: next_id, ..reminder.clone() });
+        }
+    }
+    (write, conflicts)
+}
+
+/// `reminder merge`: copies the reminders from another SQLite database into
+/// this one, done, archived and trashed ones included, skipping any already
+/// here. Where both have a reminder, the copy changed last wins. Pending
+/// alerts and snoozes of the other file are not carried over.
+pub fn run(config: &Config, path: &Path) -> Result<(), Box<dyn Error>> {
+    if !path.exists() {
+        return Err(format!("{} does not exist", path.display()).into());
+    }
+    if matches!(config.storage, Storage::Sqlite | Storage::Sqlcipher)
+        && fs::canonicalize(path).ok() == fs::canonicalize(config.database_path()?).ok()
+    {
+        return Err(format!("{} is the database being merged into", path.display()).into());
+    }
+
+    let incoming = Database::new(path)?.reminders()?;
+    let store = open_store(config)?;
+    let (write, conflicts) = plan(&store.reminders()?, incoming.clone());
+    for reminder in &write {
+        store.restore_reminder(reminder)?;
+    }
+    println!(
+        "Merged {} reminder(s) from {}: {} added or updated, {} conflict(s)",
+        incoming.len(),
+        path.display(),
+        write.len(),
+        conflicts.len()
+    );
+    report(&conflicts, &path.display().to_string());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reminder::{MemoryStore, NewReminder};
+
+    fn add(store: &MemoryStore, title: &str, time: &str) -> Reminder {
+        store
+            .add_reminder(NewReminder { title: title.to_string(), time: time.to_string(), ..Default::default() })
+            .unwrap()
+    }
+
+    #[test]
+    fn skips_reminders_with_the_same_content() {
+        let here = MemoryStore::new();
+        add(&here, "Tea", "16:00");
+        add(&here, "Standup", "09:30");
+        let other = MemoryStore::new();
+        let tea = add(&other, "Tea", "16:00");
+        other.set_completed(tea.id, Some("2026-10-14 16:05")).unwrap();
+        add(&other, "Tea", "17:00");
+        add(&other, "Tea", "17:00");
+        add(&other, "Nap", "14:00");
+        let standup = here.reminders().unwrap().pop().unwrap();
+        other.restore_reminder(&Reminder { id: 9, time: "10:00".to_string(), ..standup }).unwrap();
+
+        let (added, conflicts) = plan(&here.reminders().unwrap(), other.reminders().unwrap());
+        let added: Vec<(i32, &str, &str)> =
+            added.iter().map(|r| (r.id, r.title.as_str(), r.time.as_str())).collect();
+        assert_eq!(added, [(3, "Tea", "17:00"), (4, "Nap", "14:00")]);
+        // Both standups claim the same change time, so ours stays.
+        assert_eq!(conflicts.len(), 1);
+    }
+
+    #[test]
+    fn keeps_the_copy_changed_last() {
+        let here = MemoryStore::new();
+        let tea = add(&here, "Tea", "16:00");
+        let nap = add(&here, "Nap", "14:00");
+        let incoming = vec![
+            Reminder { time: "17:00".to_string(), updated_at: "2999-01-01T00:00:00.000Z".to_string(), ..tea.clone() },
+            Reminder { time: "13:00".to_string(), updated_at: "2000-01-01T00:00:00.000Z".to_string(), ..nap },
+        ];
+
+        let (write, conflicts) = plan(&here.reminders().unwrap(), incoming);
+        let write: Vec<(i32, &str)> = write.iter().map(|r| (r.id, r.time.as_str())).collect();
+        assert_eq!(write, [(tea.id, "17:00")]);
+        assert_eq!(conflicts.len(), 1);
+    }
+}
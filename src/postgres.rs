@@ -0,0 +1,404 @@
+//! Reminders kept in a PostgreSQL database, so several machines can share them.
+
+use crate::db::{new_uuid, timestamp, Delivery, Exception, LoggedNotification, NewReminder, Priority, Reminder, Vacation};
+use crate::store::{self, ReminderStore};
+use chrono::Local;
+use postgres::{Client, NoTls, Row};
+use std::sync::{Mutex, MutexGuard};
+
+const REMINDER_COLUMNS: &str = "id, title, description, time, created_at, alerts, date, tags, cron, interval_minutes, until, \
+     last_fired_at, timezone, completed_at, deleted_at, archived, delete_after_firing, uuid, updated_at, weekdays, skip_holidays, \
+     icon, category, resident, priority";
+
+/// Reminders stored in PostgreSQL.
+///
+/// Alerts and snoozes are claimed in the database just like with
+/// [`Database`](crate::Database), so every machine can run a worker and each
+/// alert is still delivered once.
+pub struct PgStore {
+    client: Mutex<Client>,
+}
+
+impl PgStore {
+    /// Connects with a libpq-style connection string such as
+    /// `host=db.example.com user=reminder dbname=reminders` or a
+    /// `postgresql://` URL, creating the tables if they don't exist.
+    pub fn connect(url: &str) -> store::Result<Self> {
+        let mut client = Client::connect(url, NoTls)?;
+        client.batch_execute(
+            "CREATE TABLE IF NOT EXISTS reminders (
+                id SERIAL PRIMARY KEY,
+                title TEXT NOT NULL,
+                description TEXT NOT NULL,
+                time TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                alerts BIGINT[] NOT NULL DEFAULT '{0}',
+                date TEXT,
+                tags TEXT[] NOT NULL DEFAULT '{}',
+                cron TEXT,
+                interval_minutes BIGINT,
+                until TEXT,
+                last_fired_at TEXT,
+                timezone TEXT,
+                completed_at TEXT,
+                deleted_at TEXT,
+                archived BOOLEAN NOT NULL DEFAULT FALSE,
+                delete_after_firing BOOLEAN NOT NULL DEFAULT FALSE
+            );
+            CREATE TABLE IF NOT EXISTS notified (
+                reminder_id INTEGER NOT NULL,
+                offset_minutes BIGINT NOT NULL,
+                occurrence TEXT NOT NULL,
+                PRIMARY KEY (reminder_id, offset_minutes, occurrence)
+            );
+            CREATE TABLE IF NOT EXISTS snoozes (
+                reminder_id INTEGER PRIMARY KEY,
+                until TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS notification_log (
+                id SERIAL PRIMARY KEY,
+                reminder_id INTEGER,
+                at TEXT NOT NULL,
+                summary TEXT NOT NULL,
+                delivery TEXT NOT NULL,
+                error TEXT
+            );
+            CREATE TABLE IF NOT EXISTS worker_state (
+                name TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS vacations (
+                id SERIAL PRIMARY KEY,
+                start_date TEXT NOT NULL,
+                end_date TEXT NOT NULL,
+                tags TEXT[] NOT NULL DEFAULT '{}'
+            );
+            CREATE TABLE IF NOT EXISTS exceptions (
+                reminder_id INTEGER NOT NULL,
+                date TEXT NOT NULL,
+                PRIMARY KEY (reminder_id, date)
+            );
+            ALTER TABLE exceptions ADD COLUMN IF NOT EXISTS time TEXT;
+            ALTER TABLE exceptions ADD COLUMN IF NOT EXISTS note TEXT;
+            ALTER TABLE reminders ADD COLUMN IF NOT EXISTS uuid TEXT;
+            UPDATE reminders SET uuid = gen_random_uuid()::TEXT WHERE uuid IS NULL;
+            CREATE UNIQUE INDEX IF NOT EXISTS reminders_uuid ON reminders (uuid);
+            ALTER TABLE reminders ADD COLUMN IF NOT EXISTS updated_at TEXT NOT NULL DEFAULT '';
+            UPDATE reminders
+                SET updated_at = to_char(created_at::TIMESTAMPTZ AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS.MS\"Z\"')
+                WHERE updated_at = '';
+            ALTER TABLE reminders ADD COLUMN IF NOT EXISTS weekdays TEXT;
+            ALTER TABLE reminders ADD COLUMN IF NOT EXISTS skip_holidays BOOLEAN NOT NULL DEFAULT FALSE;
+            ALTER TABLE reminders ADD COLUMN IF NOT EXISTS icon TEXT;
+            ALTER TABLE reminders ADD COLUMN IF NOT EXISTS category TEXT;
+            ALTER TABLE reminders ADD COLUMN IF NOT EXISTS resident BOOLEAN NOT NULL DEFAULT FALSE;
+            ALTER TABLE reminders ADD COLUMN IF NOT EXISTS priority TEXT NOT NULL DEFAULT 'normal';
+            DO $$ BEGIN
+                IF EXISTS (SELECT 1 FROM information_schema.columns WHERE table_name = 'notified' AND column_name = 'fired_on') THEN
+                    ALTER TABLE notified RENAME COLUMN fired_on TO occurrence;
+                    DELETE FROM notified WHERE length(occurrence) = 10;
+                END IF;
+            END $$;",
+        )?;
+        Ok(PgStore { client: Mutex::new(client) })
+    }
+
+    fn client(&self) -> MutexGuard<'_, Client> {
+        // Every statement runs on its own, so a poisoned lock is fine to reuse.
+        self.client.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn reminder_from_row(row: &Row) -> Reminder {
+        Reminder {
+            id: row.get(0),
+            title: row.get(1),
+            description: row.get(2),
+            time: row.get(3),
+            created_at: row.get(4),
+            alerts: row.get(5),
+            date: row.get(6),
+            tags: row.get(7),
+            cron: row.get(8),
+            interval_minutes: row.get(9),
+            until: row.get(10),
+            last_fired_at: row.get(11),
+            timezone: row.get(12),
+            completed_at: row.get(13),
+            deleted_at: row.get(14),
+            archived: row.get(15),
+            delete_after_firing: row.get(16),
+            uuid: row.get(17),
+            updated_at: row.get(18),
+            weekdays: row.get(19),
+            skip_holidays: row.get(20),
+            exceptions: Vec::new(),
+            icon: row.get(21),
+            category: row.get(22),
+            resident: row.get(23),
+            priority: Priority::from_stored(row.get(24)),
+        }
+    }
+}
+
+impl ReminderStore for PgStore {
+    fn add_reminder(&self, new: NewReminder) -> store::Result<Reminder> {
+        let now = Local::now().to_rfc3339();
+        let row = self.client().query_one(
+            &format!(
+                "INSERT INTO reminders (title, description, time, created_at, alerts, date, tags, cron, interval_minutes,
+                 until, timezone, delete_after_firing, uuid, updated_at, weekdays, skip_holidays, icon, category, resident,
+                 priority) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20)
+                 RETURNING {}",
+                REMINDER_COLUMNS
+            ),
+            &[
+                &new.title,
+                &new.description,
+                &new.time,
+                &now,
+                &new.alerts,
+                &new.date,
+                &new.tags,
+                &new.cron,
+                &new.interval_minutes,
+                &new.until,
+                &new.timezone,
+                &new.delete_after_firing,
+                &new.uuid.unwrap_or_else(new_uuid),
+                &timestamp(),
+                &new.weekdays,
+                &new.skip_holidays,
+                &new.icon,
+                &new.category,
+                &new.resident,
+                &new.priority.as_str(),
+            ],
+        )?;
+        Ok(Self::reminder_from_row(&row))
+    }
+
+    fn reminders(&self) -> store::Result<Vec<Reminder>> {
+        let rows = self.client().query(
+            &format!(
+                "SELECT {},
+                   ARRAY(SELECT date FROM exceptions WHERE reminder_id = reminders.id ORDER BY date),
+                   ARRAY(SELECT time FROM exceptions WHERE reminder_id = reminders.id ORDER BY date),
+                   ARRAY(SELECT note FROM exceptions WHERE reminder_id = reminders.id ORDER BY date)
+                 FROM reminders ORDER BY id",
+                REMINDER_COLUMNS
+            ),
+            &[],
+        )?;
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let dates: Vec<String> = row.get(25);
+                let times: Vec<Option<String>> = row.get(26);
+                let notes: Vec<Option<String>> = row.get(27);
+                let exceptions = dates.into_iter().zip(times).zip(notes);
+                Reminder {
+                    exceptions: exceptions.map(|((date, time), note)| Exception { date, time, note }).collect(),
+                    ..Self::reminder_from_row(row)
+                }
+            })
+            .collect())
+    }
+
+    fn restore_reminder(&self, reminder: &Reminder) -> store::Result<()> {
+        let mut client = self.client();
+        let mut tx = client.transaction()?;
+        tx.execute(
+            &format!(
+                "INSERT INTO reminders ({}) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21,
+                 $22, $23, $24, $25)
+                 ON CONFLICT (id) DO UPDATE SET title = $2, description = $3, time = $4, created_at = $5, alerts = $6,
+                 date = $7, tags = $8, cron = $9, interval_minutes = $10, until = $11, last_fired_at = $12,
+                 timezone = $13, completed_at = $14, deleted_at = $15, archived = $16, delete_after_firing = $17,
+                 uuid = $18, updated_at = $19, weekdays = $20, skip_holidays = $21,
+                 icon = $22, category = $23, resident = $24, priority = $25",
+                REMINDER_COLUMNS
+            ),
+            &[
+                &reminder.id,
+                &reminder.title,
+                &reminder.description,
+                &reminder.time,
+                &reminder.created_at,
+                &reminder.alerts,
+                &reminder.date,
+                &reminder.tags,
+                &reminder.cron,
+                &reminder.interval_minutes,
+                &reminder.until,
+                &reminder.last_fired_at,
+                &reminder.timezone,
+                &reminder.completed_at,
+                &reminder.deleted_at,
+                &reminder.archived,
+                &reminder.delete_after_firing,
+                &reminder.uuid,
+                &reminder.updated_at,
+                &reminder.weekdays,
+                &reminder.skip_holidays,
+                &reminder.icon,
+                &reminder.category,
+                &reminder.resident,
+                &reminder.priority.as_str(),
+            ],
+        )?;
+        tx.execute("DELETE FROM exceptions WHERE reminder_id = $1", &[&reminder.id])?;
+        for exception in &reminder.exceptions {
+            tx.execute(
+                "INSERT INTO exceptions (reminder_id, date, time, note) VALUES ($1, $2, $3, $4) ON CONFLICT DO NOTHING",
+                &[&reminder.id, &exception.date, &exception.time, &exception.note],
+            )?;
+        }
+        // Keep SERIAL ahead of ids written by hand, or the next insert collides.
+        tx.execute(
+            "SELECT setval('reminders_id_seq', $1::INTEGER) WHERE $1::INTEGER > (SELECT last_value FROM reminders_id_seq)",
+            &[&reminder.id],
+        )?;
+        Ok(tx.commit()?)
+    }
+
+    fn purge_reminder(&self, id: i32) -> store::Result<()> {
+        let mut client = self.client();
+        let mut tx = client.transaction()?;
+        tx.execute("DELETE FROM reminders WHERE id = $1", &[&id])?;
+        tx.execute("DELETE FROM notified WHERE reminder_id = $1", &[&id])?;
+        tx.execute("DELETE FROM snoozes WHERE reminder_id = $1", &[&id])?;
+        tx.execute("DELETE FROM exceptions WHERE reminder_id = $1", &[&id])?;
+        Ok(tx.commit()?)
+    }
+
+    fn snooze_reminder(&self, id: i32, until: &str) -> store::Result<()> {
+        self.client().execute(
+            "INSERT INTO snoozes (reminder_id, until) VALUES ($1, $2)
+             ON CONFLICT (reminder_id) DO UPDATE SET until = $2",
+            &[&id, &until],
+        )?;
+        Ok(())
+    }
+
+    fn unsnooze_reminder(&self, id: i32) -> store::Result<()> {
+        self.client().execute("DELETE FROM snoozes WHERE reminder_id = $1", &[&id])?;
+        Ok(())
+    }
+
+    fn claim_due_snoozes(&self, now: &str) -> store::Result<Vec<i32>> {
+        let rows = self
+            .client()
+            .query("DELETE FROM snoozes WHERE until <= $1 RETURNING reminder_id", &[&now])?;
+        Ok(rows.iter().map(|row| row.get(0)).collect())
+    }
+
+    fn next_snooze(&self) -> store::Result<Option<String>> {
+        Ok(self.client().query_one("SELECT MIN(until) FROM snoozes", &[])?.get(0))
+    }
+
+    fn claim_notification(&self, reminder_id: i32, offset_minutes: i64, occurrence: &str) -> store::Result<bool> {
+        let inserted = self.client().execute(
+            "INSERT INTO notified (reminder_id, offset_minutes, occurrence) VALUES ($1, $2, $3) ON CONFLICT DO NOTHING",
+            &[&reminder_id, &offset_minutes, &occurrence],
+        )?;
+        Ok(inserted == 1)
+    }
+
+    fn release_notification(&self, reminder_id: i32, offset_minutes: i64, occurrence: &str) -> store::Result<()> {
+        self.client().execute(
+            "DELETE FROM notified WHERE reminder_id = $1 AND offset_minutes = $2 AND occurrence = $3",
+            &[&reminder_id, &offset_minutes, &occurrence],
+        )?;
+        Ok(())
+    }
+
+    fn log_notification(&self, notification: &LoggedNotification) -> store::Result<()> {
+        self.client().execute(
+            "INSERT INTO notification_log (reminder_id, at, summary, delivery, error) VALUES ($1, $2, $3, $4, $5)",
+            &[
+                &notification.reminder_id,
+                &notification.at,
+                &notification.summary,
+                &notification.delivery.as_str(),
+                &notification.error,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn notification_log(&self, limit: usize) -> store::Result<Vec<LoggedNotification>> {
+        let rows = self.client().query(
+            "SELECT reminder_id, at, summary, delivery, error FROM notification_log ORDER BY id DESC LIMIT $1",
+            &[&(limit as i64)],
+        )?;
+        Ok(rows
+            .iter()
+            .map(|row| LoggedNotification {
+                reminder_id: row.get(0),
+                at: row.get(1),
+                summary: row.get(2),
+                delivery: Delivery::from_stored(row.get(3)),
+                error: row.get(4),
+            })
+            .collect())
+    }
+
+    fn last_check(&self) -> store::Result<Option<String>> {
+        let row = self
+            .client()
+            .query_opt("SELECT value FROM worker_state WHERE name = 'last_check'", &[])?;
+        Ok(row.map(|row| row.get(0)))
+    }
+
+    /// Keeps the latest minute when several machines record at once.
+    fn record_check(&self, at: &str) -> store::Result<()> {
+        self.client().execute(
+            "INSERT INTO worker_state (name, value) VALUES ('last_check', $1)
+             ON CONFLICT (name) DO UPDATE SET value = GREATEST(worker_state.value, $1)",
+            &[&at],
+        )?;
+        Ok(())
+    }
+
+    fn add_vacation(&self, start: &str, end: &str, tags: &[String]) -> store::Result<Vacation> {
+        let row = self.client().query_one(
+            "INSERT INTO vacations (start_date, end_date, tags) VALUES ($1, $2, $3) RETURNING id",
+            &[&start, &end, &tags],
+        )?;
+        Ok(Vacation {
+            id: row.get(0),
+            start: start.to_string(),
+            end: end.to_string(),
+            tags: tags.to_vec(),
+        })
+    }
+
+    fn vacations(&self) -> store::Result<Vec<Vacation>> {
+        let rows = self
+            .client()
+            .query("SELECT id, start_date, end_date, tags FROM vacations ORDER BY start_date, id", &[])?;
+        Ok(rows
+            .iter()
+            .map(|row| Vacation {
+                id: row.get(0),
+                start: row.get(1),
+                end: row.get(2),
+                tags: row.get(3),
+            })
+            .collect())
+    }
+
+    fn delete_vacation(&self, id: i32) -> store::Result<()> {
+        self.client().execute("DELETE FROM vacations WHERE id = $1", &[&id])?;
+        Ok(())
+    }
+
+    /// Done in one statement so two machines can't both fire the same interval.
+    fn claim_interval_fire(&self, id: i32, previous: Option<&str>, at: Option<&str>) -> store::Result<bool> {
+        let updated = self.client().execute(
+            "UPDATE reminders SET last_fired_at = $1 WHERE id = $2 AND last_fired_at IS NOT DISTINCT FROM $3",
+            &[&at, &id, &previous],
+        )?;
+        Ok(updated == 1)
+    }
+}
@@ -0,0 +1,325 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Weekday};
+
+/// A reminder parsed from one line such as `Call mom tomorrow 18:30 #family`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuickAdd {
+    pub title: String,
+    /// `None` when the line asked for a daily reminder.
+    pub date: Option<NaiveDate>,
+    pub time: NaiveTime,
+    pub tags: Vec<String>,
+}
+
+/// Parses a quick-add line relative to `now`.
+///
+/// Recognised anywhere in the line:
+/// - times: `18:30`, `18:30:15`, `6pm`, `6:30 pm`, `noon`, `midnight`
+///   (optionally after `at`)
+/// - dates: `today`, `tonight`, `tomorrow`, `friday`, `next mon`, `2026-10-20`
+///   (optionally after `on`)
+/// - relative: `in 90s`, `in 15m`, `in 2 hours`, `in 3 days`
+/// - repetition: `daily`, `every day`
+/// - tags: `#family`
+///
+/// Everything else becomes the title. Without a date the reminder is set for
+/// the next time the clock reaches the given time.
+pub fn parse(input: &str, now: NaiveDateTime) -> Result<QuickAdd, String> {
+    let tokens: Vec<&str> = input.split_whitespace().collect();
+    let mut title = Vec::new();
+    let mut tags: Vec<String> = Vec::new();
+    let mut date = None;
+    let mut time = None;
+    let mut relative_time = None;
+    let mut daily = false;
+    let mut tonight = false;
+
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = tokens[i];
+        let lower = token.to_lowercase();
+        let next = tokens.get(i + 1).map(|t| t.to_lowercase());
+        let after_next = tokens.get(i + 2).map(|t| t.to_lowercase());
+
+        if let Some(tag) = token.strip_prefix('#').filter(|t| !t.is_empty()) {
+            let tag = tag.to_lowercase();
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        } else if lower == "daily" {
+            daily = true;
+        } else if lower == "every" && next.as_deref() == Some("day") {
+            daily = true;
+            i += 1;
+        } else if let Some((offset, used)) = (lower == "in")
+            .then(|| parse_duration(next.as_deref()?, after_next.as_deref()))
+            .flatten()
+        {
+            let at = now + offset;
+            date = Some(at.date());
+            relative_time = NaiveTime::from_hms_opt(at.hour(), at.minute(), at.second());
+            i += used;
+        } else if let Some((parsed, used)) = parse_date(&lower, next.as_deref(), now.date()) {
+            tonight |= lower == "tonight";
+            date = Some(parsed);
+            i += used - 1;
+        } else if let Some((parsed, used)) = parse_time(&lower, next.as_deref()).filter(|_| time.is_none()) {
+            time = Some(parsed);
+            i += used - 1;
+        } else if (lower == "at" || lower == "on") && next.as_deref().is_some_and(|next| {
+            parse_time(next, after_next.as_deref()).is_some()
+                || parse_date(next, after_next.as_deref(), now.date()).is_some()
+                || is_weekday_abbreviation(next)
+        }) {
+            // A preposition in front of a date or time isn't part of the title.
+            if lower == "on" {
+                if let Some(weekday) = next.as_deref().and_then(parse_weekday) {
+                    date = Some(next_weekday(now.date(), weekday, false));
+                    i += 1;
+                }
+            }
+        } else {
+            title.push(token);
+        }
+        i += 1;
+    }
+
+    let title = title.join(" ");
+    if title.is_empty() {
+        return Err("Add a title, e.g. \"Call mom tomorrow 18:30\"".to_string());
+    }
+    let time = match time.or(relative_time) {
+        Some(time) => time,
+        None if tonight => NaiveTime::from_hms_opt(20, 0, 0).unwrap(),
+        None => return Err("Add a time, e.g. 18:30 or 6pm".to_string()),
+    };
+
+    let date = if daily {
+        None
+    } else {
+        Some(date.unwrap_or_else(|| {
+            if time > now.time() {
+                now.date()
+            } else {
+                now.date() + Duration::days(1)
+            }
+        }))
+    };
+
+    Ok(QuickAdd {
+        title,
+        date,
+        time,
+        tags,
+    })
+}
+
+/// Returns the parsed time and how many tokens it used.
+fn parse_time(token: &str, next: Option<&str>) -> Option<(NaiveTime, usize)> {
+    match token {
+        "noon" => return Some((NaiveTime::from_hms_opt(12, 0, 0)?, 1)),
+        "midnight" => return Some((NaiveTime::from_hms_opt(0, 0, 0)?, 1)),
+        _ => {}
+    }
+
+    let (clock, meridiem, used) = if let Some(clock) = token.strip_suffix("am") {
+        (clock, Some(false), 1)
+    } else if let Some(clock) = token.strip_suffix("pm") {
+        (clock, Some(true), 1)
+    } else if next == Some("am") {
+        (token, Some(false), 2)
+    } else if next == Some("pm") {
+        (token, Some(true), 2)
+    } else {
+        (token, None, 1)
+    };
+
+    let (hour, minute, second) = match clock.split_once(':') {
+        Some((h, m)) if m.len() == 2 => (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?, 0),
+        Some((h, ms)) => match ms.split_once(':') {
+            Some((m, s)) if m.len() == 2 && s.len() == 2 => {
+                (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?, s.parse::<u32>().ok()?)
+            }
+            _ => return None,
+        },
+        // A bare number is only a time with am/pm, so "Buy 5 apples" stays a title.
+        None if meridiem.is_some() => (clock.parse::<u32>().ok()?, 0, 0),
+        None => return None,
+    };
+
+    let hour = match meridiem {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some(false) => hour % 12,
+        Some(true) => hour % 12 + 12,
+        None => hour,
+    };
+    Some((NaiveTime::from_hms_opt(hour, minute, second)?, used))
+}
+
+/// Returns the parsed date and how many tokens it used.
+fn parse_date(token: &str, next: Option<&str>, today: NaiveDate) -> Option<(NaiveDate, usize)> {
+    match token {
+        "today" | "tonight" => return Some((today, 1)),
+        "tomorrow" => return Some((today + Duration::days(1), 1)),
+        "next" => {
+            let weekday = next.and_then(parse_weekday)?;
+            return Some((next_weekday(today, weekday, true), 2));
+        }
+        _ => {}
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(token, "%Y-%m-%d") {
+        return Some((date, 1));
+    }
+    // Abbreviations like "sun" or "wed" are too common in titles to match on
+    // their own; they need "on" or "next" in front.
+    if is_weekday_abbreviation(token) {
+        return None;
+    }
+    let weekday = parse_weekday(token)?;
+    Some((next_weekday(today, weekday, false), 1))
+}
+
+fn parse_weekday(token: &str) -> Option<Weekday> {
+    token.parse::<Weekday>().ok()
+}
+
+fn is_weekday_abbreviation(token: &str) -> bool {
+    token.len() == 3 && parse_weekday(token).is_some()
+}
+
+/// The next `weekday` on or after `today`, or strictly after it when `skip_today`.
+fn next_weekday(today: NaiveDate, weekday: Weekday, skip_today: bool) -> NaiveDate {
+    let mut days = (7 + weekday.num_days_from_monday() as i64
+        - today.weekday().num_days_from_monday() as i64)
+        % 7;
+    if days == 0 && skip_today {
+        days = 7;
+    }
+    today + Duration::days(days)
+}
+
+/// Parses `90s`, `15m`, `2h`, `3d` or `15 minutes`, returning how many tokens it used.
+fn parse_duration(token: &str, next: Option<&str>) -> Option<(Duration, usize)> {
+    let split = token.find(|c: char| !c.is_ascii_digit()).unwrap_or(token.len());
+    let amount: i64 = token[..split].parse().ok()?;
+    let (unit, used) = if split < token.len() {
+        (&token[split..], 1)
+    } else {
+        (next?, 2)
+    };
+
+    let duration = match unit {
+        "s" | "sec" | "secs" | "second" | "seconds" => Duration::seconds(amount),
+        "m" | "min" | "mins" | "minute" | "minutes" => Duration::minutes(amount),
+        "h" | "hr" | "hrs" | "hour" | "hours" => Duration::hours(amount),
+        "d" | "day" | "days" => Duration::days(amount),
+        _ => return None,
+    };
+    Some((duration, used))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Thursday 2026-10-15 10:00.
+    fn now() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, 15)
+            .unwrap()
+            .and_hms_opt(10, 0, 0)
+            .unwrap()
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(y, m, d)
+    }
+
+    fn time(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn parses_title_date_time_and_tags() {
+        let parsed = parse("Call mom tomorrow 18:30 #family", now()).unwrap();
+        assert_eq!(
+            parsed,
+            QuickAdd {
+                title: "Call mom".to_string(),
+                date: date(2026, 10, 16),
+                time: time(18, 30),
+                tags: vec!["family".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn parses_meridiem_times() {
+        assert_eq!(parse("Lunch 12pm", now()).unwrap().time, time(12, 0));
+        assert_eq!(parse("Walk 6:45 am tomorrow", now()).unwrap().time, time(6, 45));
+        assert_eq!(parse("Sleep 12am", now()).unwrap().time, time(0, 0));
+        assert_eq!(parse("Lunch at noon", now()).unwrap().title, "Lunch");
+    }
+
+    #[test]
+    fn without_a_date_picks_the_next_occurrence() {
+        assert_eq!(parse("Stretch 17:00", now()).unwrap().date, date(2026, 10, 15));
+        assert_eq!(parse("Stretch 08:00", now()).unwrap().date, date(2026, 10, 16));
+    }
+
+    #[test]
+    fn daily_has_no_date() {
+        let parsed = parse("Standup 9:30am daily #work #Work", now()).unwrap();
+        assert_eq!(parsed.date, None);
+        assert_eq!(parsed.tags, vec!["work".to_string()]);
+        assert_eq!(parse("Meds every day 8pm", now()).unwrap().date, None);
+    }
+
+    #[test]
+    fn parses_weekdays_and_iso_dates() {
+        assert_eq!(parse("Dentist friday 3pm", now()).unwrap().date, date(2026, 10, 16));
+        assert_eq!(parse("Review thursday 3pm", now()).unwrap().date, date(2026, 10, 15));
+        assert_eq!(parse("Review next thu 3pm", now()).unwrap().date, date(2026, 10, 22));
+        assert_eq!(parse("Gym on mon at 7am", now()).unwrap().date, date(2026, 10, 19));
+
+        let parsed = parse("Pay rent on 2026-11-01 at 9am", now()).unwrap();
+        assert_eq!(parsed.title, "Pay rent");
+        assert_eq!(parsed.date, date(2026, 11, 1));
+    }
+
+    #[test]
+    fn keeps_ambiguous_words_in_the_title() {
+        let parsed = parse("Buy 5 sun hats 18:00", now()).unwrap();
+        assert_eq!(parsed.title, "Buy 5 sun hats");
+        assert_eq!(parse("Go in peace 18:00", now()).unwrap().title, "Go in peace");
+    }
+
+    #[test]
+    fn parses_relative_times() {
+        let parsed = parse("Tea in 15m", now()).unwrap();
+        assert_eq!((parsed.date, parsed.time), (date(2026, 10, 15), time(10, 15)));
+
+        let parsed = parse("Check oven in 2 hours", now()).unwrap();
+        assert_eq!(parsed.title, "Check oven");
+        assert_eq!(parsed.time, time(12, 0));
+
+        assert_eq!(parse("Renew in 3 days 9:00", now()).unwrap().date, date(2026, 10, 18));
+
+        let parsed = parse("Tea in 3 minutes", now() + Duration::seconds(20)).unwrap();
+        assert_eq!(parsed.time, NaiveTime::from_hms_opt(10, 3, 20).unwrap());
+        assert_eq!(parse("Pasta in 90s", now()).unwrap().time, NaiveTime::from_hms_opt(10, 1, 30).unwrap());
+        assert_eq!(parse("Launch 10:30:15", now()).unwrap().time, NaiveTime::from_hms_opt(10, 30, 15).unwrap());
+    }
+
+    #[test]
+    fn tonight_defaults_to_the_evening() {
+        let parsed = parse("Take out trash tonight", now()).unwrap();
+        assert_eq!((parsed.date, parsed.time), (date(2026, 10, 15), time(20, 0)));
+    }
+
+    #[test]
+    fn rejects_missing_parts() {
+        assert!(parse("Call mom tomorrow", now()).is_err());
+        assert!(parse("tomorrow 18:30 #family", now()).is_err());
+        assert!(parse("Nap 25:00", now()).is_err());
+    }
+}
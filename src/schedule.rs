@@ -0,0 +1,354 @@
+use crate::db::{Exception, Reminder};
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike, Weekday};
+use chrono_tz::Tz;
+use croner::{
+    parser::{CronParser, Seconds, Year},
+    Cron,
+};
+
+/// Parses a standard five-field cron expression such as `0 9 * * MON-FRI`.
+pub fn parse_cron(expression: &str) -> Result<Cron, String> {
+    CronParser::builder()
+        .seconds(Seconds::Disallowed)
+        .year(Year::Disallowed)
+        .build()
+        .parse(expression)
+        .map_err(|e| e.to_string())
+}
+
+const DAY_NAMES: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+/// Fires skipped looking for one on an allowed day before giving up.
+const WEEKDAY_TRIES: usize = 366;
+
+/// Monday to Friday, as a mask from [`parse_weekdays`].
+const WEEKDAYS: u8 = 0b0011111;
+
+/// Saturday and Sunday, as a mask from [`parse_weekdays`].
+const WEEKENDS: u8 = 0b1100000;
+
+/// Parses days of the week such as `weekdays`, `weekends`, `mon,wed,fri` or
+/// `mon-thu sat` into a mask with a bit per day, Monday lowest.
+pub fn parse_weekdays(text: &str) -> Result<u8, String> {
+    let day = |name: &str| {
+        name.parse::<Weekday>()
+            .map(|day| day.num_days_from_monday())
+            .map_err(|_| format!("Unknown day {:?}", name))
+    };
+    let mut mask = 0;
+    for part in text.split([',', ' ']).filter(|part| !part.trim().is_empty()) {
+        mask |= match part.trim().to_lowercase().as_str() {
+            "weekdays" => WEEKDAYS,
+            "weekends" | "weekend" => WEEKENDS,
+            part => match part.split_once('-') {
+                Some((from, to)) => {
+                    let (from, to) = (day(from)?, day(to)?);
+                    // Wraps past Sunday, so `fri-mon` is a long weekend.
+                    (0..7).map(|i| (from + i) % 7).take(((to + 7 - from) % 7 + 1) as usize).fold(0, |mask, d| mask | 1 << d)
+                }
+                None => 1 << day(part)?,
+            },
+        };
+    }
+    if mask == 0 {
+        return Err("No days given".to_string());
+    }
+    Ok(mask)
+}
+
+/// How a mask from [`parse_weekdays`] is written back: `weekdays`,
+/// `weekends`, or the days such as `mon,wed,fri`.
+pub fn weekdays_text(mask: u8) -> String {
+    match mask {
+        WEEKDAYS => "weekdays".to_string(),
+        WEEKENDS => "weekends".to_string(),
+        mask => (0..7).filter(|d| mask & 1 << d != 0).map(|d| DAY_NAMES[d]).collect::<Vec<_>>().join(","),
+    }
+}
+
+/// Whether a repeating reminder's own schedule applies on `day`: one of its
+/// days of the week and without an exception, which replaces the schedule
+/// for that day. One-off reminders have their date instead.
+fn allowed_on(reminder: &Reminder, day: NaiveDate) -> bool {
+    if reminder.date.is_some() {
+        return true;
+    }
+    let on_weekday = match reminder.weekdays.as_deref().and_then(|days| parse_weekdays(days).ok()) {
+        Some(mask) => mask & 1 << day.weekday().num_days_from_monday() != 0,
+        None => true,
+    };
+    on_weekday && exception_on(reminder, day).is_none()
+}
+
+/// The exception for `day`, in the reminder's timezone, if it has one.
+fn exception_on(reminder: &Reminder, day: NaiveDate) -> Option<&Exception> {
+    let day = day.format("%Y-%m-%d").to_string();
+    reminder.exceptions.iter().find(|exception| exception.date == day)
+}
+
+/// The note an exception adds to the occurrence at the local time `at`.
+pub fn note_at(reminder: &Reminder, at: NaiveDateTime) -> Option<&str> {
+    exception_on(reminder, to_zone(reminder, at).date())?.note.as_deref()
+}
+
+/// When a repeating reminder first goes off on `day`, in its own timezone,
+/// by its usual schedule rather than an exception.
+pub fn usual_time(reminder: &Reminder, day: NaiveDate) -> Option<NaiveTime> {
+    let usual = Reminder { exceptions: Vec::new(), ..reminder.clone() };
+    next_fire_in_zone(&usual, day.and_time(NaiveTime::MIN)).filter(|at| at.date() == day).map(|at| at.time())
+}
+
+/// Truncates to the start of the minute, the resolution reminders fire at.
+pub fn minute_of(at: NaiveDateTime) -> NaiveDateTime {
+    at.with_second(0).and_then(|at| at.with_nanosecond(0)).unwrap_or(at)
+}
+
+/// Parses an IANA zone name such as `America/New_York`.
+pub fn parse_timezone(name: &str) -> Result<Tz, String> {
+    name.parse::<Tz>().map_err(|_| format!("Unknown timezone {}", name))
+}
+
+fn zone_of(reminder: &Reminder) -> Option<Tz> {
+    reminder.timezone.as_deref().and_then(|name| parse_timezone(name).ok())
+}
+
+/// Converts a local wall-clock time into the reminder's own timezone.
+fn to_zone(reminder: &Reminder, local: NaiveDateTime) -> NaiveDateTime {
+    match (zone_of(reminder), Local.from_local_datetime(&local).earliest()) {
+        (Some(zone), Some(at)) => at.with_timezone(&zone).naive_local(),
+        _ => local,
+    }
+}
+
+/// Converts a wall-clock time in the reminder's timezone back to local time.
+/// Times skipped by a DST change move forward to when the clock resumes.
+pub fn to_local(reminder: &Reminder, at: NaiveDateTime) -> NaiveDateTime {
+    let Some(zone) = zone_of(reminder) else {
+        return at;
+    };
+    (0..=2)
+        .find_map(|hours| zone.from_local_datetime(&(at + Duration::hours(hours))).earliest())
+        .map_or(at, |at| at.with_timezone(&Local).naive_local())
+}
+
+/// Reads a stored time: `HH:MM`, or `HH:MM:SS` for one that goes off on
+/// the second.
+pub fn parse_time(text: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(text, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(text, "%H:%M"))
+        .ok()
+}
+
+/// How `time` is stored: `HH:MM`, with the seconds only if there are any.
+pub fn time_text(time: NaiveTime) -> String {
+    let format = if time.second() == 0 { "%H:%M" } else { "%H:%M:%S" };
+    time.format(format).to_string()
+}
+
+fn time_of(reminder: &Reminder) -> Option<NaiveTime> {
+    parse_time(&reminder.time)
+}
+
+fn date_of(reminder: &Reminder) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(reminder.date.as_deref()?, "%Y-%m-%d").ok()
+}
+
+fn until_of(reminder: &Reminder) -> NaiveTime {
+    reminder
+        .until
+        .as_deref()
+        .and_then(parse_time)
+        .unwrap_or_else(|| NaiveTime::from_hms_opt(23, 59, 0).unwrap())
+}
+
+/// The next fire of an interval reminder on `day`, at or after `from`.
+///
+/// Fires continue `interval` minutes after the last one; a fire that was
+/// missed (e.g. while the worker was down) is due straight away. The first
+/// fire of a day sits on the `time + n * interval` grid.
+fn next_interval_fire(reminder: &Reminder, interval: i64, day: NaiveDate, from: NaiveDateTime) -> Option<NaiveDateTime> {
+    let start = day.and_time(time_of(reminder)?);
+    let end = day.and_time(until_of(reminder));
+    let interval = Duration::minutes(interval.max(1));
+    let last = reminder
+        .last_fired_at
+        .as_deref()
+        .and_then(|last| NaiveDateTime::parse_from_str(last, "%Y-%m-%d %H:%M").ok())
+        .map(|last| to_zone(reminder, last))
+        .filter(|last| *last >= start && *last <= end);
+
+    let candidate = match last {
+        Some(last) => (last + interval).max(from),
+        None if from <= start => start,
+        None => {
+            let elapsed = (from - start).num_minutes();
+            let steps = (elapsed + interval.num_minutes() - 1) / interval.num_minutes();
+            start + interval * steps as i32
+        }
+    };
+    Some(candidate).filter(|at| *at <= end)
+}
+
+/// Whether `reminder` is scheduled to go off in the local minute starting at
+/// `at`, at whatever second of it.
+pub fn fires_at(reminder: &Reminder, at: NaiveDateTime) -> bool {
+    let at = minute_of(to_zone(reminder, at));
+    if reminder.date.is_none() && exception_on(reminder, at.date()).is_some() {
+        return next_fire_in_zone(reminder, at).map(minute_of) == Some(at);
+    }
+    if !allowed_on(reminder, at.date()) {
+        return false;
+    }
+
+    if reminder.interval_minutes.is_some() {
+        return next_fire_in_zone(reminder, at).map(minute_of) == Some(at);
+    }
+
+    if let Some(expression) = &reminder.cron {
+        return parse_cron(expression).is_ok_and(|cron| cron.is_time_matching(&at).unwrap_or(false));
+    }
+
+    let Some(time) = time_of(reminder) else {
+        return false;
+    };
+    match &reminder.date {
+        Some(_) => date_of(reminder).is_some_and(|date| minute_of(date.and_time(time)) == at),
+        None => minute_of(at.date().and_time(time)) == at,
+    }
+}
+
+/// The alerts of `reminder` due in the local minute starting at `at`, as
+/// minutes before the reminder itself. Interval reminders only ever alert at
+/// their time.
+pub fn due_alerts(reminder: &Reminder, at: NaiveDateTime) -> Vec<i64> {
+    if reminder.interval_minutes.is_some() {
+        return if fires_at(reminder, at) { vec![0] } else { Vec::new() };
+    }
+    reminder
+        .alerts
+        .iter()
+        .copied()
+        .filter(|&offset| fires_at(reminder, at + Duration::minutes(offset)))
+        .collect()
+}
+
+/// The first local minute at or after `from` in which one of `reminder`'s
+/// alerts is due, as [`due_alerts`] would report it.
+pub fn next_alert(reminder: &Reminder, from: NaiveDateTime) -> Option<NaiveDateTime> {
+    if reminder.interval_minutes.is_some() {
+        return next_fire(reminder, from);
+    }
+    reminder
+        .alerts
+        .iter()
+        .filter_map(|&offset| Some(next_fire(reminder, from + Duration::minutes(offset))? - Duration::minutes(offset)))
+        .min()
+}
+
+/// The first local time at or after `from` that `reminder` goes off, if it ever does again.
+pub fn next_fire(reminder: &Reminder, from: NaiveDateTime) -> Option<NaiveDateTime> {
+    next_fire_in_zone(reminder, to_zone(reminder, from)).map(|at| to_local(reminder, at))
+}
+
+/// Like `next_fire`, but both `from` and the result are in the reminder's timezone.
+fn next_fire_in_zone(reminder: &Reminder, from: NaiveDateTime) -> Option<NaiveDateTime> {
+    let from = minute_of(from);
+    let usual = next_usual_fire(reminder, from);
+    match (usual, next_moved_fire(reminder, from)) {
+        (Some(usual), Some(moved)) => Some(usual.min(moved)),
+        (usual, moved) => usual.or(moved),
+    }
+}
+
+/// Like `next_fire_in_zone`, leaving out the days with an exception.
+fn next_usual_fire(reminder: &Reminder, mut from: NaiveDateTime) -> Option<NaiveDateTime> {
+    // Enough for a cron schedule whose own days rarely meet the allowed ones.
+    for _ in 0..WEEKDAY_TRIES {
+        let at = next_fire_any_day(reminder, from)?;
+        if allowed_on(reminder, at.date()) {
+            return Some(at);
+        }
+        from = (at.date() + Duration::days(1)).and_time(NaiveTime::MIN);
+    }
+    None
+}
+
+/// The first fire at or after `from` that an exception moved to another
+/// time of its day. An interval reminder's window starts at that time
+/// instead; anything else goes off once, at that time.
+fn next_moved_fire(reminder: &Reminder, from: NaiveDateTime) -> Option<NaiveDateTime> {
+    if reminder.date.is_some() {
+        return None;
+    }
+    reminder
+        .exceptions
+        .iter()
+        .filter_map(|exception| {
+            let day = NaiveDate::parse_from_str(&exception.date, "%Y-%m-%d").ok()?;
+            let time = exception.time.as_deref()?;
+            match reminder.interval_minutes {
+                Some(interval) => {
+                    let moved = Reminder { time: time.to_string(), ..reminder.clone() };
+                    next_interval_fire(&moved, interval, day, from)
+                }
+                None => Some(day.and_time(parse_time(time)?)).filter(|at| *at >= from),
+            }
+        })
+        .min()
+}
+
+/// The day, in the reminder's own timezone, of the next occurrence at or
+/// after the local `from` that isn't left out yet, and the local time it
+/// goes off. What "skip next" skips.
+pub fn next_occurrence(reminder: &Reminder, from: NaiveDateTime) -> Option<(NaiveDate, NaiveDateTime)> {
+    reminder.date.is_none().then_some(())?;
+    let at = next_fire_in_zone(reminder, to_zone(reminder, from))?;
+    Some((at.date(), to_local(reminder, at)))
+}
+
+/// Like `next_fire_in_zone`, ignoring which days the reminder is allowed on.
+fn next_fire_any_day(reminder: &Reminder, from: NaiveDateTime) -> Option<NaiveDateTime> {
+
+    if let Some(expression) = &reminder.cron {
+        return parse_cron(expression).ok()?.find_next_occurrence(&from, true).ok();
+    }
+
+    if let Some(interval) = reminder.interval_minutes {
+        return match &reminder.date {
+            Some(_) => next_interval_fire(reminder, interval, date_of(reminder)?, from),
+            None => next_interval_fire(reminder, interval, from.date(), from)
+                .or_else(|| next_interval_fire(reminder, interval, from.date() + Duration::days(1), from)),
+        };
+    }
+
+    let time = time_of(reminder)?;
+    match &reminder.date {
+        Some(_) => Some(date_of(reminder)?.and_time(time)).filter(|at| *at >= from),
+        None => {
+            let today = from.date().and_time(time);
+            Some(if today >= from { today } else { today + Duration::days(1) })
+        }
+    }
+}
+
+/// When a one-off reminder went off, if that is before `now`.
+pub fn overdue_since(reminder: &Reminder, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    if reminder.cron.is_some() {
+        return None;
+    }
+    let at = to_local(reminder, date_of(reminder)?.and_time(time_of(reminder)?));
+    Some(at).filter(|at| *at < minute_of(now))
+}
+
+/// When a reminder was due before the minute of `now` and is worth chasing
+/// up: a one-off's time, or the first time a repeating one went off today.
+pub fn due_before(reminder: &Reminder, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    overdue_since(reminder, now).or_else(|| {
+        next_fire(reminder, now.date().and_time(NaiveTime::MIN)).filter(|at| at.date() == now.date() && *at < minute_of(now))
+    })
+}
+
+/// Whether `reminder` goes off at some point on the local `day`.
+pub fn fires_on(reminder: &Reminder, day: NaiveDate) -> bool {
+    next_fire(reminder, day.and_time(NaiveTime::MIN)).is_some_and(|at| at.date() == day)
+}
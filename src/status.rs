@@ -0,0 +1,164 @@
+//! `reminder status` and `reminder next`: what's coming up, as lines, as
+//! JSON for a Waybar custom module, or short enough for a tmux status line.
+
+use crate::config::{Config, TimeFormat};
+use crate::open_store;
+use crate::ui::format_duration;
+use chrono::{Duration, Local, NaiveDateTime};
+use reminder::db::Reminder;
+use reminder::schedule;
+use reminder::store::{self, ReminderStore};
+use serde::Serialize;
+use std::error::Error;
+
+/// Within this many minutes the next reminder counts as soon.
+const SOON_MINUTES: i64 = 60;
+
+/// What's coming up, leaving out reminders on vacation.
+struct Status {
+    /// The soonest open reminder and when it goes off.
+    next: Option<(Reminder, NaiveDateTime)>,
+    /// How many open reminders go off today.
+    today: usize,
+}
+
+/// What Waybar reads from a custom module with `"return-type": "json"`,
+/// plus the numbers for scripts that want them.
+#[derive(Serialize)]
+struct Waybar {
+    text: String,
+    tooltip: String,
+    /// `soon`, `later` or `none`, for styling the module.
+    class: &'static str,
+    next: Option<String>,
+    minutes: Option<i64>,
+    today: usize,
+}
+
+pub fn run(config: &Config, waybar: bool) -> Result<(), Box<dyn Error>> {
+    let now = Local::now().naive_local();
+    let status = status(open_store(config)?.as_ref(), now)?;
+    let next = status.next.as_ref().map(|(reminder, at)| {
+        let when = format!("{} {}", at_text(*at, now, config.time_format), in_text(*at, now));
+        (reminder, *at, when)
+    });
+    let today = format!("{} due today", status.today);
+
+    if waybar {
+        let minutes = next.as_ref().map(|(_, at, _)| (*at - schedule::minute_of(now)).num_minutes());
+        let waybar = Waybar {
+            text: next.as_ref().map(|(reminder, at, _)| format!("{} {}", reminder.title, in_text(*at, now))).unwrap_or_default(),
+            tooltip: match &next {
+                Some((reminder, _, when)) => format!("Next: {} at {}\n{}", reminder.title, when, today),
+                None => format!("Nothing coming up\n{}", today),
+            },
+            class: match minutes {
+                Some(minutes) if minutes <= SOON_MINUTES => "soon",
+                Some(_) => "later",
+                None => "none",
+            },
+            next: next.as_ref().map(|(reminder, _, _)| reminder.title.clone()),
+            minutes,
+            today: status.today,
+        };
+        println!("{}", serde_json::to_string(&waybar)?);
+    } else {
+        match &next {
+            Some((reminder, _, when)) => println!("Next: {} at {}", reminder.title, when),
+            None => println!("Nothing coming up"),
+        }
+        println!("{}", today);
+    }
+    Ok(())
+}
+
+/// `reminder next`: the next reminder, e.g. `⏰ Standup 09:30 (in 12m)`
+/// with `short`. Nothing is printed when `within` is given and it isn't due
+/// that soon, nor with `short` when nothing is coming up, so a status line
+/// stays empty.
+pub fn next(config: &Config, short: bool, within: Option<i64>) -> Result<(), Box<dyn Error>> {
+    let now = Local::now().naive_local();
+    let next = status(open_store(config)?.as_ref(), now)?
+        .next
+        .filter(|(_, at)| within.is_none_or(|minutes| *at - schedule::minute_of(now) <= Duration::minutes(minutes)));
+    let when = |at| format!("{} {}", at_text(at, now, config.time_format), in_text(at, now));
+    match next {
+        Some((reminder, at)) if short => println!("⏰ {} {}", reminder.title, when(at)),
+        Some((reminder, at)) => println!("Next: {} at {}", reminder.title, when(at)),
+        None if short || within.is_some() => {}
+        None => println!("Nothing coming up"),
+    }
+    Ok(())
+}
+
+fn status(store: &dyn ReminderStore, now: NaiveDateTime) -> store::Result<Status> {
+    let vacations = store.vacations()?;
+    let on_vacation = |reminder: &Reminder, at: NaiveDateTime| vacations.iter().any(|v| v.covers(reminder, at.date()));
+    let next = store
+        .get_upcoming_reminders(now)?
+        .into_iter()
+        .filter_map(|reminder| Some((schedule::next_fire(&reminder, now)?, reminder)))
+        .find(|(at, reminder)| !on_vacation(reminder, *at))
+        .map(|(at, reminder)| (reminder, at));
+    let today = store.get_today_reminders(now.date())?.iter().filter(|r| !on_vacation(r, now)).count();
+    Ok(Status { next, today })
+}
+
+/// When `at` is: its time today, otherwise with the weekday, or the date
+/// if it's a week or more away.
+fn at_text(at: NaiveDateTime, now: NaiveDateTime, time_format: TimeFormat) -> String {
+    let time = time_format.format(at.time());
+    match (at.date() - now.date()).num_days() {
+        0 => time,
+        1..=6 => format!("{} {}", at.format("%a"), time),
+        _ => format!("{} {}", at.format("%Y-%m-%d"), time),
+    }
+}
+
+/// How long until `at`, e.g. `(in 12m)` or `(now)`.
+fn in_text(at: NaiveDateTime, now: NaiveDateTime) -> String {
+    if at <= now {
+        "(now)".to_string()
+    } else {
+        format!("(in {})", format_duration(at - schedule::minute_of(now)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use reminder::{MemoryStore, NewReminder};
+
+    #[test]
+    fn finds_the_next_reminder_off_vacation() {
+        let store = MemoryStore::new();
+        let add = |title: &str, time: &str, tags: &[&str]| {
+            store
+                .add_reminder(NewReminder {
+                    title: title.to_string(),
+                    time: time.to_string(),
+                    alerts: vec![0],
+                    tags: tags.iter().map(|tag| tag.to_string()).collect(),
+                    ..Default::default()
+                })
+                .unwrap()
+        };
+        add("Standup", "09:30", &["work"]);
+        add("Tea", "16:00", &[]);
+        add("Stretch", "08:00", &[]);
+        let now = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap().and_hms_opt(9, 0, 0).unwrap();
+
+        let found = status(&store, now).unwrap();
+        assert_eq!(found.next.map(|(r, at)| (r.title, at)), Some(("Standup".to_string(), now + Duration::minutes(30))));
+        assert_eq!(found.today, 3);
+
+        store.add_vacation("2026-10-15", "2026-10-15", &["work".to_string()]).unwrap();
+        let found = status(&store, now).unwrap();
+        assert_eq!(found.next.map(|(r, _)| r.title), Some("Tea".to_string()));
+        assert_eq!(found.today, 2);
+
+        assert_eq!(at_text(now + Duration::days(1), now, TimeFormat::H24), "Fri 09:00");
+        assert_eq!(in_text(now + Duration::minutes(150), now), "(in 2h 30m)");
+    }
+}
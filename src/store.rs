@@ -0,0 +1,915 @@
+//! The storage interface the app is written against, and an in-memory store.
+
+use crate::db::{new_uuid, timestamp, Delivery, Exception, LoggedNotification, NewReminder, Priority, Reminder, Vacation};
+use crate::schedule;
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Mutex, MutexGuard};
+
+pub use crate::error::ReminderError as Error;
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Notifications looked through for whether an overdue reminder was shown.
+const OVERDUE_LOG: usize = 500;
+
+/// Where reminders are kept.
+///
+/// A backend only has to implement the required methods; the rest are built
+/// on top of them and can be overridden with something faster, as
+/// [`Database`](crate::Database) does with SQL.
+pub trait ReminderStore {
+    /// Stores a new reminder and returns it with its id.
+    fn add_reminder(&self, new: NewReminder) -> Result<Reminder>;
+
+    /// Every stored reminder, archived and trashed ones included, by id.
+    fn reminders(&self) -> Result<Vec<Reminder>>;
+
+    /// Writes `reminder` exactly as it is, id and all, recreating it if it
+    /// was purged. Used to undo changes and by the default methods.
+    fn restore_reminder(&self, reminder: &Reminder) -> Result<()>;
+
+    /// Permanently deletes a reminder and everything stored with it.
+    fn purge_reminder(&self, id: i32) -> Result<()>;
+
+    /// Schedules one extra notification at `until` (`YYYY-MM-DD HH:MM`),
+    /// replacing any earlier snooze of the same reminder.
+    fn snooze_reminder(&self, id: i32, until: &str) -> Result<()>;
+
+    /// Drops a reminder's pending snooze, if it has one.
+    fn unsnooze_reminder(&self, id: i32) -> Result<()>;
+
+    /// Removes and returns the snoozes that are due at `now`. A snooze is only
+    /// returned to the caller that actually removed it.
+    fn claim_due_snoozes(&self, now: &str) -> Result<Vec<i32>>;
+
+    /// When the earliest pending snooze ends (`YYYY-MM-DD HH:MM`), if any.
+    fn next_snooze(&self) -> Result<Option<String>>;
+
+    /// Records that an alert is being delivered for the occurrence at
+    /// `occurrence` (`YYYY-MM-DD HH:MM`). Returns `false` if it was already
+    /// claimed, so every alert is shown at most once per occurrence.
+    fn claim_notification(&self, reminder_id: i32, offset_minutes: i64, occurrence: &str) -> Result<bool>;
+
+    /// Undoes a claim whose notification failed to show, so it is retried.
+    fn release_notification(&self, reminder_id: i32, offset_minutes: i64, occurrence: &str) -> Result<()>;
+
+    /// Adds a notification to the log.
+    fn log_notification(&self, notification: &LoggedNotification) -> Result<()>;
+
+    /// The last `limit` notifications logged, newest first.
+    fn notification_log(&self, limit: usize) -> Result<Vec<LoggedNotification>>;
+
+    /// The minute (`YYYY-MM-DD HH:MM`) a worker last looked for due alerts,
+    /// or `None` if none ever has.
+    fn last_check(&self) -> Result<Option<String>>;
+
+    /// Records that a worker looked for due alerts in the minute `at`.
+    fn record_check(&self, at: &str) -> Result<()>;
+
+    /// Suspends reminders from `start` to `end` (`YYYY-MM-DD`, both
+    /// included), only those with one of `tags` unless it is empty.
+    fn add_vacation(&self, start: &str, end: &str, tags: &[String]) -> Result<Vacation>;
+
+    /// Every vacation, past ones included, by start.
+    fn vacations(&self) -> Result<Vec<Vacation>>;
+
+    /// Cancels a vacation.
+    fn delete_vacation(&self, id: i32) -> Result<()>;
+
+    /// Vacations that include `day`.
+    fn vacations_on(&self, day: NaiveDate) -> Result<Vec<Vacation>> {
+        let day = day.format("%Y-%m-%d").to_string();
+        Ok(self.vacations()?.into_iter().filter(|v| v.start <= day && day <= v.end).collect())
+    }
+
+    /// Stores several new reminders, e.g. from an import, and returns them
+    /// with their ids.
+    fn add_reminders_batch(&self, new: Vec<NewReminder>) -> Result<Vec<Reminder>> {
+        new.into_iter().map(|new| self.add_reminder(new)).collect()
+    }
+
+    /// The reminder with `id`, unless it doesn't exist or is in the trash.
+    fn get_reminder(&self, id: i32) -> Result<Option<Reminder>> {
+        Ok(self.reminders()?.into_iter().find(|r| r.id == id && r.deleted_at.is_none()))
+    }
+
+    /// A reminder outside the trash that `new` [duplicates](NewReminder::duplicates).
+    fn find_duplicate(&self, new: &NewReminder) -> Result<Option<Reminder>> {
+        Ok(self.reminders()?.into_iter().find(|r| r.deleted_at.is_none() && new.duplicates(r)))
+    }
+
+    /// Every reminder that isn't archived or in the trash: repeating ones by
+    /// time, then dated ones by date and time.
+    fn get_all_reminders(&self) -> Result<Vec<Reminder>> {
+        let mut reminders: Vec<Reminder> = self
+            .reminders()?
+            .into_iter()
+            .filter(|r| r.deleted_at.is_none() && !r.archived)
+            .collect();
+        reminders.sort_by(|a, b| (a.date.is_some(), &a.date, &a.time, a.id).cmp(&(b.date.is_some(), &b.date, &b.time, b.id)));
+        Ok(reminders)
+    }
+
+    /// One page of `get_all_reminders`, in the same order.
+    fn get_reminders_page(&self, offset: usize, limit: usize) -> Result<Vec<Reminder>> {
+        Ok(self.get_all_reminders()?.into_iter().skip(offset).take(limit).collect())
+    }
+
+    /// How many reminders `get_all_reminders` returns.
+    fn count_reminders(&self) -> Result<usize> {
+        Ok(self.get_all_reminders()?.len())
+    }
+
+    /// Open reminders that go off on `today`.
+    fn get_today_reminders(&self, today: NaiveDate) -> Result<Vec<Reminder>> {
+        let mut reminders = self.get_all_reminders()?;
+        reminders.retain(|r| r.completed_at.is_none() && schedule::fires_on(r, today));
+        Ok(reminders)
+    }
+
+    /// Open reminders that will still go off at or after `now`, soonest first.
+    fn get_upcoming_reminders(&self, now: NaiveDateTime) -> Result<Vec<Reminder>> {
+        let mut upcoming: Vec<_> = self
+            .get_all_reminders()?
+            .into_iter()
+            .filter(|r| r.completed_at.is_none())
+            .filter_map(|r| Some((schedule::next_fire(&r, now)?, r)))
+            .collect();
+        upcoming.sort_by_key(|(at, _)| *at);
+        Ok(upcoming.into_iter().map(|(_, r)| r).collect())
+    }
+
+    /// Open reminders with an alert or interval fire due in the local minute
+    /// starting at `now`, for the worker.
+    fn get_due_reminders(&self, now: NaiveDateTime) -> Result<Vec<Reminder>> {
+        let mut due = self.get_all_reminders()?;
+        due.retain(|r| r.completed_at.is_none() && is_due(r, now));
+        Ok(due)
+    }
+
+    /// Open reminders next going off between `now` and `until`, soonest first.
+    fn get_reminders_due_by(&self, now: NaiveDateTime, until: NaiveDateTime) -> Result<Vec<Reminder>> {
+        let mut due = self.get_upcoming_reminders(now)?;
+        due.retain(|r| schedule::next_fire(r, now).is_some_and(|at| at <= until));
+        Ok(due)
+    }
+
+    /// Open reminders that were due before the minute of `now` without a
+    /// notification being delivered for them since, counting from their
+    /// earliest alert.
+    fn get_overdue_reminders(&self, now: NaiveDateTime) -> Result<Vec<Reminder>> {
+        let log = self.notification_log(OVERDUE_LOG)?;
+        let mut overdue = self.get_all_reminders()?;
+        overdue.retain(|r| {
+            r.completed_at.is_none()
+                && chased_since(r, now).is_some_and(|since| {
+                    !log.iter().any(|n| n.reminder_id == Some(r.id) && n.delivery == Delivery::Delivered && n.at >= since)
+                })
+        });
+        Ok(overdue)
+    }
+
+    /// One page of reminders marked done, most recently finished first.
+    fn get_done_reminders_page(&self, offset: usize, limit: usize) -> Result<Vec<Reminder>> {
+        let mut done: Vec<Reminder> = self
+            .reminders()?
+            .into_iter()
+            .filter(|r| r.completed_at.is_some() && r.deleted_at.is_none())
+            .collect();
+        done.sort_by(|a, b| b.completed_at.cmp(&a.completed_at).then(b.id.cmp(&a.id)));
+        Ok(done.into_iter().skip(offset).take(limit).collect())
+    }
+
+    /// How many reminders are marked done.
+    fn count_done_reminders(&self) -> Result<usize> {
+        Ok(self.reminders()?.iter().filter(|r| r.completed_at.is_some() && r.deleted_at.is_none()).count())
+    }
+
+    /// Archived one-off reminders, most recent first.
+    fn get_archived_reminders(&self) -> Result<Vec<Reminder>> {
+        let mut archived: Vec<Reminder> =
+            self.reminders()?.into_iter().filter(|r| r.archived && r.deleted_at.is_none()).collect();
+        archived.sort_by(|a, b| (&b.date, &b.time).cmp(&(&a.date, &a.time)));
+        Ok(archived)
+    }
+
+    /// Reminders in the trash, most recently deleted first.
+    fn get_trash_reminders(&self) -> Result<Vec<Reminder>> {
+        let mut trash: Vec<Reminder> = self.reminders()?.into_iter().filter(|r| r.deleted_at.is_some()).collect();
+        trash.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
+        Ok(trash)
+    }
+
+    /// Writes the editable fields of `reminder` back to the stored one,
+    /// taking it out of the archive so its schedule is looked at again.
+    fn update_reminder(&self, reminder: &Reminder) -> Result<()> {
+        let Some(stored) = self.reminders()?.into_iter().find(|r| r.id == reminder.id) else {
+            return Ok(());
+        };
+        self.restore_reminder(&Reminder {
+            last_fired_at: stored.last_fired_at,
+            created_at: stored.created_at,
+            updated_at: timestamp(),
+            completed_at: stored.completed_at,
+            deleted_at: stored.deleted_at,
+            archived: false,
+            ..reminder.clone()
+        })
+    }
+
+    /// Marks a reminder done at `at` (`YYYY-MM-DD HH:MM`), or open again for `None`.
+    fn set_completed(&self, id: i32, at: Option<&str>) -> Result<()> {
+        modify(self, id, |r| r.completed_at = at.map(str::to_string))
+    }
+
+    /// Marks several reminders done (or open again).
+    fn set_completed_many(&self, ids: &[i32], at: Option<&str>) -> Result<()> {
+        ids.iter().try_for_each(|&id| self.set_completed(id, at))
+    }
+
+    /// Gives several reminders the same priority.
+    fn set_priority(&self, ids: &[i32], priority: Priority) -> Result<()> {
+        ids.iter().try_for_each(|&id| modify(self, id, |r| r.priority = priority))
+    }
+
+    /// Sets how a reminder's notifications look, leaving everything else,
+    /// such as whether it is archived, as it is.
+    fn set_notification_settings(&self, id: i32, icon: Option<&str>, category: Option<&str>, resident: bool) -> Result<()> {
+        modify(self, id, |r| {
+            r.icon = icon.map(str::to_string);
+            r.category = category.map(str::to_string);
+            r.resident = resident;
+        })
+    }
+
+    /// Skips or changes the occurrence of a reminder on `exception.date`,
+    /// replacing any earlier exception for that day.
+    fn add_exception(&self, id: i32, exception: &Exception) -> Result<()> {
+        modify(self, id, |r| r.set_exception(exception.clone()))
+    }
+
+    /// Puts `date` (`YYYY-MM-DD`) back on a reminder's usual schedule.
+    fn remove_exception(&self, id: i32, date: &str) -> Result<()> {
+        modify(self, id, |r| r.remove_exception(date))
+    }
+
+    /// Archives one-off reminders that won't go off again after `now`.
+    /// Returns how many were archived.
+    fn archive_past(&self, now: NaiveDateTime) -> Result<usize> {
+        let past: Vec<Reminder> = self
+            .get_all_reminders()?
+            .into_iter()
+            .filter(|r| r.cron.is_none() && r.date.is_some() && schedule::next_fire(r, now).is_none())
+            .collect();
+        for reminder in &past {
+            self.restore_reminder(&Reminder { archived: true, ..reminder.clone() })?;
+        }
+        Ok(past.len())
+    }
+
+    /// Moves a reminder to the trash. It stops firing and can be restored
+    /// until it is purged.
+    fn delete_reminder(&self, id: i32) -> Result<()> {
+        let now = Local::now().format("%Y-%m-%d %H:%M").to_string();
+        modify(self, id, |r| r.deleted_at = Some(now))?;
+        self.unsnooze_reminder(id)
+    }
+
+    /// Moves several reminders to the trash.
+    fn delete_reminders(&self, ids: &[i32]) -> Result<()> {
+        ids.iter().try_for_each(|&id| self.delete_reminder(id))
+    }
+
+    /// Takes a reminder back out of the trash.
+    fn restore_from_trash(&self, id: i32) -> Result<()> {
+        modify(self, id, |r| r.deleted_at = None)
+    }
+
+    /// Permanently deletes several reminders.
+    fn purge_reminders(&self, ids: &[i32]) -> Result<()> {
+        ids.iter().try_for_each(|&id| self.purge_reminder(id))
+    }
+
+    /// Permanently deletes reminders that have been in the trash since before
+    /// `before` (`YYYY-MM-DD HH:MM`).
+    fn purge_trash(&self, before: &str) -> Result<usize> {
+        let ids: Vec<i32> = self
+            .reminders()?
+            .iter()
+            .filter(|r| r.deleted_at.as_deref().is_some_and(|at| at < before))
+            .map(|r| r.id)
+            .collect();
+        self.purge_reminders(&ids)?;
+        Ok(ids.len())
+    }
+
+    /// Moves an interval reminder's `last_fired_at` from `previous` to `at`.
+    /// Returns `false` if it no longer equals `previous`, i.e. it was fired
+    /// by someone else first.
+    fn claim_interval_fire(&self, id: i32, previous: Option<&str>, at: Option<&str>) -> Result<bool> {
+        match self.reminders()?.into_iter().find(|r| r.id == id) {
+            Some(reminder) if reminder.last_fired_at.as_deref() == previous => {
+                self.restore_reminder(&Reminder { last_fired_at: at.map(str::to_string), ..reminder })?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Reminders outside the trash whose title or description contains every
+    /// word of `query`, ignoring case, by time.
+    fn search_reminders(&self, query: &str) -> Result<Vec<Reminder>> {
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        let mut found: Vec<Reminder> = self
+            .reminders()?
+            .into_iter()
+            .filter(|r| r.deleted_at.is_none())
+            .filter(|r| {
+                let text = format!("{}\n{}", r.title, r.description).to_lowercase();
+                words.iter().all(|word| text.contains(word.as_str()))
+            })
+            .collect();
+        found.sort_by(|a, b| a.time.cmp(&b.time).then(a.id.cmp(&b.id)));
+        Ok(found)
+    }
+
+    /// Titles used before that start with `prefix`, most recently used first.
+    fn title_suggestions(&self, prefix: &str, limit: usize) -> Result<Vec<String>> {
+        let prefix = prefix.to_lowercase();
+        let mut reminders = self.reminders()?;
+        reminders.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        let mut titles: Vec<String> = Vec::new();
+        for title in reminders.into_iter().map(|r| r.title) {
+            if title.to_lowercase().starts_with(&prefix) && title.to_lowercase() != prefix && !titles.contains(&title) {
+                titles.push(title);
+            }
+        }
+        titles.truncate(limit);
+        Ok(titles)
+    }
+
+    /// Tags used before that start with `prefix`, most used first.
+    fn tag_suggestions(&self, prefix: &str, limit: usize) -> Result<Vec<String>> {
+        let prefix = prefix.to_lowercase();
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for tag in self.reminders()?.into_iter().flat_map(|r| r.tags) {
+            if tag.starts_with(&prefix) {
+                *counts.entry(tag).or_default() += 1;
+            }
+        }
+        let mut tags: Vec<(String, usize)> = counts.into_iter().collect();
+        tags.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+        Ok(tags.into_iter().take(limit).map(|(tag, _)| tag).collect())
+    }
+
+    /// The reminders of `get_all_reminders` tagged `tag`, in the same order.
+    fn get_tagged_reminders(&self, tag: &str) -> Result<Vec<Reminder>> {
+        let mut tagged = self.get_all_reminders()?;
+        tagged.retain(|r| r.tags.iter().any(|t| t == tag));
+        Ok(tagged)
+    }
+
+    /// Every tag on the reminders of `get_all_reminders`, with how many have
+    /// it, by name.
+    fn tag_counts(&self) -> Result<Vec<(String, usize)>> {
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for tag in self.get_all_reminders()?.into_iter().flat_map(|r| r.tags) {
+            *counts.entry(tag).or_default() += 1;
+        }
+        Ok(counts.into_iter().collect())
+    }
+}
+
+/// When a notification for `reminder` would have had to be delivered to
+/// count for its last time due before `now`: its earliest alert before that
+/// time, as the notification log writes it. `None` if it wasn't due.
+pub(crate) fn chased_since(reminder: &Reminder, now: NaiveDateTime) -> Option<String> {
+    let due = schedule::due_before(reminder, now)?;
+    let earliest = due - Duration::minutes(reminder.alerts.iter().copied().max().unwrap_or(0));
+    Some(earliest.format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+/// Whether `reminder` has something to deliver in the minute starting at `now`.
+pub(crate) fn is_due(reminder: &Reminder, now: NaiveDateTime) -> bool {
+    if reminder.interval_minutes.is_some() {
+        schedule::fires_at(reminder, now)
+    } else {
+        !schedule::due_alerts(reminder, now).is_empty()
+    }
+}
+
+/// Applies `change` to the stored reminder with `id`, trashed or not.
+fn modify<S: ReminderStore + ?Sized>(store: &S, id: i32, change: impl FnOnce(&mut Reminder)) -> Result<()> {
+    if let Some(mut reminder) = store.reminders()?.into_iter().find(|r| r.id == id) {
+        change(&mut reminder);
+        reminder.touch();
+        store.restore_reminder(&reminder)?;
+    }
+    Ok(())
+}
+
+/// A store several threads can use at once, such as the TUI, the worker and
+/// the servers sharing one database connection. Each call holds a lock for
+/// as long as it runs.
+pub struct SharedStore {
+    inner: Mutex<Box<dyn ReminderStore + Send>>,
+}
+
+impl SharedStore {
+    pub fn new(store: Box<dyn ReminderStore + Send>) -> Self {
+        SharedStore { inner: Mutex::new(store) }
+    }
+
+    /// Locks the store for several calls in a row, e.g. to read a reminder
+    /// and write it back without anyone changing it in between.
+    pub fn lock(&self) -> MutexGuard<'_, Box<dyn ReminderStore + Send>> {
+        // A panic mid-call leaves nothing half-written that the next call could trip over.
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Swaps in another store, e.g. when the TUI switches profile. Everyone
+    /// sharing this one uses it from their next call.
+    pub fn replace(&self, store: Box<dyn ReminderStore + Send>) {
+        *self.lock() = store;
+    }
+}
+
+impl ReminderStore for SharedStore {
+    fn add_reminder(&self, new: NewReminder) -> Result<Reminder> {
+        self.lock().add_reminder(new)
+    }
+
+    fn reminders(&self) -> Result<Vec<Reminder>> {
+        self.lock().reminders()
+    }
+
+    fn restore_reminder(&self, reminder: &Reminder) -> Result<()> {
+        self.lock().restore_reminder(reminder)
+    }
+
+    fn purge_reminder(&self, id: i32) -> Result<()> {
+        self.lock().purge_reminder(id)
+    }
+
+    fn snooze_reminder(&self, id: i32, until: &str) -> Result<()> {
+        self.lock().snooze_reminder(id, until)
+    }
+
+    fn unsnooze_reminder(&self, id: i32) -> Result<()> {
+        self.lock().unsnooze_reminder(id)
+    }
+
+    fn claim_due_snoozes(&self, now: &str) -> Result<Vec<i32>> {
+        self.lock().claim_due_snoozes(now)
+    }
+
+    fn next_snooze(&self) -> Result<Option<String>> {
+        self.lock().next_snooze()
+    }
+
+    fn claim_notification(&self, reminder_id: i32, offset_minutes: i64, occurrence: &str) -> Result<bool> {
+        self.lock().claim_notification(reminder_id, offset_minutes, occurrence)
+    }
+
+    fn release_notification(&self, reminder_id: i32, offset_minutes: i64, occurrence: &str) -> Result<()> {
+        self.lock().release_notification(reminder_id, offset_minutes, occurrence)
+    }
+
+    fn log_notification(&self, notification: &LoggedNotification) -> Result<()> {
+        self.lock().log_notification(notification)
+    }
+
+    fn notification_log(&self, limit: usize) -> Result<Vec<LoggedNotification>> {
+        self.lock().notification_log(limit)
+    }
+
+    fn last_check(&self) -> Result<Option<String>> {
+        self.lock().last_check()
+    }
+
+    fn record_check(&self, at: &str) -> Result<()> {
+        self.lock().record_check(at)
+    }
+
+    fn add_vacation(&self, start: &str, end: &str, tags: &[String]) -> Result<Vacation> {
+        self.lock().add_vacation(start, end, tags)
+    }
+
+    fn vacations(&self) -> Result<Vec<Vacation>> {
+        self.lock().vacations()
+    }
+
+    fn delete_vacation(&self, id: i32) -> Result<()> {
+        self.lock().delete_vacation(id)
+    }
+
+    fn vacations_on(&self, day: NaiveDate) -> Result<Vec<Vacation>> {
+        self.lock().vacations_on(day)
+    }
+
+    fn add_reminders_batch(&self, new: Vec<NewReminder>) -> Result<Vec<Reminder>> {
+        self.lock().add_reminders_batch(new)
+    }
+
+    fn get_reminder(&self, id: i32) -> Result<Option<Reminder>> {
+        self.lock().get_reminder(id)
+    }
+
+    fn find_duplicate(&self, new: &NewReminder) -> Result<Option<Reminder>> {
+        self.lock().find_duplicate(new)
+    }
+
+    fn get_all_reminders(&self) -> Result<Vec<Reminder>> {
+        self.lock().get_all_reminders()
+    }
+
+    fn get_reminders_page(&self, offset: usize, limit: usize) -> Result<Vec<Reminder>> {
+        self.lock().get_reminders_page(offset, limit)
+    }
+
+    fn count_reminders(&self) -> Result<usize> {
+        self.lock().count_reminders()
+    }
+
+    fn get_today_reminders(&self, today: NaiveDate) -> Result<Vec<Reminder>> {
+        self.lock().get_today_reminders(today)
+    }
+
+    fn get_upcoming_reminders(&self, now: NaiveDateTime) -> Result<Vec<Reminder>> {
+        self.lock().get_upcoming_reminders(now)
+    }
+
+    fn get_due_reminders(&self, now: NaiveDateTime) -> Result<Vec<Reminder>> {
+        self.lock().get_due_reminders(now)
+    }
+
+    fn get_reminders_due_by(&self, now: NaiveDateTime, until: NaiveDateTime) -> Result<Vec<Reminder>> {
+        self.lock().get_reminders_due_by(now, until)
+    }
+
+    fn get_overdue_reminders(&self, now: NaiveDateTime) -> Result<Vec<Reminder>> {
+        self.lock().get_overdue_reminders(now)
+    }
+
+    fn get_done_reminders_page(&self, offset: usize, limit: usize) -> Result<Vec<Reminder>> {
+        self.lock().get_done_reminders_page(offset, limit)
+    }
+
+    fn count_done_reminders(&self) -> Result<usize> {
+        self.lock().count_done_reminders()
+    }
+
+    fn get_archived_reminders(&self) -> Result<Vec<Reminder>> {
+        self.lock().get_archived_reminders()
+    }
+
+    fn get_trash_reminders(&self) -> Result<Vec<Reminder>> {
+        self.lock().get_trash_reminders()
+    }
+
+    fn update_reminder(&self, reminder: &Reminder) -> Result<()> {
+        self.lock().update_reminder(reminder)
+    }
+
+    fn set_completed(&self, id: i32, at: Option<&str>) -> Result<()> {
+        self.lock().set_completed(id, at)
+    }
+
+    fn set_completed_many(&self, ids: &[i32], at: Option<&str>) -> Result<()> {
+        self.lock().set_completed_many(ids, at)
+    }
+
+    fn set_priority(&self, ids: &[i32], priority: Priority) -> Result<()> {
+        self.lock().set_priority(ids, priority)
+    }
+
+    fn set_notification_settings(&self, id: i32, icon: Option<&str>, category: Option<&str>, resident: bool) -> Result<()> {
+        self.lock().set_notification_settings(id, icon, category, resident)
+    }
+
+    fn add_exception(&self, id: i32, exception: &Exception) -> Result<()> {
+        self.lock().add_exception(id, exception)
+    }
+
+    fn remove_exception(&self, id: i32, date: &str) -> Result<()> {
+        self.lock().remove_exception(id, date)
+    }
+
+    fn archive_past(&self, now: NaiveDateTime) -> Result<usize> {
+        self.lock().archive_past(now)
+    }
+
+    fn delete_reminder(&self, id: i32) -> Result<()> {
+        self.lock().delete_reminder(id)
+    }
+
+    fn delete_reminders(&self, ids: &[i32]) -> Result<()> {
+        self.lock().delete_reminders(ids)
+    }
+
+    fn restore_from_trash(&self, id: i32) -> Result<()> {
+        self.lock().restore_from_trash(id)
+    }
+
+    fn purge_reminders(&self, ids: &[i32]) -> Result<()> {
+        self.lock().purge_reminders(ids)
+    }
+
+    fn purge_trash(&self, before: &str) -> Result<usize> {
+        self.lock().purge_trash(before)
+    }
+
+    fn claim_interval_fire(&self, id: i32, previous: Option<&str>, at: Option<&str>) -> Result<bool> {
+        self.lock().claim_interval_fire(id, previous, at)
+    }
+
+    fn search_reminders(&self, query: &str) -> Result<Vec<Reminder>> {
+        self.lock().search_reminders(query)
+    }
+
+    fn title_suggestions(&self, prefix: &str, limit: usize) -> Result<Vec<String>> {
+        self.lock().title_suggestions(prefix, limit)
+    }
+
+    fn tag_suggestions(&self, prefix: &str, limit: usize) -> Result<Vec<String>> {
+        self.lock().tag_suggestions(prefix, limit)
+    }
+
+    fn get_tagged_reminders(&self, tag: &str) -> Result<Vec<Reminder>> {
+        self.lock().get_tagged_reminders(tag)
+    }
+
+    fn tag_counts(&self) -> Result<Vec<(String, usize)>> {
+        self.lock().tag_counts()
+    }
+}
+
+/// Reminders kept in memory and lost when it is dropped. Meant for tests and
+/// for embedding without a database file.
+#[derive(Default)]
+pub struct MemoryStore {
+    inner: Mutex<Memory>,
+}
+
+#[derive(Default)]
+struct Memory {
+    reminders: BTreeMap<i32, Reminder>,
+    next_id: i32,
+    snoozes: HashMap<i32, String>,
+    notified: HashSet<(i32, i64, String)>,
+    notification_log: Vec<LoggedNotification>,
+    last_check: Option<String>,
+    vacations: Vec<Vacation>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Memory> {
+        // Nothing in here can be left half-written, so a poisoned lock is fine to reuse.
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl ReminderStore for MemoryStore {
+    fn add_reminder(&self, new: NewReminder) -> Result<Reminder> {
+        let mut memory = self.lock();
+        memory.next_id += 1;
+        let reminder = Reminder {
+            id: memory.next_id,
+            uuid: new.uuid.unwrap_or_else(new_uuid),
+            title: new.title,
+            description: new.description,
+            time: new.time,
+            created_at: Local::now().to_rfc3339(),
+            updated_at: timestamp(),
+            alerts: new.alerts,
+            date: new.date,
+            tags: new.tags,
+            cron: new.cron,
+            interval_minutes: new.interval_minutes,
+            until: new.until,
+            weekdays: new.weekdays,
+            last_fired_at: None,
+            timezone: new.timezone,
+            completed_at: None,
+            deleted_at: None,
+            archived: false,
+            delete_after_firing: new.delete_after_firing,
+            skip_holidays: new.skip_holidays,
+            exceptions: Vec::new(),
+            icon: new.icon,
+            category: new.category,
+            resident: new.resident,
+            priority: new.priority,
+        };
+        memory.reminders.insert(reminder.id, reminder.clone());
+        Ok(reminder)
+    }
+
+    fn reminders(&self) -> Result<Vec<Reminder>> {
+        Ok(self.lock().reminders.values().cloned().collect())
+    }
+
+    fn restore_reminder(&self, reminder: &Reminder) -> Result<()> {
+        let mut memory = self.lock();
+        memory.next_id = memory.next_id.max(reminder.id);
+        memory.reminders.insert(reminder.id, reminder.clone());
+        Ok(())
+    }
+
+    fn purge_reminder(&self, id: i32) -> Result<()> {
+        let mut memory = self.lock();
+        memory.reminders.remove(&id);
+        memory.snoozes.remove(&id);
+        memory.notified.retain(|(reminder_id, _, _)| *reminder_id != id);
+        Ok(())
+    }
+
+    fn snooze_reminder(&self, id: i32, until: &str) -> Result<()> {
+        self.lock().snoozes.insert(id, until.to_string());
+        Ok(())
+    }
+
+    fn unsnooze_reminder(&self, id: i32) -> Result<()> {
+        self.lock().snoozes.remove(&id);
+        Ok(())
+    }
+
+    fn claim_due_snoozes(&self, now: &str) -> Result<Vec<i32>> {
+        let mut memory = self.lock();
+        let due: Vec<i32> = memory.snoozes.iter().filter(|(_, until)| until.as_str() <= now).map(|(id, _)| *id).collect();
+        for id in &due {
+            memory.snoozes.remove(id);
+        }
+        Ok(due)
+    }
+
+    fn next_snooze(&self) -> Result<Option<String>> {
+        Ok(self.lock().snoozes.values().min().cloned())
+    }
+
+    fn claim_notification(&self, reminder_id: i32, offset_minutes: i64, occurrence: &str) -> Result<bool> {
+        Ok(self.lock().notified.insert((reminder_id, offset_minutes, occurrence.to_string())))
+    }
+
+    fn release_notification(&self, reminder_id: i32, offset_minutes: i64, occurrence: &str) -> Result<()> {
+        self.lock().notified.remove(&(reminder_id, offset_minutes, occurrence.to_string()));
+        Ok(())
+    }
+
+    fn log_notification(&self, notification: &LoggedNotification) -> Result<()> {
+        self.lock().notification_log.push(notification.clone());
+        Ok(())
+    }
+
+    fn notification_log(&self, limit: usize) -> Result<Vec<LoggedNotification>> {
+        Ok(self.lock().notification_log.iter().rev().take(limit).cloned().collect())
+    }
+
+    fn last_check(&self) -> Result<Option<String>> {
+        Ok(self.lock().last_check.clone())
+    }
+
+    fn record_check(&self, at: &str) -> Result<()> {
+        self.lock().last_check = Some(at.to_string());
+        Ok(())
+    }
+
+    fn add_vacation(&self, start: &str, end: &str, tags: &[String]) -> Result<Vacation> {
+        let mut memory = self.lock();
+        let vacation = Vacation {
+            id: memory.vacations.iter().map(|v| v.id).max().unwrap_or(0) + 1,
+            start: start.to_string(),
+            end: end.to_string(),
+            tags: tags.to_vec(),
+        };
+        memory.vacations.push(vacation.clone());
+        Ok(vacation)
+    }
+
+    fn vacations(&self) -> Result<Vec<Vacation>> {
+        let mut vacations = self.lock().vacations.clone();
+        vacations.sort_by(|a, b| (&a.start, a.id).cmp(&(&b.start, b.id)));
+        Ok(vacations)
+    }
+
+    fn delete_vacation(&self, id: i32) -> Result<()> {
+        self.lock().vacations.retain(|v| v.id != id);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add(store: &MemoryStore, title: &str, time: &str, date: Option<&str>) -> Reminder {
+        store
+            .add_reminder(NewReminder {
+                title: title.to_string(),
+                time: time.to_string(),
+                date: date.map(str::to_string),
+                alerts: vec![0],
+                ..Default::default()
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn trash_hides_reminders_until_restored() {
+        let store = MemoryStore::new();
+        let tea = add(&store, "Tea", "16:00", None);
+        add(&store, "Standup", "09:30", None);
+
+        store.delete_reminder(tea.id).unwrap();
+        assert_eq!(store.get_all_reminders().unwrap().len(), 1);
+        assert!(store.get_reminder(tea.id).unwrap().is_none());
+        assert_eq!(store.get_trash_reminders().unwrap()[0].id, tea.id);
+
+        store.restore_from_trash(tea.id).unwrap();
+        let titles: Vec<String> = store.get_all_reminders().unwrap().into_iter().map(|r| r.title).collect();
+        assert_eq!(titles, ["Standup", "Tea"]);
+    }
+
+    #[test]
+    fn alerts_are_claimed_once() {
+        let store = MemoryStore::new();
+        let tea = add(&store, "Tea", "16:00", None);
+        assert!(store.claim_notification(tea.id, 0, "2026-10-15 16:00").unwrap());
+        assert!(!store.claim_notification(tea.id, 0, "2026-10-15 16:00").unwrap());
+        store.release_notification(tea.id, 0, "2026-10-15 16:00").unwrap();
+        assert!(store.claim_notification(tea.id, 0, "2026-10-15 16:00").unwrap());
+    }
+
+    #[test]
+    fn archives_one_off_reminders_once_past() {
+        let store = MemoryStore::new();
+        add(&store, "Dentist", "10:00", Some("2026-10-14"));
+        add(&store, "Tea", "16:00", None);
+        let now = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap().and_hms_opt(9, 0, 0).unwrap();
+
+        assert_eq!(store.archive_past(now).unwrap(), 1);
+        assert_eq!(store.get_archived_reminders().unwrap()[0].title, "Dentist");
+        assert_eq!(store.get_today_reminders(now.date()).unwrap()[0].title, "Tea");
+    }
+
+    #[test]
+    fn setting_the_priority_or_notifications_leaves_the_rest_alone() {
+        let stores: [Box<dyn ReminderStore>; 2] =
+            [Box::new(crate::Database::new(":memory:").unwrap()), Box::new(MemoryStore::new())];
+        for store in stores {
+            let dentist = store
+                .add_reminder(NewReminder {
+                    title: "Dentist".to_string(),
+                    time: "10:00".to_string(),
+                    date: Some("2026-10-14".to_string()),
+                    ..Default::default()
+                })
+                .unwrap();
+            let tea =
+                store.add_reminder(NewReminder { title: "Tea".to_string(), time: "16:00".to_string(), ..Default::default() }).unwrap();
+            store.archive_past(NaiveDate::from_ymd_opt(2026, 10, 15).unwrap().and_hms_opt(9, 0, 0).unwrap()).unwrap();
+
+            store.set_priority(&[dentist.id, tea.id], Priority::High).unwrap();
+            store.set_notification_settings(dentist.id, Some("dialog-warning"), None, true).unwrap();
+            let archived = store.get_archived_reminders().unwrap();
+            assert_eq!((archived[0].id, archived[0].priority), (dentist.id, Priority::High));
+            assert_eq!((archived[0].icon.as_deref(), archived[0].resident), (Some("dialog-warning"), true));
+            let open = store.get_all_reminders().unwrap();
+            assert_eq!((open[0].id, open[0].priority), (tea.id, Priority::High));
+        }
+    }
+
+    #[test]
+    fn weekday_reminders_skip_the_weekend() {
+        let store = MemoryStore::new();
+        let standup = add(&store, "Standup", "09:00", None);
+        store.update_reminder(&Reminder { weekdays: Some("weekdays".to_string()), ..standup }).unwrap();
+        let friday = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        let at = |day: NaiveDate| day.and_hms_opt(9, 0, 0).unwrap();
+
+        assert_eq!(store.get_due_reminders(at(friday)).unwrap().len(), 1);
+        assert!(store.get_due_reminders(at(friday + chrono::Duration::days(1))).unwrap().is_empty());
+        let next = schedule::next_fire(&store.get_all_reminders().unwrap()[0], at(friday) + chrono::Duration::minutes(1));
+        assert_eq!(next, Some(at(friday + chrono::Duration::days(3))));
+    }
+
+    #[test]
+    fn a_moved_occurrence_replaces_that_days_usual_one() {
+        let store = MemoryStore::new();
+        let standup = add(&store, "Standup", "09:00", None);
+        let moved = Exception { date: "2026-10-16".to_string(), time: Some("10:30".to_string()), note: Some("Room 4B".to_string()) };
+        store.add_exception(standup.id, &moved).unwrap();
+        let friday = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        let at = |hour, minute| friday.and_hms_opt(hour, minute, 0).unwrap();
+
+        assert!(store.get_due_reminders(at(9, 0)).unwrap().is_empty());
+        let due = store.get_due_reminders(at(10, 30)).unwrap();
+        assert_eq!(schedule::note_at(&due[0], at(10, 30)), Some("Room 4B"));
+        assert_eq!(schedule::usual_time(&due[0], friday), chrono::NaiveTime::from_hms_opt(9, 0, 0));
+        assert_eq!(schedule::next_fire(&due[0], at(10, 31)), Some(at(9, 0) + chrono::Duration::days(1)));
+    }
+}
@@ -0,0 +1,268 @@
+//! `reminder sync`: mirrors reminders to a directory holding one JSON file per
+//! reminder, named after its UUID, so they can be versioned in git or shared
+//! through a Syncthing folder.
+
+use crate::config::Config;
+use crate::db::Reminder;
+use crate::open_store;
+use crate::worker::trash_cutoff;
+use chrono::{DateTime, Local};
+use serde_json::Value;
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// The directory given on the command line, or the configured one.
+fn sync_dir(config: &Config, dir: Option<&Path>) -> Result<PathBuf, Box<dyn Error>> {
+    match (dir, &config.sync) {
+        (Some(dir), _) => Ok(dir.to_path_buf()),
+        (None, Some(sync)) => Ok(sync.dir.clone()),
+        (None, None) => Err("give a directory, or set `dir` under [sync] in the config".into()),
+    }
+}
+
+/// A reminder's file: everything but the id, which only means something in
+/// one database and would make every machine's copy differ.
+fn to_file(reminder: &Reminder) -> String {
+    let mut value = serde_json::to_value(reminder).unwrap_or_default();
+    if let Some(object) = value.as_object_mut() {
+        object.remove("id");
+    }
+    let mut text = serde_json::to_string_pretty(&value).unwrap_or_default();
+    text.push('\n');
+    text
+}
+
+fn from_file(text: &str) -> Result<Reminder, String> {
+    let mut value: Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    if let Some(object) = value.as_object_mut() {
+        object.insert("id".to_string(), 0.into());
+    }
+    let reminder: Reminder = serde_json::from_value(value).map_err(|e| e.to_string())?;
+    if reminder.uuid.is_empty() {
+        return Err("missing uuid".to_string());
+    }
+    Ok(reminder)
+}
+
+/// The `.json` files in `dir`.
+fn reminder_files(dir: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// A reminder that differs between our copy and theirs, and which was kept.
+pub(crate) struct Conflict {
+    title: String,
+    pub(crate) kept_theirs: bool,
+    ours: String,
+    theirs: String,
+}
+
+/// Compares our copy of a reminder with theirs: `None` if they match apart
+/// from the id, otherwise the one changed last wins, ours on a tie.
+pub(crate) fn resolve(ours: &Reminder, theirs: &Reminder) -> Option<Conflict> {
+    if *ours == (Reminder { id: ours.id, ..theirs.clone() }) {
+        return None;
+    }
+    Some(Conflict {
+        title: theirs.title.clone(),
+        kept_theirs: theirs.updated_at > ours.updated_at,
+        ours: ours.updated_at.clone(),
+        theirs: theirs.updated_at.clone(),
+    })
+}
+
+/// A `YYYY-MM-DD HH:MM:SS` local time for an `updated_at` timestamp.
+fn changed_at(updated_at: &str) -> String {
+    match DateTime::parse_from_rfc3339(updated_at) {
+        Ok(at) => at.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string(),
+        Err(_) => "an unknown time".to_string(),
+    }
+}
+
+/// Lists `conflicts` with both change times, calling the other side `theirs`.
+pub(crate) fn report(conflicts: &[Conflict], theirs: &str) {
+    for conflict in conflicts {
+        let (kept, kept_at, lost, lost_at) = if conflict.kept_theirs {
+            (theirs, &conflict.theirs, "this database", &conflict.ours)
+        } else {
+            ("this database", &conflict.ours, theirs, &conflict.theirs)
+        };
+        println!(
+            "  {:?}: kept {} (changed {}) over {} (changed {})",
+            conflict.title,
+            kept,
+            changed_at(kept_at),
+            lost,
+            changed_at(lost_at)
+        );
+    }
+}
+
+/// Whether `reminder` sat in the trash long enough to be purged by `cutoff`.
+fn purged(reminder: &Reminder, cutoff: &str) -> bool {
+    reminder.deleted_at.as_deref().is_some_and(|at| at < cutoff)
+}
+
+/// What importing `files` into a store holding `existing` changes: the
+/// reminders to write, and the files passed over because our copy changed
+/// later. A file changed after our copy replaces it under the same id; one
+/// we don't have gets a new id unless it was trashed before `cutoff`.
+fn plan(existing: &[Reminder], files: Vec<Reminder>, cutoff: &str) -> (Vec<Reminder>, Vec<Conflict>) {
+    let by_uuid: HashMap<&str, &Reminder> = existing.iter().map(|r| (r.uuid.as_str(), r)).collect();
+    let mut next_id = existing.iter().map(|r| r.id).max().unwrap_or(0);
+    let mut write = Vec::new();
+    let mut conflicts = Vec::new();
+    for reminder in files {
+        match by_uuid.get(reminder.uuid.as_str()) {
+            Some(stored) => {
+                match resolve(stored, &reminder) {
+                    Some(conflict) if conflict.kept_theirs => write.push(Reminder { id: stored.id, ..reminder }),
+                    Some(conflict) => conflicts.push(conflict),
+                    None => {}
+                }
+            }
+            None if purged(&reminder, cutoff) => {}
+            None => {
+                next_id += 1;
+                write.push(Reminder { id: next_id, ..reminder });
+            }
+        }
+    }
+    (write, conflicts)
+}
+
+/// `reminder sync export`: writes every reminder, done and trashed ones
+/// included, to its file, unless the file holds a later change. Unchanged
+/// files are left alone so git and Syncthing only see real changes. Files of
+/// reminders other machines added stay; only those long in the trash go.
+pub fn export(config: &Config, dir: Option<&Path>) -> Result<(), Box<dyn Error>> {
+    let dir = sync_dir(config, dir)?;
+    fs::create_dir_all(&dir)?;
+    let reminders = open_store(config)?.reminders()?;
+
+    let mut written = 0;
+    let mut conflicts = Vec::new();
+    for reminder in &reminders {
+        let path = dir.join(format!("{}.json", reminder.uuid));
+        let text = to_file(reminder);
+        let current = fs::read_to_string(&path).ok();
+        if current.as_deref() == Some(text.as_str()) {
+            continue;
+        }
+        let file = current.and_then(|current| from_file(&current).ok());
+        if let Some(conflict) = file.and_then(|file| resolve(reminder, &file)).filter(|c| c.kept_theirs) {
+            conflicts.push(conflict);
+            continue;
+        }
+        fs::write(&path, text)?;
+        written += 1;
+    }
+
+    let uuids: HashSet<&str> = reminders.iter().map(|r| r.uuid.as_str()).collect();
+    let cutoff = trash_cutoff(Local::now());
+    let mut removed = 0;
+    for path in reminder_files(&dir)? {
+        let ours = path.file_stem().and_then(|stem| stem.to_str()).is_some_and(|stem| uuids.contains(stem));
+        let file = fs::read_to_string(&path).ok().and_then(|text| from_file(&text).ok());
+        if !ours && file.is_some_and(|file| purged(&file, &cutoff)) {
+            fs::remove_file(&path)?;
+            removed += 1;
+        }
+    }
+    println!(
+        "Exported {} reminder(s) to {}: {} file(s) written, {} removed, {} conflict(s)",
+        reminders.len(),
+        dir.display(),
+        written,
+        removed,
+        conflicts.len()
+    );
+    report(&conflicts, "the file");
+    Ok(())
+}
+
+/// `reminder sync import`: adds the reminders in the directory that aren't
+/// here and takes the files' version of those changed there more recently.
+/// Nothing is removed, since a missing file may just not have synced yet.
+pub fn import(config: &Config, dir: Option<&Path>) -> Result<(), Box<dyn Error>> {
+    let dir = sync_dir(config, dir)?;
+    let mut files = Vec::new();
+    for path in reminder_files(&dir)? {
+        files.push(from_file(&fs::read_to_string(&path)?).map_err(|e| format!("{}: {}", path.display(), e))?);
+    }
+
+    let store = open_store(config)?;
+    let existing = store.reminders()?;
+    let (write, conflicts) = plan(&existing, files, &trash_cutoff(Local::now()));
+    for reminder in &write {
+        store.restore_reminder(reminder)?;
+    }
+    println!(
+        "Imported from {}: {} reminder(s) added or updated, {} conflict(s)",
+        dir.display(),
+        write.len(),
+        conflicts.len()
+    );
+    report(&conflicts, "the file");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reminder::{MemoryStore, NewReminder, ReminderStore};
+
+    #[test]
+    fn files_leave_out_the_id_and_come_back_the_same() {
+        let store = MemoryStore::new();
+        let tea = store
+            .add_reminder(NewReminder { title: "Tea".to_string(), time: "16:00".to_string(), ..Default::default() })
+            .unwrap();
+        let text = to_file(&tea);
+        assert!(!text.contains("\"id\""));
+        assert_eq!(from_file(&text).unwrap(), Reminder { id: 0, ..tea });
+    }
+
+    #[test]
+    fn import_takes_later_changes_and_keeps_ours_otherwise() {
+        let store = MemoryStore::new();
+        let add = |title: &str| {
+            store
+                .add_reminder(NewReminder { title: title.to_string(), time: "09:00".to_string(), ..Default::default() })
+                .unwrap()
+        };
+        let (tea, nap, walk) = (add("Tea"), add("Nap"), add("Walk"));
+        let existing = store.reminders().unwrap();
+        let later = "2999-01-01T00:00:00.000Z".to_string();
+
+        let files = vec![
+            Reminder { id: 0, time: "16:00".to_string(), updated_at: later.clone(), ..tea.clone() },
+            Reminder { id: 0, time: "15:00".to_string(), updated_at: String::new(), ..nap.clone() },
+            Reminder { id: 0, ..walk },
+            Reminder { id: 0, uuid: "new".to_string(), title: "Read".to_string(), ..tea.clone() },
+            Reminder {
+                id: 0,
+                uuid: "gone".to_string(),
+                deleted_at: Some("2026-01-01 09:00".to_string()),
+                ..tea.clone()
+            },
+        ];
+        let (write, conflicts) = plan(&existing, files, "2026-09-15 09:00");
+        let write: Vec<(i32, &str, &str)> = write.iter().map(|r| (r.id, r.title.as_str(), r.time.as_str())).collect();
+        assert_eq!(write, [(tea.id, "Tea", "16:00"), (4, "Read", "09:00")]);
+        let conflicts: Vec<&str> = conflicts.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(conflicts, ["Nap"]);
+    }
+}
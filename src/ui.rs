@@ -1,7 +1,35 @@
 use ratatui::{
-    Frame, layout::{Alignment, Constraint, Direction, Layout}, style::{Color, Modifier, Style}, text::{Line, Span}, widgets::{Block, Borders, List, ListItem, Paragraph}
+    Frame, layout::{Alignment, Constraint, Direction, Layout, Rect}, style::{Color, Modifier, Style}, text::{Line, Span}, widgets::{block::{self, Title}, Block, Borders, Cell, Clear, List, ListItem, Paragraph, Row, Table, TableState, Tabs, Wrap}
 };
-use crate::db::Reminder;
+use crate::config::{snooze_until, KeyPreset, LockConfig, Sections, SortOrder, TimeFormat};
+use crate::db::{Delivery, Exception, LoggedNotification, NewReminder, Priority, Reminder, Vacation};
+use crate::events::Missed;
+use crate::form::{self, OCCURRENCE_FIELD_COUNT, SETTINGS_FIELD_COUNT};
+use crate::fuzzy;
+use crate::keymap;
+use crate::history::History;
+use crate::markdown;
+use crate::quick_add;
+use crate::schedule;
+use chrono::{DateTime, Datelike, Duration, Local, Months, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Weekday};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::Instant;
+
+pub const FORM_FIELD_COUNT: usize = 12;
+/// Lines of the description shown in the form.
+const DESCRIPTION_LINES: u16 = 4;
+/// Seconds a message stays in the status bar.
+const STATUS_SECS: u64 = 5;
+const STATUS_ERROR_SECS: u64 = 10;
+
+/// What closing a form with unsaved changes was for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Discard {
+    /// Back to the list.
+    Cancel,
+    Quit,
+}
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Mode {
@@ -9,39 +37,445 @@ pub enum Mode {
     Add,
     Edit,
     Delete,
+    QuickAdd,
+    Calendar,
+    Agenda,
+    Search,
+    Profiles,
+    Duplicate,
+    Logs,
+    Missed,
+    Notifications,
+    Occurrence,
+    Snooze,
+    Settings,
+    Tags,
+    Help,
+}
+
+/// Which set of reminders the list shows.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tab {
+    Today,
+    Upcoming,
+    All,
+    Done,
+    Archive,
+    Trash,
+}
+
+impl Tab {
+    pub const ALL: [Tab; 6] = [Tab::Today, Tab::Upcoming, Tab::All, Tab::Done, Tab::Archive, Tab::Trash];
+
+    fn title(self) -> &'static str {
+        match self {
+            Tab::Today => "Today",
+            Tab::Upcoming => "Upcoming",
+            Tab::All => "All",
+            Tab::Done => "Done",
+            Tab::Archive => "Archive",
+            Tab::Trash => "Trash",
+        }
+    }
+
+    pub fn index(self) -> usize {
+        Tab::ALL.iter().position(|tab| *tab == self).unwrap_or(0)
+    }
+}
+
+/// One-key views over every tab, for finding things in a long list.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QuickFilter {
+    /// Going off within the next hour, on `H`.
+    NextHour,
+    /// Due earlier without having been shown, on `!`.
+    Overdue,
+    /// Going off today, on `T`.
+    Today,
+}
+
+impl QuickFilter {
+    fn title(self) -> &'static str {
+        match self {
+            QuickFilter::NextHour => "⏰ Reminders due in the next hour",
+            QuickFilter::Overdue => "⚠  Overdue reminders",
+            QuickFilter::Today => "📅 Reminders due today",
+        }
+    }
 }
 
 pub struct AppState {
     pub mode: Mode,
+    pub tab: Tab,
     pub reminders: Vec<Reminder>,
     pub selected_idx: usize,
     pub input: String,
+    /// Byte offset of the cursor in `input`.
+    pub cursor: usize,
     pub input_field: usize,
-    pub form_fields: [String; 3],
+    pub form_fields: [String; FORM_FIELD_COUNT],
+    /// Form fields that have been left or saved, so empty required ones get flagged.
+    pub touched: [bool; FORM_FIELD_COUNT],
+    /// The form's fields as it opened, to tell whether anything was changed.
+    pub form_opened: [String; FORM_FIELD_COUNT],
+    /// The reminder `c` filled the add form from; what the form has no
+    /// fields for is copied from it when the new one is added.
+    pub copying: Option<Reminder>,
+    /// Set while asking whether to throw away the form's changes, to what
+    /// was asked for.
+    pub discarding: Option<Discard>,
     pub error_msg: Option<String>,
+    pub time_format: TimeFormat,
+    /// Day under the cursor in the calendar; the agenda shows its week.
+    pub calendar_day: NaiveDate,
+    /// Limits the list to reminders going off on this day, after picking it in the calendar.
+    pub day_filter: Option<NaiveDate>,
+    /// Limits the list to reminders matching these words, typed after `/`.
+    pub search: String,
+    /// Limits the list to reminders with this tag, picked with `t`.
+    pub tag_filter: Option<String>,
+    /// Limits the list to the reminders due soon, overdue or due today.
+    pub quick_filter: Option<QuickFilter>,
+    /// Every tag and how many reminders have it, while picking one, and the
+    /// one under the cursor.
+    pub tag_counts: Vec<(String, usize)>,
+    pub tag_idx: usize,
+    /// What the list is sorted by; `None` keeps the tab's own order.
+    pub sort: Option<SortOrder>,
+    pub sort_desc: bool,
+    /// Scroll offset of the table, kept between draws so it only scrolls when
+    /// the selection would leave the screen.
+    pub table_state: TableState,
+    /// Rows the table or log showed on the last draw, for PageUp/PageDown.
+    pub page_size: usize,
+    /// How many reminders the current tab has; more than `reminders.len()`
+    /// while later pages haven't been loaded yet.
+    pub total: usize,
+    /// Where the table and form fields were last drawn, for mouse clicks.
+    pub table_area: Rect,
+    pub field_areas: [Rect; FORM_FIELD_COUNT],
+    /// Ids marked with Space for bulk actions.
+    pub marked: HashSet<i32>,
+    pub history: History,
+    /// Earlier titles or tags offered with Ctrl+N/Ctrl+P in the form.
+    pub completions: Vec<String>,
+    /// The suggestion currently filled in.
+    pub completion: Option<usize>,
+    /// The field's text before the word being completed.
+    completion_base: String,
+    /// Day under the cursor while the form's date picker is open.
+    pub date_picker: Option<NaiveDate>,
+    /// Lock screen settings; `None` never locks.
+    pub lock: Option<LockConfig>,
+    /// Whether the lock screen hides everything else.
+    pub locked: bool,
+    /// What has been typed on the lock screen, kept apart from the form's input.
+    pub unlock_input: String,
+    /// The last PIN typed on the lock screen was wrong.
+    pub unlock_failed: bool,
+    /// When the last key, click or paste arrived, for the idle lock.
+    pub last_input: Instant,
+    /// The profile being shown; `None` is the default one.
+    pub profile: Option<String>,
+    /// Profiles with a section in the config file.
+    pub profiles: Vec<String>,
+    /// Row under the cursor in the profile switcher.
+    pub profile_idx: usize,
+    /// A reminder about to be added and the existing one it duplicates,
+    /// while asking what to do.
+    pub duplicate: Option<(NewReminder, Reminder)>,
+    /// Set by `q`; the main loop returns once it sees it.
+    pub quit: bool,
+    /// The latest notifications, newest first, for the notification history.
+    pub notifications: Vec<LoggedNotification>,
+    /// Rows the notification history is scrolled down by.
+    pub notifications_scroll: usize,
+    /// Rows the key help is scrolled down by.
+    pub help_scroll: usize,
+    /// Reminders that went off while the app wasn't running, until dismissed.
+    pub missed: Vec<Missed>,
+    /// Every one of them went off while notifications were muted.
+    pub missed_while_muted: bool,
+    /// Desktop notifications are muted, toggled with `m`.
+    pub muted: bool,
+    /// How long `Z` snoozes the due reminders for.
+    pub snooze_minutes: i64,
+    /// What `z` offers to snooze for, from the config, and the one picked.
+    pub snooze_presets: Vec<String>,
+    pub snooze_idx: usize,
+    /// The end of the newest log file, for the log view.
+    pub log: Vec<String>,
+    pub log_path: Option<PathBuf>,
+    /// Lines the log view is scrolled back by; 0 follows new lines as they come.
+    pub log_scroll: usize,
+    /// The message in the status bar, until it expires.
+    pub status: Option<Status>,
+    /// Open reminders going off within the next hour, shown when there is no message.
+    pub due_soon: usize,
+    /// Open reminders whose time has passed without a notification being shown.
+    pub overdue: HashSet<i32>,
+    /// List overdue reminders first, whatever the sort.
+    pub overdue_first: bool,
+    /// Colors of tags, from the config.
+    pub tag_colors: HashMap<String, Color>,
+    /// Extra keys the list takes, from the config.
+    pub key_preset: KeyPreset,
+    /// The first key of a pair like `gg`, waiting for the second.
+    pub pending_key: Option<keymap::Key>,
+    /// How the list is split up by the time of day.
+    pub sections: Sections,
+    /// The reminder behind each row of the table as last drawn; `None` for
+    /// a section header.
+    pub table_rows: Vec<Option<usize>>,
+    /// The soonest open reminder and when it goes off, counted down above the list.
+    pub next_up: Option<(Reminder, NaiveDateTime)>,
+    /// Vacations going on today, shown in the list's header.
+    pub vacations: Vec<Vacation>,
+    /// The occurrence being changed with `E`.
+    pub occurrence: Option<OccurrenceEdit>,
+    /// The notification settings being changed with `N`.
+    pub settings: Option<SettingsEdit>,
+}
+
+/// One day of a repeating reminder being changed in the occurrence editor.
+pub struct OccurrenceEdit {
+    pub reminder: Reminder,
+    /// Date, time and note; the focused one lives in `AppState::input` meanwhile.
+    pub fields: [String; OCCURRENCE_FIELD_COUNT],
+    pub field: usize,
+}
+
+/// How a reminder's notifications look, being changed in the settings sub-form.
+pub struct SettingsEdit {
+    pub reminder: Reminder,
+    /// Icon, category and whether it stays; the focused one lives in
+    /// `AppState::input` meanwhile.
+    pub fields: [String; SETTINGS_FIELD_COUNT],
+    pub field: usize,
+}
+
+/// A message in the status bar.
+pub struct Status {
+    pub text: String,
+    pub error: bool,
+    shown_at: Instant,
+}
+
+impl Status {
+    /// Errors stay up longer so there is time to read them.
+    fn expired(&self) -> bool {
+        let lasts = if self.error { STATUS_ERROR_SECS } else { STATUS_SECS };
+        self.shown_at.elapsed().as_secs() >= lasts
+    }
 }
 
 impl AppState {
-    pub fn new(reminders: Vec<Reminder>) -> Self {
+    pub fn new(time_format: TimeFormat) -> Self {
         AppState {
             mode: Mode::List,
-            reminders,
+            tab: Tab::All,
+            reminders: Vec::new(),
             selected_idx: 0,
             input: String::new(),
+            cursor: 0,
             input_field: 0,
-            form_fields: [String::new(), String::new(), String::new()],
+            form_fields: Default::default(),
+            form_opened: Default::default(),
+            copying: None,
+            discarding: None,
+            touched: Default::default(),
             error_msg: None,
+            time_format,
+            calendar_day: Local::now().date_naive(),
+            day_filter: None,
+            search: String::new(),
+            tag_filter: None,
+            quick_filter: None,
+            tag_counts: Vec::new(),
+            tag_idx: 0,
+            sort: None,
+            sort_desc: false,
+            table_state: TableState::default(),
+            page_size: 1,
+            total: 0,
+            table_area: Rect::default(),
+            field_areas: [Rect::default(); FORM_FIELD_COUNT],
+            marked: HashSet::new(),
+            history: History::default(),
+            completions: Vec::new(),
+            completion: None,
+            completion_base: String::new(),
+            date_picker: None,
+            lock: None,
+            locked: false,
+            unlock_input: String::new(),
+            unlock_failed: false,
+            last_input: Instant::now(),
+            profile: None,
+            profiles: Vec::new(),
+            profile_idx: 0,
+            duplicate: None,
+            quit: false,
+            notifications: Vec::new(),
+            notifications_scroll: 0,
+            help_scroll: 0,
+            missed: Vec::new(),
+            missed_while_muted: false,
+            muted: false,
+            snooze_minutes: 10,
+            snooze_presets: Vec::new(),
+            snooze_idx: 0,
+            log: Vec::new(),
+            log_path: None,
+            log_scroll: 0,
+            status: None,
+            due_soon: 0,
+            overdue: HashSet::new(),
+            overdue_first: false,
+            tag_colors: HashMap::new(),
+            key_preset: KeyPreset::Default,
+            pending_key: None,
+            sections: Sections::default(),
+            table_rows: Vec::new(),
+            next_up: None,
+            vacations: Vec::new(),
+            occurrence: None,
+            settings: None,
+        }
+    }
+
+    /// Shows `text` in the status bar for a few seconds.
+    pub fn notify(&mut self, text: impl Into<String>) {
+        self.status = Some(Status { text: text.into(), error: false, shown_at: Instant::now() });
+    }
+
+    /// Shows `text` in red in the status bar for a little longer.
+    pub fn notify_error(&mut self, text: impl Into<String>) {
+        self.status = Some(Status { text: text.into(), error: true, shown_at: Instant::now() });
+    }
+
+    /// Clears the status bar once its message has been up long enough.
+    pub fn expire_status(&mut self) {
+        if self.status.as_ref().is_some_and(Status::expired) {
+            self.status = None;
+        }
+    }
+
+    /// What the profile switcher offers: the default profile, every
+    /// configured one, and the current one even if it has no section.
+    pub fn profile_choices(&self) -> Vec<Option<String>> {
+        let mut choices = vec![None];
+        choices.extend(self.profiles.iter().cloned().map(Some));
+        if self.profile.is_some() && !choices.contains(&self.profile) {
+            choices.push(self.profile.clone());
+        }
+        choices
+    }
+
+    /// Shows the lock screen, if one is configured.
+    pub fn lock(&mut self) {
+        if self.lock.is_some() {
+            self.locked = true;
+            self.unlock_input.clear();
+        }
+    }
+
+    /// Locks once there has been no input for the configured time.
+    pub fn lock_if_idle(&mut self) {
+        let idle = self.lock.as_ref().map(|lock| std::time::Duration::from_secs(lock.idle_minutes * 60));
+        if !self.locked && idle.is_some_and(|idle| self.last_input.elapsed() >= idle) {
+            self.lock();
+        }
+    }
+
+    /// Checks what was typed on the lock screen, unlocking on the right PIN.
+    pub fn try_unlock(&mut self) {
+        if self.lock.as_ref().is_some_and(|lock| lock.pin == self.unlock_input) {
+            self.locked = false;
+            self.unlock_failed = false;
+        } else {
+            self.unlock_failed = true;
+        }
+        self.unlock_input.clear();
+    }
+
+    /// Replaces the list after it changed underneath us, keeping the selection in range.
+    pub fn set_reminders(&mut self, mut reminders: Vec<Reminder>) {
+        if let Some(day) = self.day_filter {
+            reminders.retain(|r| schedule::fires_on(r, day));
+        }
+        self.marked.retain(|id| reminders.iter().any(|r| r.id == *id));
+        self.reminders = reminders;
+        self.sort_reminders();
+        if self.selected_idx >= self.reminders.len() {
+            self.selected_idx = self.reminders.len().saturating_sub(1);
+        }
+    }
+
+    /// Moves to the next sort order (then back to the tab's own), keeping
+    /// the selected reminder selected.
+    pub fn cycle_sort(&mut self) {
+        self.sort = match self.sort {
+            None => Some(SortOrder::ALL[0]),
+            Some(sort) => SortOrder::ALL
+                .iter()
+                .position(|s| *s == sort)
+                .and_then(|i| SortOrder::ALL.get(i + 1).copied()),
+        };
+        self.resort();
+    }
+
+    pub fn reverse_sort(&mut self) {
+        self.sort_desc = !self.sort_desc;
+        self.resort();
+    }
+
+    /// Replaces the overdue reminders, moving them up if they go first.
+    pub fn set_overdue(&mut self, overdue: HashSet<i32>) {
+        if overdue != self.overdue {
+            self.overdue = overdue;
+            self.resort();
+        }
+    }
+
+    fn resort(&mut self) {
+        let selected = self.reminders.get(self.selected_idx).map(|r| r.id);
+        self.sort_reminders();
+        if let Some(idx) = selected.and_then(|id| self.reminders.iter().position(|r| r.id == id)) {
+            self.selected_idx = idx;
+        }
+    }
+
+    fn sort_reminders(&mut self) {
+        let now = Local::now().naive_local();
+        match self.sort {
+            None => {}
+            // Reminders that won't go off again sort last.
+            Some(SortOrder::Time) => self.reminders.sort_by_key(|r| (schedule::next_fire(r, now).is_none(), schedule::next_fire(r, now))),
+            Some(SortOrder::Title) => self.reminders.sort_by_key(|r| r.title.to_lowercase()),
+            Some(SortOrder::Created) => self.reminders.sort_by_key(|r| DateTime::parse_from_rfc3339(&r.created_at).ok()),
+            // Then by time, so equally urgent ones come in the order they're due.
+            Some(SortOrder::Priority) => self.reminders.sort_by_key(|r| {
+                (std::cmp::Reverse(r.priority), schedule::next_fire(r, now).is_none(), schedule::next_fire(r, now))
+            }),
+        }
+        if self.sort.is_some() && self.sort_desc {
+            self.reminders.reverse();
+        }
+        if self.overdue_first {
+            // Stable, so they keep the order they were in among themselves.
+            self.reminders.sort_by_key(|r| !self.overdue.contains(&r.id));
         }
     }
 
     pub fn next(&mut self) {
-        if self.mode == Mode::List && !self.reminders.is_empty() {
+        if matches!(self.mode, Mode::List | Mode::Search) && !self.reminders.is_empty() {
             self.selected_idx = (self.selected_idx + 1) % self.reminders.len();
         }
     }
 
     pub fn prev(&mut self) {
-        if self.mode == Mode::List && !self.reminders.is_empty() {
+        if matches!(self.mode, Mode::List | Mode::Search) && !self.reminders.is_empty() {
             self.selected_idx = if self.selected_idx == 0 {
                 self.reminders.len() - 1
             } else {
@@ -50,107 +484,941 @@ impl AppState {
         }
     }
 
-    pub fn next_field(&mut self) {
+    pub fn page_down(&mut self) {
+        if !self.reminders.is_empty() {
+            self.selected_idx = (self.selected_idx + self.page_size).min(self.reminders.len() - 1);
+        }
+    }
+
+    pub fn page_up(&mut self) {
+        self.selected_idx = self.selected_idx.saturating_sub(self.page_size);
+    }
+
+    pub fn first(&mut self) {
+        self.selected_idx = 0;
+    }
+
+    pub fn last(&mut self) {
+        self.selected_idx = self.reminders.len().saturating_sub(1);
+    }
+
+    /// Marks or unmarks the selected reminder and moves on to the next one.
+    pub fn toggle_mark(&mut self) {
+        if let Some(reminder) = self.reminders.get(self.selected_idx) {
+            if !self.marked.remove(&reminder.id) {
+                self.marked.insert(reminder.id);
+            }
+            self.scroll(1);
+        }
+    }
+
+    /// Moves the selection by `delta` rows without wrapping, as the mouse wheel does.
+    pub fn scroll(&mut self, delta: isize) {
+        if !self.reminders.is_empty() {
+            self.selected_idx = self.selected_idx.saturating_add_signed(delta).min(self.reminders.len() - 1);
+        }
+    }
+
+    /// The reminder row at screen position (`column`, `row`), if any.
+    pub fn row_at(&self, column: u16, row: u16) -> Option<usize> {
+        let area = self.table_area;
+        // Skip the border and the header row.
+        let first = area.y + 2;
+        let inside = column > area.x && column < area.right().saturating_sub(1) && row >= first && row < area.bottom().saturating_sub(1);
+        let row = self.table_state.offset() + (row.checked_sub(first)? as usize);
+        self.table_rows.get(row).copied().flatten().filter(|_| inside)
+    }
+
+    /// The form field at screen position (`column`, `row`), if any.
+    pub fn field_at(&self, column: u16, row: u16) -> Option<usize> {
+        let position = ratatui::layout::Position { x: column, y: row };
+        self.field_areas.iter().position(|area| area.contains(position))
+    }
+
+    /// Replaces the text being typed, with the cursor at its end.
+    pub fn set_input(&mut self, text: String) {
+        self.cursor = text.len();
+        self.input = text;
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        self.input.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    /// The time typed in the time field, if it is a valid one.
+    pub fn typed_time(&self) -> Option<NaiveTime> {
+        self.time_format
+            .parse(&self.input)
+            .and_then(|time| schedule::parse_time(&time))
+    }
+
+    /// Steps the hour or the minutes under the cursor in the time field, the
+    /// minutes in fives. An empty or invalid time starts from now.
+    pub fn step_time(&mut self, up: bool) {
+        let minutes = self.input.find(':').is_some_and(|colon| self.cursor > colon);
+        let time = self.typed_time().unwrap_or_else(|| {
+            let now = Local::now().time();
+            NaiveTime::from_hms_opt(now.hour(), now.minute() / 5 * 5, 0).unwrap_or(now)
+        });
+        let step = match (minutes, up) {
+            (false, true) => Duration::hours(1),
+            (false, false) => Duration::hours(-1),
+            // Land on the five-minute grid first.
+            (true, true) => Duration::minutes(5 - i64::from(time.minute() % 5)),
+            (true, false) => Duration::minutes(-i64::from(match time.minute() % 5 {
+                0 => 5,
+                off => off,
+            })),
+        };
+        let (time, _) = time.overflowing_add_signed(step);
+        self.set_input(self.time_format.format(time));
+        self.select_time_part(minutes);
+    }
+
+    /// Puts the cursor on the hour or the minutes of the time field.
+    pub fn select_time_part(&mut self, minutes: bool) {
+        self.cursor = match self.input.find(':') {
+            Some(colon) if minutes => colon + 1,
+            _ => 0,
+        };
+    }
+
+    /// What the focused form field would be completed from: the whole title,
+    /// or the last tag typed. `None` for fields without suggestions.
+    pub fn completion_prefix(&self) -> Option<&str> {
+        match self.input_field {
+            0 => Some(self.input.trim_start()),
+            5 => {
+                let start = self.input.rfind(|c: char| c == ',' || c.is_whitespace()).map_or(0, |i| i + 1);
+                Some(self.input[start..].trim_start_matches('#'))
+            }
+            _ => None,
+        }
+    }
+
+    /// Offers `suggestions` for the text typed so far.
+    pub fn start_completion(&mut self, suggestions: Vec<String>) {
+        self.completion_base = match self.input_field {
+            5 => {
+                let start = self.input.rfind(|c: char| c == ',' || c.is_whitespace()).map_or(0, |i| i + 1);
+                self.input[..start].to_string()
+            }
+            _ => String::new(),
+        };
+        self.completions = suggestions;
+        self.completion = None;
+    }
+
+    /// Fills in the next (or previous) suggestion.
+    pub fn cycle_completion(&mut self, forward: bool) {
+        let count = self.completions.len();
+        if count == 0 {
+            return;
+        }
+        let idx = match (self.completion, forward) {
+            (None, true) => 0,
+            (None, false) => count - 1,
+            (Some(i), true) => (i + 1) % count,
+            (Some(i), false) => (i + count - 1) % count,
+        };
+        self.completion = Some(idx);
+        self.set_input(format!("{}{}", self.completion_base, self.completions[idx]));
+    }
+
+    pub fn clear_completions(&mut self) {
+        self.completions.clear();
+        self.completion = None;
+    }
+
+    /// Inserts pasted text at the cursor. Line breaks are kept in the
+    /// description and become spaces everywhere else; other control
+    /// characters are dropped.
+    pub fn paste(&mut self, text: &str) {
+        let multiline = matches!(self.mode, Mode::Add | Mode::Edit) && self.input_field == 1;
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+        for c in text.trim_end_matches('\n').chars() {
+            match c {
+                '\n' if multiline => self.insert_char('\n'),
+                '\n' | '\t' => self.insert_char(' '),
+                c if c.is_control() => {}
+                c => self.insert_char(c),
+            }
+        }
+    }
+
+    /// Deletes the character before the cursor.
+    pub fn backspace(&mut self) {
+        if let Some(c) = self.input[..self.cursor].chars().next_back() {
+            self.cursor -= c.len_utf8();
+            self.input.remove(self.cursor);
+        }
+    }
+
+    /// Deletes the character under the cursor.
+    pub fn delete_char(&mut self) {
+        if self.cursor < self.input.len() {
+            self.input.remove(self.cursor);
+        }
+    }
+
+    pub fn cursor_left(&mut self) {
+        if let Some(c) = self.input[..self.cursor].chars().next_back() {
+            self.cursor -= c.len_utf8();
+        }
+    }
+
+    pub fn cursor_right(&mut self) {
+        if let Some(c) = self.input[self.cursor..].chars().next() {
+            self.cursor += c.len_utf8();
+        }
+    }
+
+    /// Moves to the start of the cursor's line.
+    pub fn cursor_home(&mut self) {
+        self.cursor = self.input[..self.cursor].rfind('\n').map_or(0, |i| i + 1);
+    }
+
+    /// Moves to the end of the cursor's line.
+    pub fn cursor_end(&mut self) {
+        self.cursor = self.input[self.cursor..].find('\n').map_or(self.input.len(), |i| self.cursor + i);
+    }
+
+    /// Line and column of the cursor within `input`, counting characters.
+    fn cursor_position(&self) -> (u16, u16) {
+        let before = &self.input[..self.cursor];
+        let line = before.matches('\n').count();
+        let column = before.rsplit('\n').next().unwrap_or("").chars().count();
+        (line as u16, column as u16)
+    }
+
+    /// Fills the form with a reminder's values, written the way the form reads them back.
+    pub fn fill_form(&mut self, reminder: &Reminder) {
+        let time = if reminder.cron.is_some() { String::new() } else { self.time_format.render(&reminder.time) };
+        let every = match (reminder.interval_minutes, &reminder.until) {
+            (Some(minutes), Some(until)) => format!("{} until {}", minutes_text(minutes), self.time_format.render(until)),
+            (Some(minutes), None) => minutes_text(minutes),
+            (None, _) => String::new(),
+        };
+        self.form_fields = [
+            reminder.title.clone(),
+            reminder.description.clone(),
+            time,
+            reminder.date.clone().unwrap_or_default(),
+            reminder.alerts.iter().map(|m| minutes_text(*m)).collect::<Vec<_>>().join(","),
+            reminder.tags.join(", "),
+            reminder.cron.clone().unwrap_or_default(),
+            every,
+            reminder.weekdays.clone().unwrap_or_default(),
+            reminder.timezone.clone().unwrap_or_default(),
+            if reminder.delete_after_firing { "delete".to_string() } else { String::new() },
+            if reminder.skip_holidays { "skip".to_string() } else { String::new() },
+        ];
+        self.form_opened = self.form_fields.clone();
+        self.touched = Default::default();
+        self.input_field = 0;
+        self.set_input(self.form_fields[0].clone());
+        self.error_msg = None;
+    }
+
+    /// The form's fields, including what is being typed.
+    pub fn current_form(&self) -> [String; FORM_FIELD_COUNT] {
+        let mut current = self.form_fields.clone();
+        current[self.input_field] = self.input.clone();
+        current
+    }
+
+    /// Whether the form holds anything it didn't open with.
+    pub fn form_changed(&self) -> bool {
+        self.current_form() != self.form_opened
+    }
+
+    /// Opens the date picker on the typed date, or today.
+    pub fn open_date_picker(&mut self) {
+        let typed = NaiveDate::parse_from_str(self.input.trim(), "%Y-%m-%d").ok();
+        self.date_picker = Some(typed.unwrap_or_else(|| Local::now().date_naive()));
+    }
+
+    pub fn focus_field(&mut self, field: usize) {
         if self.mode == Mode::Add || self.mode == Mode::Edit {
+            self.date_picker = None;
+            self.touched[self.input_field] = true;
             self.form_fields[self.input_field] = self.input.clone();
-            self.input_field = (self.input_field + 1) % 3;
-            self.input = self.form_fields[self.input_field].clone();
+            self.input_field = field % FORM_FIELD_COUNT;
+            self.set_input(self.form_fields[self.input_field].clone());
         }
     }
 
+    pub fn next_field(&mut self) {
+        self.focus_field(self.input_field + 1);
+    }
+
     pub fn prev_field(&mut self) {
-        if self.mode == Mode::Add || self.mode == Mode::Edit {
-            self.form_fields[self.input_field] = self.input.clone();
-            self.input_field = if self.input_field == 0 { 2 } else { self.input_field - 1 };
-            self.input = self.form_fields[self.input_field].clone();
+        self.focus_field(self.input_field + FORM_FIELD_COUNT - 1);
+    }
+
+    /// Opens the occurrence editor on `reminder`'s occurrence on `day`.
+    pub fn edit_occurrence(&mut self, reminder: Reminder, day: NaiveDate) {
+        self.occurrence = Some(OccurrenceEdit { reminder, fields: Default::default(), field: 0 });
+        self.fill_occurrence(day);
+        self.error_msg = None;
+        self.mode = Mode::Occurrence;
+    }
+
+    /// Fills the occurrence editor in with how `day` goes now: its exception,
+    /// or the usual time and no note.
+    fn fill_occurrence(&mut self, day: NaiveDate) {
+        let time_format = self.time_format;
+        let Some(edit) = &mut self.occurrence else {
+            return;
+        };
+        let date = day.format("%Y-%m-%d").to_string();
+        let (time, note) = match edit.reminder.exceptions.iter().find(|e| e.date == date) {
+            Some(exception) => (
+                exception.time.as_deref().map(|time| time_format.render(time)),
+                exception.note.clone(),
+            ),
+            None => (schedule::usual_time(&edit.reminder, day).map(|time| time_format.format(time)), None),
+        };
+        edit.fields = [date, time.unwrap_or_default(), note.unwrap_or_default()];
+        let text = edit.fields[edit.field].clone();
+        self.set_input(text);
+    }
+
+    /// Moves to another field of the occurrence editor. Leaving the date
+    /// fills the rest in for the day typed.
+    pub fn focus_occurrence_field(&mut self, field: usize) {
+        let input = self.input.clone();
+        let Some(edit) = &mut self.occurrence else {
+            return;
+        };
+        let changed_day = (edit.field == 0 && edit.fields[0] != input)
+            .then(|| NaiveDate::parse_from_str(input.trim(), "%Y-%m-%d").ok())
+            .flatten();
+        edit.fields[edit.field] = input;
+        edit.field = field % OCCURRENCE_FIELD_COUNT;
+        match changed_day {
+            Some(day) => self.fill_occurrence(day),
+            None => {
+                let text = edit.fields[edit.field].clone();
+                self.set_input(text);
+            }
         }
     }
+
+    /// Opens the notification settings of `reminder`.
+    pub fn edit_settings(&mut self, reminder: Reminder) {
+        let fields = [
+            reminder.icon.clone().unwrap_or_default(),
+            reminder.category.clone().unwrap_or_default(),
+            if reminder.resident { "keep".to_string() } else { String::new() },
+        ];
+        self.set_input(fields[0].clone());
+        self.settings = Some(SettingsEdit { reminder, fields, field: 0 });
+        self.error_msg = None;
+        self.mode = Mode::Settings;
+    }
+
+    /// Moves to another field of the notification settings.
+    pub fn focus_settings_field(&mut self, field: usize) {
+        let input = self.input.clone();
+        let Some(edit) = &mut self.settings else {
+            return;
+        };
+        edit.fields[edit.field] = input;
+        edit.field = field % SETTINGS_FIELD_COUNT;
+        let text = edit.fields[edit.field].clone();
+        self.set_input(text);
+    }
 }
 
-pub fn draw_ui(f: &mut Frame, app: &AppState) {
+pub fn draw_ui(f: &mut Frame, app: &mut AppState) {
+    if app.locked {
+        draw_lock_screen(f, app);
+        return;
+    }
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(f.size());
+    let area = chunks[0];
     match app.mode {
-        Mode::List => draw_list(f, app),
-        Mode::Add => draw_add_form(f, app),
-        Mode::Edit => draw_edit_form(f, app),
-        Mode::Delete => draw_delete_confirm(f, app),
+        Mode::List | Mode::Search => draw_list(f, app, area),
+        Mode::Add => draw_add_form(f, app, area),
+        Mode::Edit => draw_edit_form(f, app, area),
+        Mode::Delete => draw_delete_confirm(f, app, area),
+        Mode::QuickAdd => draw_quick_add(f, app, area),
+        Mode::Calendar => draw_calendar(f, app, area),
+        Mode::Agenda => draw_agenda(f, app, area),
+        Mode::Profiles => {
+            draw_list(f, app, area);
+            draw_profiles(f, app, area);
+        }
+        Mode::Duplicate => draw_duplicate_confirm(f, app, area),
+        Mode::Logs => draw_logs(f, app, area),
+        Mode::Notifications => draw_notifications(f, app, area),
+        Mode::Missed => {
+            draw_list(f, app, area);
+            draw_missed(f, app, area);
+        }
+        Mode::Occurrence => {
+            draw_list(f, app, area);
+            draw_occurrence(f, app, area);
+        }
+        Mode::Snooze => {
+            draw_list(f, app, area);
+            draw_snooze(f, app, area);
+        }
+        Mode::Settings => {
+            draw_list(f, app, area);
+            draw_settings(f, app, area);
+        }
+        Mode::Tags => {
+            draw_list(f, app, area);
+            draw_tags(f, app, area);
+        }
+        Mode::Help => draw_help(f, app, area),
+    }
+    draw_status_bar(f, app, chunks[1]);
+}
+
+/// The last message, or how many reminders are coming up when there is none,
+/// with the date, time and UTC offset on the right.
+fn draw_status_bar(f: &mut Frame, app: &AppState, area: Rect) {
+    let now = Local::now();
+    let clock = format!(
+        " {} {} UTC{}",
+        now.format("%a %Y-%m-%d"),
+        app.time_format.format_exact(now.time()),
+        now.format("%:z")
+    );
+    let [area, clock_area] = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(0), Constraint::Length(clock.chars().count() as u16)])
+        .areas(area);
+    f.render_widget(Paragraph::new(Span::styled(clock, Style::default().fg(Color::Cyan))), clock_area);
+
+    let line = match &app.status {
+        Some(status) if status.error => Span::styled(status.text.clone(), Style::default().fg(Color::Red)),
+        Some(status) => Span::raw(status.text.clone()),
+        None => Span::styled(
+            format!("{} reminder(s) due in the next hour", app.due_soon),
+            Style::default().fg(Color::DarkGray),
+        ),
+    };
+    f.render_widget(Paragraph::new(Line::from(line)), area);
+}
+
+/// Formats a duration as its two largest units, e.g. `2h 15m` or `3d 4h`.
+pub fn format_duration(duration: Duration) -> String {
+    let minutes = duration.num_minutes().max(0);
+    let (days, hours, minutes) = (minutes / (24 * 60), minutes / 60 % 24, minutes % 60);
+    match (days, hours, minutes) {
+        (0, 0, m) => format!("{}m", m),
+        (0, h, 0) => format!("{}h", h),
+        (0, h, m) => format!("{}h {}m", h, m),
+        (d, 0, _) => format!("{}d", d),
+        (d, h, _) => format!("{}d {}h", d, h),
     }
 }
 
-fn draw_list(f: &mut Frame, app: &AppState) {
+/// Time left as `HH:MM:SS`, with the hours running past 24 when need be.
+fn countdown_text(duration: Duration) -> String {
+    let seconds = duration.num_seconds().max(0);
+    format!("{:02}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+}
+
+/// The first line of a multi-line description, with `…` if there is more.
+fn first_line(description: &str) -> String {
+    match description.split_once('\n') {
+        Some((first, _)) => format!("{} …", first),
+        None => description.to_string(),
+    }
+}
+
+/// `text` with the letters a search matched picked out, as fzf does;
+/// `positions` count from after the first `skip` chars.
+fn highlighted(text: String, positions: &[usize], skip: usize) -> Line<'static> {
+    if positions.is_empty() {
+        return Line::from(text);
+    }
+    let matched = Style::default().fg(Color::Green).add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut run = String::new();
+    let mut in_match = false;
+    for (at, c) in text.chars().enumerate() {
+        let hit = at >= skip && positions.binary_search(&(at - skip)).is_ok();
+        if hit != in_match && !run.is_empty() {
+            let run = std::mem::take(&mut run);
+            spans.push(if in_match { Span::styled(run, matched) } else { Span::raw(run) });
+        }
+        in_match = hit;
+        run.push(c);
+    }
+    spans.push(if in_match { Span::styled(run, matched) } else { Span::raw(run) });
+    Line::from(spans)
+}
+
+/// Minutes as the form takes them, e.g. `0`, `10m` or `2h`.
+fn minutes_text(minutes: i64) -> String {
+    match minutes {
+        0 => "0".to_string(),
+        m if m % 60 == 0 => format!("{}h", m / 60),
+        m => format!("{}m", m),
+    }
+}
+
+/// When a reminder goes off, e.g. `09:00 weekdays`, `2026-10-20 09:00` or `0 9 * * MON → Mon 09:00`.
+fn schedule_text(app: &AppState, reminder: &Reminder, now: NaiveDateTime) -> String {
+    let mut when = match (&reminder.cron, &reminder.date) {
+        _ if reminder.interval_minutes.is_some() => {
+            let every = format!(
+                "{}–{} every {}m",
+                app.time_format.render(&reminder.time),
+                app.time_format.render(reminder.until.as_deref().unwrap_or("23:59")),
+                reminder.interval_minutes.unwrap_or_default()
+            );
+            match &reminder.date {
+                Some(date) => format!("{} {}", date, every),
+                None => every,
+            }
+        }
+        (Some(cron), _) => match schedule::next_fire(reminder, now) {
+            Some(next) => format!("{} → {} {}", cron, next.format("%a"), app.time_format.format(next.time())),
+            None => cron.clone(),
+        },
+        (None, Some(date)) => format!("{} {}", date, app.time_format.render(&reminder.time)),
+        (None, None) => app.time_format.render(&reminder.time),
+    };
+    if let (Some(days), None) = (&reminder.weekdays, &reminder.date) {
+        when.push_str(&format!(" {}", days));
+    }
+    if let Some(zone) = &reminder.timezone {
+        when.push_str(&format!(" {}", zone));
+        // Cron reminders already show their next fire, which is in local time.
+        if reminder.cron.is_none() {
+            if let Some(next) = schedule::next_fire(reminder, now) {
+                when.push_str(&format!(" = {} local", app.time_format.format(next.time())));
+            }
+        }
+    }
+    when
+}
+
+/// How far away an open reminder is, e.g. `in 2h 15m` or `overdue 2h`.
+fn relative_text(reminder: &Reminder, now: NaiveDateTime) -> Option<String> {
+    if reminder.completed_at.is_some() {
+        return None;
+    }
+    match schedule::next_fire(reminder, now) {
+        Some(next) if next <= now => Some("now".to_string()),
+        Some(next) => Some(format!("in {}", format_duration(next - schedule::minute_of(now)))),
+        None => schedule::overdue_since(reminder, now).map(|since| format!("overdue {}", format_duration(now - since))),
+    }
+}
+
+/// The section a reminder is listed under, by the time it goes off.
+fn section_title(app: &AppState, reminder: &Reminder) -> String {
+    let Some(time) = schedule::parse_time(&reminder.time) else {
+        return "Other times".to_string();
+    };
+    match app.sections {
+        Sections::Hours => app.time_format.format(time.with_minute(0).and_then(|t| t.with_second(0)).unwrap_or(time)),
+        _ => match time.hour() {
+            0..=4 => "Night",
+            5..=11 => "Morning",
+            12..=16 => "Afternoon",
+            _ => "Evening",
+        }
+        .to_string(),
+    }
+}
+
+/// Marks a title with how urgent it is, for when colors don't show.
+fn priority_glyph(priority: Priority) -> &'static str {
+    match priority {
+        Priority::High => "! ",
+        Priority::Normal => "",
+        Priority::Low => "↓ ",
+    }
+}
+
+/// The color of a row, unless it is normal.
+fn priority_style(priority: Priority) -> Option<Style> {
+    match priority {
+        Priority::High => Some(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+        Priority::Normal => None,
+        Priority::Low => Some(Style::default().fg(Color::DarkGray)),
+    }
+}
+
+/// Tags as `#work #home`, each in its color from the config if it has one.
+fn tags_line(app: &AppState, tags: &[String]) -> Line<'static> {
+    let mut spans = Vec::new();
+    for (i, tag) in tags.iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::raw(" "));
+        }
+        let style = app.tag_colors.get(tag).map(|color| Style::default().fg(*color)).unwrap_or_default();
+        spans.push(Span::styled(format!("#{}", tag), style));
+    }
+    Line::from(spans)
+}
+
+fn alerts_text(reminder: &Reminder) -> String {
+    let alerts: Vec<String> = reminder.alerts.iter().map(|m| if *m == 0 { "0m".to_string() } else { format!("-{}m", m) }).collect();
+    alerts.join(" ")
+}
+
+fn draw_list(f: &mut Frame, app: &mut AppState, area: Rect) {
+    let [banner, area] = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(app.next_up.is_some().into()), Constraint::Min(0)])
+        .areas(area);
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Min(5), Constraint::Length(4)])
-        .split(f.size());
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(5),
+            Constraint::Length(if app.mode == Mode::Search { 4 } else { 1 }),
+        ])
+        .split(area);
 
-    let items: Vec<ListItem> = app
-        .reminders
-        .iter()
-        .enumerate()
-        .map(|(i, reminder)| {
-            let style = if i == app.selected_idx {
-                Style::default()
-                    .bg(Color::DarkGray)
-                    .fg(Color::White)
-                    .add_modifier(Modifier::BOLD)
-            } else {
-                Style::default()
+    let now = Local::now().naive_local();
+    if let Some((next, at)) = &app.next_up {
+        let when = match *at - now {
+            left if left > Duration::zero() => format!(" in {}", countdown_text(left)),
+            _ => " now".to_string(),
+        };
+        let line = Line::from(vec![
+            Span::styled("Next: ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+            Span::raw(next.title.clone()),
+            Span::styled(when, Style::default().fg(Color::Yellow)),
+        ]);
+        f.render_widget(Paragraph::new(line), banner);
+    }
+
+    let tabs = Tabs::new(
+        Tab::ALL
+            .iter()
+            .enumerate()
+            .map(|(i, tab)| format!("{} {}", i + 1, tab.title()))
+            .collect::<Vec<_>>(),
+    )
+    .block({
+        let mut title = Vec::new();
+        if let Some(profile) = &app.profile {
+            title.push(Span::raw(format!("Profile: {} ", profile)));
+        }
+        if app.muted {
+            title.push(Span::styled("MUTED ", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)));
+        }
+        for vacation in &app.vacations {
+            let what = match vacation.tags.as_slice() {
+                [] => "On vacation".to_string(),
+                tags => tags.iter().map(|tag| format!("#{}", tag)).collect::<Vec<_>>().join(" ") + " off",
             };
+            title.push(Span::styled(
+                format!("{} until {} ", what, vacation.end),
+                Style::default().fg(Color::Cyan),
+            ));
+        }
+        Block::default().borders(Borders::ALL).title(Line::from(title))
+    })
+    .select(app.tab.index())
+    .highlight_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    f.render_widget(tabs, chunks[0]);
 
-            let content = format!("[{}] {} - {}", reminder.time, reminder.title, reminder.description);
-            ListItem::new(content).style(style)
+    let rows: Vec<(String, &Reminder)> = app
+        .reminders
+        .iter()
+        .map(|reminder| {
+            let mut when = schedule_text(app, reminder, now);
+            let overdue = app.overdue.contains(&reminder.id).then(|| schedule::due_before(reminder, now)).flatten();
+            if let Some(due) = overdue {
+                when.push_str(&format!(" · OVERDUE {}", format_duration(now - due)));
+            } else if let Some(relative) = relative_text(reminder, now) {
+                when.push_str(&format!(" · {}", relative));
+            }
+            (when, reminder)
         })
         .collect();
+    // A checkbox column appears once anything is marked.
+    let marking = !app.marked.is_empty();
+    let header = Row::new(marking.then_some("").into_iter().chain(["Time", "Title", "Description", "Tags"]))
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+
+    // Headers only make sense while the list is in time-of-day order, as
+    // today's is unless sorted otherwise.
+    let sectioned = app.sections != Sections::Off && app.tab == Tab::Today && app.sort.is_none();
+    let mut section = None;
+    let mut table_rows = Vec::new();
+    let mut time_width = 4;
+    let mut table = Vec::new();
+    for (idx, (when, reminder)) in rows.into_iter().enumerate() {
+        if sectioned {
+            let title = section_title(app, reminder);
+            if section.as_ref() != Some(&title) {
+                let header = format!("── {}", title);
+                time_width = time_width.max(header.chars().count());
+                let cells = marking.then(String::new).into_iter().chain([header]);
+                table.push(Row::new(cells).style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)));
+                table_rows.push(None);
+                section = Some(title);
+            }
+        }
+        time_width = time_width.max(when.chars().count());
+        let overdue = app.overdue.contains(&reminder.id);
+        let glyph = match reminder.completed_at {
+            Some(_) => "✓ ",
+            None => priority_glyph(reminder.priority),
+        };
+        let mut found = fuzzy::find(&app.search, &reminder.title, &reminder.description).unwrap_or_default();
+        // Only the description's first line is shown.
+        let shown = reminder.description.lines().next().unwrap_or_default().chars().count();
+        found.description.retain(|at| *at < shown);
+        let mut cells = vec![
+            Cell::from(when),
+            Cell::from(highlighted(format!("{}{}", glyph, reminder.title), &found.title, glyph.chars().count())),
+            Cell::from(highlighted(first_line(&reminder.description), &found.description, 0)),
+            Cell::from(tags_line(app, &reminder.tags)),
+        ];
+        if marking {
+            let mark = if app.marked.contains(&reminder.id) { "[x]" } else { "[ ]" };
+            cells.insert(0, Cell::from(mark));
+        }
+        let row = Row::new(cells);
+        table.push(match priority_style(reminder.priority) {
+            _ if overdue => row.style(Style::default().fg(Color::Red)),
+            Some(style) if reminder.completed_at.is_none() => row.style(style),
+            _ => row,
+        });
+        table_rows.push(Some(idx));
+    }
+
+    let mut title = match app.day_filter {
+        _ if app.tab == Tab::Trash => "🗑  Trash (r: restore, d: delete forever; purged after 30 days)".to_string(),
+        _ if marking => format!("📝 Reminders ({} marked, Esc: clear)", app.marked.len()),
+        _ if !app.search.is_empty() => format!("🔍 Reminders matching \"{}\" (Esc: all)", app.search),
+        _ if app.tag_filter.is_some() => {
+            format!("🏷  Reminders tagged #{} (Esc: all)", app.tag_filter.as_deref().unwrap_or_default())
+        }
+        _ if app.quick_filter.is_some() => {
+            format!("{} (Esc: all)", app.quick_filter.map(QuickFilter::title).unwrap_or_default())
+        }
+        Some(day) => format!("📝 Reminders on {} (Esc: all)", day.format("%a %Y-%m-%d")),
+        None if app.total > app.reminders.len() => format!("📝 Reminders ({} of {})", app.reminders.len(), app.total),
+        None => "📝 Reminders".to_string(),
+    };
+    if let Some(sort) = app.sort {
+        title.push_str(&format!(" · by {} {}", sort.as_str(), if app.sort_desc { "▼" } else { "▲" }));
+    }
+    let mut widths = vec![
+        Constraint::Length(time_width.min(40) as u16),
+        Constraint::Fill(1),
+        Constraint::Fill(2),
+        Constraint::Length(16),
+    ];
+    if marking {
+        widths.insert(0, Constraint::Length(3));
+    }
+    let table = Table::new(table, widths)
+    .header(header)
+    .block(Block::default().borders(Borders::ALL).title(title))
+    .highlight_style(
+        Style::default()
+            .bg(Color::DarkGray)
+            .fg(Color::White)
+            .add_modifier(Modifier::BOLD),
+    );
 
-    let list = List::new(items)
-        .block(Block::default().borders(Borders::ALL).title("📝 Reminders"))
-        .highlight_style(Style::default().add_modifier(Modifier::BOLD));
+    let panes = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+        .split(chunks[1]);
+    // Borders and the header take three rows.
+    app.page_size = (panes[0].height as usize).saturating_sub(3).max(1);
+    app.table_state.select(table_rows.iter().position(|row| *row == Some(app.selected_idx)));
+    app.table_rows = table_rows;
+    f.render_stateful_widget(table, panes[0], &mut app.table_state);
+    app.table_area = panes[0];
+    draw_details(f, app, panes[1], now);
 
-    f.render_widget(list, chunks[0]);
+    if app.mode == Mode::Search {
+        let scroll = place_cursor(f, app, chunks[2], 2);
+        let input = Paragraph::new(format!("/ {}", app.input))
+            .block(Block::default().borders(Borders::ALL).title("Search (Enter: keep results | Esc: cancel)"))
+            .scroll(scroll);
+        f.render_widget(input, chunks[2]);
+        return;
+    }
 
-    let help_text = vec![
-        Line::from(vec![
-            Span::styled("↑↓", Style::default().fg(Color::Yellow)),
-            Span::raw(" Navigate | "),
-            Span::styled("a", Style::default().fg(Color::Green)),
-            Span::raw(" Add | "),
-            Span::styled("e", Style::default().fg(Color::Blue)),
-            Span::raw(" Edit | "),
-            Span::styled("d", Style::default().fg(Color::Red)),
-            Span::raw(" Delete | "),
-            Span::styled("q", Style::default().fg(Color::Magenta)),
-            Span::raw(" Quit"),
-        ]),
+    let mut hint = vec![
+        Span::styled("?", Style::default().fg(Color::Cyan)),
+        Span::raw(" Keys · "),
+        Span::styled("+/-", Style::default().fg(Color::Yellow)),
+        Span::raw(" Priority: "),
     ];
+    for priority in [Priority::High, Priority::Normal, Priority::Low] {
+        let name = format!("{}{} ", priority_glyph(priority), priority.as_str());
+        hint.push(Span::styled(name, priority_style(priority).unwrap_or_default()));
+    }
+    f.render_widget(Paragraph::new(Line::from(hint)).alignment(Alignment::Center), chunks[2]);
+}
 
-    let help = Paragraph::new(help_text)
-        .block(Block::default().borders(Borders::ALL).title("Help"))
-        .alignment(Alignment::Center);
+/// Every key of every mode, listed from the keymaps the keys go through.
+fn draw_help(f: &mut Frame, app: &mut AppState, area: Rect) {
+    let keys_style = Style::default().fg(Color::Cyan);
+    let mut lines: Vec<Line> = Vec::new();
+    for keymap in keymap::all(app.key_preset) {
+        if !lines.is_empty() {
+            lines.push(Line::default());
+        }
+        lines.push(Line::styled(keymap.title, Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)));
+        let mut help = keymap.help_lines();
+        if keymap.typing {
+            help.push(("Other keys".to_string(), "Type"));
+        }
+        let width = help.iter().map(|(keys, _)| keys.chars().count()).max().unwrap_or(0);
+        for (keys, what) in help {
+            lines.push(Line::from(vec![Span::styled(format!("  {:<width$}  ", keys), keys_style), Span::raw(what)]));
+        }
+    }
 
-    f.render_widget(help, chunks[1]);
+    // Borders take two rows.
+    let height = (area.height as usize).saturating_sub(2);
+    app.page_size = height.max(1);
+    app.help_scroll = app.help_scroll.min(lines.len().saturating_sub(height));
+    let help = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title("⌨  Keys (↑↓/PgUp/PgDn: scroll | Esc: close)"))
+        .scroll((app.help_scroll as u16, 0));
+    f.render_widget(help, area);
+}
+
+/// Shows everything about the selected reminder, wrapping the full description.
+fn draw_details(f: &mut Frame, app: &AppState, area: Rect, now: NaiveDateTime) {
+    let block = Block::default().borders(Borders::ALL).title("Details");
+    let Some(reminder) = app.reminders.get(app.selected_idx) else {
+        f.render_widget(Paragraph::new("No reminder selected").block(block), area);
+        return;
+    };
+
+    let label = Style::default().fg(Color::Yellow);
+    let field = |name: &str, value: String| Line::from(vec![Span::styled(format!("{:<10}", name), label), Span::raw(value)]);
+
+    let mut lines = vec![
+        Line::from(Span::styled(reminder.title.clone(), Style::default().add_modifier(Modifier::BOLD))),
+        Line::from(""),
+    ];
+    lines.extend(markdown::render(&reminder.description));
+    lines.push(Line::from(""));
+    lines.push(field("When", schedule_text(app, reminder, now)));
+    if let Some(relative) = relative_text(reminder, now) {
+        lines.push(field("Next", relative));
+    }
+    lines.push(field("Alerts", alerts_text(reminder)));
+    if !reminder.tags.is_empty() {
+        let mut tags = tags_line(app, &reminder.tags);
+        tags.spans.insert(0, Span::styled(format!("{:<10}", "Tags"), label));
+        lines.push(tags);
+    }
+    if reminder.priority != Priority::Normal {
+        lines.push(field("Priority", reminder.priority.as_str().to_string()));
+    }
+    if reminder.delete_after_firing {
+        lines.push(field("After firing", "move to trash".to_string()));
+    }
+    if reminder.skip_holidays {
+        lines.push(field("Holidays", "skipped".to_string()));
+    }
+    let mut look = Vec::new();
+    if let Some(icon) = &reminder.icon {
+        look.push(format!("icon {}", icon));
+    }
+    if let Some(category) = &reminder.category {
+        look.push(format!("category {}", category));
+    }
+    if reminder.resident {
+        look.push("stays until dismissed".to_string());
+    }
+    if !look.is_empty() {
+        lines.push(field("Notification", look.join(", ")));
+    }
+    let today = now.format("%Y-%m-%d").to_string();
+    for (i, exception) in reminder.exceptions.iter().filter(|e| e.date >= today).enumerate() {
+        let label = if i == 0 { "Exceptions" } else { "" };
+        lines.push(field(label, exception_text(app, exception)));
+    }
+    if let Some(last) = &reminder.last_fired_at {
+        lines.push(field("Last fired", last.clone()));
+    }
+    let created = DateTime::parse_from_rfc3339(&reminder.created_at)
+        .map(|at| at.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|_| reminder.created_at.clone());
+    lines.push(field("Created", created));
+    if let Some(done) = &reminder.completed_at {
+        lines.push(field("Done", done.clone()));
+    }
+    if let Some(deleted) = &reminder.deleted_at {
+        lines.push(field("Deleted", deleted.clone()));
+    }
+    lines.push(field("Id", reminder.id.to_string()));
+
+    let details = Paragraph::new(lines).block(block).wrap(Wrap { trim: false });
+    f.render_widget(details, area);
+}
+
+/// One day that differs from a reminder's schedule, e.g. `2026-10-16 at 10:30: Room 4B`.
+fn exception_text(app: &AppState, exception: &Exception) -> String {
+    let mut text = match &exception.time {
+        Some(time) => format!("{} at {}", exception.date, app.time_format.render(time)),
+        None => format!("{} skipped", exception.date),
+    };
+    if let Some(note) = &exception.note {
+        text.push_str(&format!(": {}", note));
+    }
+    text
 }
 
-fn draw_add_form(f: &mut Frame, app: &AppState) {
+fn draw_add_form(f: &mut Frame, app: &mut AppState, area: Rect) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-        .split(f.size());
+        .constraints([Constraint::Min(0), Constraint::Length(3)])
+        .split(area);
 
+    let mut constraints = vec![Constraint::Length(3); FORM_FIELD_COUNT];
+    // Room for a few lines of the description.
+    constraints[1] = Constraint::Length(DESCRIPTION_LINES + 2);
+    constraints.push(Constraint::Min(0));
     let form_chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3),
-            Constraint::Length(3),
-            Constraint::Length(3),
-            Constraint::Min(0),
-        ])
+        .constraints(constraints)
         .split(chunks[0]);
+    app.field_areas.copy_from_slice(&form_chunks[..FORM_FIELD_COUNT]);
 
+    let time_field = match app.time_format {
+        TimeFormat::H24 => ("Time (HH:MM)", "Type HH:MM (or HH:MM:SS), or pick with ↑↓ and ←→"),
+        TimeFormat::H12 => ("Time (h:MM AM/PM)", "Type a time like 6:59 PM, or pick with ↑↓ and ←→"),
+    };
     let fields = [
         ("Title", "Enter title"),
-        ("Description", "Enter description"),
-        ("Time (HH:MM)", "Enter time in HH:MM format"),
+        ("Description", "Enter description; Enter starts a new line"),
+        time_field,
+        ("Date", "YYYY-MM-DD (↓ for a calendar), or empty to repeat daily"),
+        ("Alerts", "Minutes before, comma-separated (e.g. 1h,10m,0)"),
+        ("Tags", "Comma-separated (e.g. work, family)"),
+        ("Repeat (cron)", "Optional cron schedule instead of time/date (e.g. 0 9 * * MON-FRI)"),
+        ("Every", "Optional interval from the time, e.g. 45m until 17:00"),
+        ("Days", "Optional days a repeating reminder goes off (e.g. weekdays or mon,wed,fri), or empty for every day"),
+        ("Timezone", "Optional zone the time is in (e.g. America/New_York), or empty for local"),
+        ("After firing", "Type delete to move it to the trash once it has gone off"),
+        ("Holidays", "Type skip to stay quiet on the holidays in the config's holiday file"),
     ];
 
+    // Check the form as it stands, including what is being typed.
+    let current = app.current_form();
+    let errors = form::validate(&current, app.time_format).err().unwrap_or_default();
+
     for (i, (label, hint)) in fields.iter().enumerate() {
         let style = if i == app.input_field {
             Style::default().bg(Color::Blue).fg(Color::White)
@@ -158,24 +1426,52 @@ fn draw_add_form(f: &mut Frame, app: &AppState) {
             Style::default().fg(Color::Gray)
         };
 
+        let prefix = if i == app.input_field { format!("{}> ", label) } else { format!("{}: ", label) };
         let title = if i == app.input_field {
-            format!("{}> {}", label, app.input)
+            format!("{}{}", prefix, app.input)
+        } else if app.form_fields[i].is_empty() {
+            format!("{}(empty)", prefix)
         } else {
-            format!("{}: (empty)", label)
+            format!("{}{}", prefix, app.form_fields[i])
         };
 
-        let widget = Paragraph::new(title)
-            .block(Block::default().borders(Borders::ALL).title(*hint))
-            .style(style);
+        // Empty fields are only flagged once they have been left.
+        let error = errors
+            .iter()
+            .find(|(field, _)| *field == i)
+            .filter(|_| app.touched[i] || !current[i].trim().is_empty());
+        let mut block = Block::default().borders(Borders::ALL).title(*hint);
+        if let Some((_, message)) = error {
+            let red = Style::default().fg(Color::LightRed).add_modifier(Modifier::BOLD);
+            block = block
+                .border_style(red)
+                .title(Title::from(Span::styled(message.clone(), red)).position(block::Position::Bottom));
+        }
+
+        let mut widget = Paragraph::new(title).block(block).style(style);
+        if i == app.input_field {
+            let scroll = place_cursor(f, app, form_chunks[i], prefix.chars().count() as u16);
+            widget = widget.scroll(scroll);
+        }
 
         f.render_widget(widget, form_chunks[i]);
     }
 
-    let help = Paragraph::new("Tab: Next field | Shift+Tab: Prev field | Enter: Save | Esc: Cancel")
+    if !app.completions.is_empty() {
+        draw_completions(f, app, form_chunks[app.input_field]);
+    }
+    if let Some(day) = app.date_picker {
+        draw_date_picker(f, day, form_chunks[app.input_field]);
+    }
+    if app.discarding.is_some() {
+        draw_discard_confirm(f, area);
+    }
+
+    let help = Paragraph::new("Tab: Next field | Shift+Tab: Prev field | Ctrl+N/P: Suggest | Ctrl+E: Description in $EDITOR | Enter/Ctrl+S: Save | Esc: Cancel")
         .alignment(Alignment::Center)
         .style(Style::default().fg(Color::Yellow));
 
-    f.render_widget(help, form_chunks[3]);
+    f.render_widget(help, form_chunks[FORM_FIELD_COUNT]);
 
     if let Some(err) = &app.error_msg {
         let error = Paragraph::new(err.clone())
@@ -185,18 +1481,125 @@ fn draw_add_form(f: &mut Frame, app: &AppState) {
     }
 }
 
-fn draw_edit_form(f: &mut Frame, app: &AppState) {
-    draw_add_form(f, app);
+/// Asks over the form whether to throw away what was typed.
+fn draw_discard_confirm(f: &mut Frame, area: Rect) {
+    let width = 36.min(area.width);
+    let height = 5.min(area.height);
+    let popup = Rect::new(area.x + (area.width - width) / 2, area.y + (area.height - height) / 2, width, height);
+    let confirm = Paragraph::new(vec![
+        Line::from("Discard changes?"),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("y", Style::default().fg(Color::Green)),
+            Span::raw(" - Yes | "),
+            Span::styled("n", Style::default().fg(Color::Red)),
+            Span::raw(" - No"),
+        ]),
+    ])
+    .block(Block::default().borders(Borders::ALL).title("Unsaved changes"))
+    .alignment(Alignment::Center);
+    f.render_widget(Clear, popup);
+    f.render_widget(confirm, popup);
+}
+
+/// A small month calendar under the date field, with `day` selected.
+fn draw_date_picker(f: &mut Frame, day: NaiveDate, field: Rect) {
+    let today = Local::now().date_naive();
+    let first = day.with_day(1).unwrap_or(day);
+    let next_month = first.checked_add_months(Months::new(1)).unwrap_or(first);
+
+    let mut lines = vec![Line::from(Span::styled("Mo Tu We Th Fr Sa Su", Style::default().fg(Color::Yellow)))];
+    let mut week = vec![Span::raw("   ".repeat(first.weekday().num_days_from_monday() as usize))];
+    let mut date = first;
+    while date < next_month {
+        let mut style = Style::default();
+        if date == today {
+            style = style.fg(Color::Green).add_modifier(Modifier::BOLD);
+        }
+        if date == day {
+            style = style.bg(Color::Blue).fg(Color::White);
+        }
+        week.push(Span::styled(format!("{:>2}", date.day()), style));
+        week.push(Span::raw(" "));
+        if date.weekday() == Weekday::Sun {
+            lines.push(Line::from(std::mem::take(&mut week)));
+        }
+        date += Duration::days(1);
+    }
+    if !week.is_empty() {
+        lines.push(Line::from(week));
+    }
+
+    let screen = f.size();
+    let area = Rect {
+        x: field.x + 2,
+        y: field.bottom(),
+        width: 22.min(screen.width.saturating_sub(field.x + 2)),
+        height: (lines.len() as u16 + 2).min(screen.height.saturating_sub(field.bottom())),
+    };
+    let block = Block::default().borders(Borders::ALL).title(day.format("%B %Y").to_string());
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Lists the suggestions under the field being completed.
+fn draw_completions(f: &mut Frame, app: &AppState, field: Rect) {
+    let screen = f.size();
+    let width = app.completions.iter().map(|c| c.chars().count() as u16).max().unwrap_or(0) + 4;
+    let area = Rect {
+        x: field.x + 2,
+        y: field.bottom(),
+        width: width.min(screen.width.saturating_sub(field.x + 2)),
+        height: (app.completions.len() as u16 + 2).min(screen.height.saturating_sub(field.bottom())),
+    };
+    let items: Vec<ListItem> = app
+        .completions
+        .iter()
+        .enumerate()
+        .map(|(i, completion)| {
+            let style = if app.completion == Some(i) {
+                Style::default().bg(Color::Yellow).fg(Color::Black)
+            } else {
+                Style::default()
+            };
+            ListItem::new(completion.clone()).style(style)
+        })
+        .collect();
+    f.render_widget(Clear, area);
+    f.render_widget(List::new(items).block(Block::default().borders(Borders::ALL)), area);
+}
+
+/// Puts the terminal cursor at the input cursor inside `area`, and returns
+/// how far to scroll the input so the cursor stays in view.
+fn place_cursor(f: &mut Frame, app: &AppState, area: Rect, prefix: u16) -> (u16, u16) {
+    let inner = Block::default().borders(Borders::ALL).inner(area);
+    let (line, column) = app.cursor_position();
+    let column = if line == 0 { prefix + column } else { column };
+    let scroll = (
+        line.saturating_sub(inner.height.saturating_sub(1)),
+        column.saturating_sub(inner.width.saturating_sub(1)),
+    );
+    f.set_cursor(inner.x + column - scroll.1, inner.y + line - scroll.0);
+    scroll
+}
+
+fn draw_edit_form(f: &mut Frame, app: &mut AppState, area: Rect) {
+    draw_add_form(f, app, area);
 }
 
-fn draw_delete_confirm(f: &mut Frame, app: &AppState) {
+fn draw_delete_confirm(f: &mut Frame, app: &AppState, area: Rect) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-        .split(f.size());
+        .split(area);
 
-    if let Some(reminder) = app.reminders.get(app.selected_idx) {
-        let msg = format!("Delete reminder: '{}'?", reminder.title);
+    let action = if app.tab == Tab::Trash { "Permanently delete" } else { "Delete" };
+    let msg = if !app.marked.is_empty() {
+        Some(format!("{} {} marked reminder(s)?", action, app.marked.len()))
+    } else {
+        app.reminders.get(app.selected_idx).map(|r| format!("{} reminder: '{}'?", action, r.title))
+    };
+    if let Some(msg) = msg {
         let confirm = Paragraph::new(vec![
             Line::from(msg),
             Line::from(""),
@@ -212,4 +1615,538 @@ fn draw_delete_confirm(f: &mut Frame, app: &AppState) {
 
         f.render_widget(confirm, chunks[0]);
     }
-}
\ No newline at end of file
+}
+
+/// Lists the profiles over the reminder list, marking the current one.
+fn draw_profiles(f: &mut Frame, app: &AppState, area: Rect) {
+    let choices = app.profile_choices();
+    let width = 40.min(area.width);
+    let height = (choices.len() as u16 + 3).min(area.height);
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+
+    let mut items: Vec<ListItem> = choices
+        .iter()
+        .enumerate()
+        .map(|(i, choice)| {
+            let current = if *choice == app.profile { "● " } else { "  " };
+            let item = ListItem::new(format!("{}{}", current, choice.as_deref().unwrap_or("default")));
+            if i == app.profile_idx {
+                item.style(Style::default().bg(Color::DarkGray).fg(Color::White).add_modifier(Modifier::BOLD))
+            } else {
+                item
+            }
+        })
+        .collect();
+    if let Some(err) = &app.error_msg {
+        items.push(ListItem::new(Span::styled(err.clone(), Style::default().fg(Color::Red))));
+    }
+    let list = List::new(items).block(Block::default().borders(Borders::ALL).title("Profiles (Enter: switch | Esc: cancel)"));
+    f.render_widget(Clear, popup);
+    f.render_widget(list, popup);
+}
+
+/// The snooze presets over the list, each with when it would end.
+fn draw_snooze(f: &mut Frame, app: &AppState, area: Rect) {
+    let now = Local::now().naive_local();
+    let width = 40.min(area.width);
+    let height = (app.snooze_presets.len() as u16 + 2).min(area.height);
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+
+    let items: Vec<ListItem> = app
+        .snooze_presets
+        .iter()
+        .enumerate()
+        .map(|(i, preset)| {
+            let until = match snooze_until(preset, now) {
+                Some(until) if until.date() == now.date() => app.time_format.format(until.time()),
+                Some(until) => format!("{} {}", until.format("%a %b %-d"), app.time_format.format(until.time())),
+                None => "?".to_string(),
+            };
+            let item = ListItem::new(format!("{}  {:<12} {}", i + 1, preset, until));
+            if i == app.snooze_idx {
+                item.style(Style::default().bg(Color::DarkGray).fg(Color::White).add_modifier(Modifier::BOLD))
+            } else {
+                item
+            }
+        })
+        .collect();
+    let list = List::new(items).block(Block::default().borders(Borders::ALL).title("Snooze until (Enter | Esc)"));
+    f.render_widget(Clear, popup);
+    f.render_widget(list, popup);
+}
+
+/// The tag picker, over the list.
+fn draw_tags(f: &mut Frame, app: &AppState, area: Rect) {
+    let width = 40.min(area.width);
+    let height = (app.tag_counts.len() as u16 + 2).min(area.height);
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+
+    let items: Vec<ListItem> = app
+        .tag_counts
+        .iter()
+        .enumerate()
+        .map(|(i, (tag, count))| {
+            let mut line = tags_line(app, std::slice::from_ref(tag));
+            line.spans.push(Span::raw(format!("  {}", count)));
+            let item = ListItem::new(line);
+            if i == app.tag_idx {
+                item.style(Style::default().bg(Color::DarkGray).fg(Color::White).add_modifier(Modifier::BOLD))
+            } else {
+                item
+            }
+        })
+        .collect();
+    // Keep the cursor in view when there are more tags than rows.
+    let mut state = ratatui::widgets::ListState::default().with_selected(Some(app.tag_idx));
+    let list = List::new(items).block(Block::default().borders(Borders::ALL).title("Show only (Enter | Esc)"));
+    f.render_widget(Clear, popup);
+    f.render_stateful_widget(list, popup, &mut state);
+}
+
+/// The occurrence editor, over the list.
+fn draw_occurrence(f: &mut Frame, app: &AppState, area: Rect) {
+    let Some(edit) = &app.occurrence else {
+        return;
+    };
+    let width = 70.min(area.width);
+    let height = 13.min(area.height);
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("\"{}\" on one day (Enter: save | Esc: cancel)", edit.reminder.title));
+    let inner = block.inner(popup);
+    f.render_widget(Clear, popup);
+    f.render_widget(block, popup);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Length(3), Constraint::Length(3), Constraint::Min(0)])
+        .split(inner);
+    let fields = [
+        ("Date", "YYYY-MM-DD"),
+        ("Time", "Another time that day, or empty to skip it"),
+        ("Note", "Optional, added to that day's notification"),
+    ];
+    draw_popup_fields(f, app, &rows, &fields, &edit.fields, edit.field);
+    let footer = match &app.error_msg {
+        Some(err) => Paragraph::new(err.clone()).style(Style::default().fg(Color::Red)),
+        None => Paragraph::new("Keep the usual time with no note to undo a change").style(Style::default().fg(Color::DarkGray)),
+    };
+    f.render_widget(footer, rows[3]);
+}
+
+/// The notification settings sub-form, over the list.
+fn draw_settings(f: &mut Frame, app: &AppState, area: Rect) {
+    let Some(edit) = &app.settings else {
+        return;
+    };
+    let width = 70.min(area.width);
+    let height = 13.min(area.height);
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("Notifications for \"{}\" (Enter: save | Esc: cancel)", edit.reminder.title));
+    let inner = block.inner(popup);
+    f.render_widget(Clear, popup);
+    f.render_widget(block, popup);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Length(3), Constraint::Length(3), Constraint::Min(0)])
+        .split(inner);
+    let fields = [
+        ("Icon", "Optional icon name (e.g. dialog-warning) or image path"),
+        ("Category", "Optional, e.g. im or x-medication"),
+        ("Stays", "Type keep to leave it on screen until dismissed"),
+    ];
+    draw_popup_fields(f, app, &rows, &fields, &edit.fields, edit.field);
+    if let Some(err) = &app.error_msg {
+        f.render_widget(Paragraph::new(err.clone()).style(Style::default().fg(Color::Red)), rows[3]);
+    }
+}
+
+/// The labelled fields of a popup form, one per row, with `focused` being
+/// typed into.
+fn draw_popup_fields(f: &mut Frame, app: &AppState, rows: &[Rect], fields: &[(&str, &str)], values: &[String], focused: usize) {
+    for (i, (label, hint)) in fields.iter().enumerate() {
+        let block = Block::default().borders(Borders::ALL).title(*hint);
+        if i == focused {
+            let prefix = format!("{}> ", label);
+            let scroll = place_cursor(f, app, rows[i], prefix.chars().count() as u16);
+            let widget = Paragraph::new(format!("{}{}", prefix, app.input))
+                .block(block)
+                .style(Style::default().bg(Color::Blue).fg(Color::White))
+                .scroll(scroll);
+            f.render_widget(widget, rows[i]);
+        } else {
+            let widget = Paragraph::new(format!("{}: {}", label, values[i]))
+                .block(block)
+                .style(Style::default().fg(Color::Gray));
+            f.render_widget(widget, rows[i]);
+        }
+    }
+}
+
+/// What went off while the app wasn't running, over the list.
+fn draw_missed(f: &mut Frame, app: &AppState, area: Rect) {
+    let width = 60.min(area.width);
+    let height = (app.missed.len() as u16 + 2).min(area.height);
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+
+    let items: Vec<ListItem> = app
+        .missed
+        .iter()
+        .map(|missed| {
+            let mut line = vec![
+                Span::styled(format!("{}  ", missed.at), Style::default().fg(Color::Cyan)),
+                Span::raw(missed.title.clone()),
+            ];
+            if missed.times > 1 {
+                line.push(Span::styled(format!(" ({}×)", missed.times), Style::default().fg(Color::Gray)));
+            }
+            ListItem::new(Line::from(line))
+        })
+        .collect();
+    let why = if app.missed_while_muted { "muted" } else { "away" };
+    let title = format!("⏰ {} missed while {} (Enter: dismiss)", app.missed.len(), why);
+    let list = List::new(items).block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(Clear, popup);
+    f.render_widget(list, popup);
+}
+
+fn draw_duplicate_confirm(f: &mut Frame, app: &AppState, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+
+    if let Some((new, existing)) = &app.duplicate {
+        let when = match &existing.cron {
+            Some(cron) => cron.clone(),
+            None => app.time_format.render(&existing.time),
+        };
+        let confirm = Paragraph::new(vec![
+            Line::from(format!("'{}' at {} already exists.", existing.title, when)),
+            Line::from(format!("Add '{}' anyway?", new.title)),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("s", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Skip | "),
+                Span::styled("r", Style::default().fg(Color::Blue)),
+                Span::raw(" - Replace it | "),
+                Span::styled("k", Style::default().fg(Color::Green)),
+                Span::raw(" - Keep both"),
+            ]),
+        ])
+        .block(Block::default().borders(Borders::ALL).title("Duplicate Reminder"))
+        .alignment(Alignment::Center);
+
+        f.render_widget(confirm, chunks[0]);
+    }
+}
+
+/// Hides everything behind a PIN prompt.
+fn draw_lock_screen(f: &mut Frame, app: &AppState) {
+    let area = f.size();
+    let width = 40.min(area.width);
+    let height = 5.min(area.height);
+    let prompt = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+
+    let mut lines = vec![Line::from(format!("PIN: {}", "*".repeat(app.unlock_input.chars().count())))];
+    if app.unlock_failed {
+        lines.push(Line::from(Span::styled("Wrong PIN", Style::default().fg(Color::Red))));
+    }
+    let lock = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title("🔒 Locked (Enter: unlock)"))
+        .alignment(Alignment::Center);
+    f.render_widget(Clear, area);
+    f.render_widget(lock, prompt);
+}
+
+fn draw_quick_add(f: &mut Frame, app: &AppState, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(2),
+            Constraint::Min(0),
+        ])
+        .split(area);
+
+    let scroll = place_cursor(f, app, chunks[0], 2);
+    let input = Paragraph::new(format!("> {}", app.input))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Quick add, e.g. Call mom tomorrow 18:30 #family"),
+        )
+        .style(Style::default().bg(Color::Blue).fg(Color::White))
+        .scroll(scroll);
+    f.render_widget(input, chunks[0]);
+
+    if !app.input.trim().is_empty() {
+        let preview = match quick_add::parse(&app.input, Local::now().naive_local()) {
+            Ok(parsed) => {
+                let when = match parsed.date {
+                    Some(date) => format!("{} {}", date.format("%a %Y-%m-%d"), app.time_format.format(parsed.time)),
+                    None => format!("every day {}", app.time_format.format(parsed.time)),
+                };
+                let tags: String = parsed.tags.iter().map(|t| format!(" #{}", t)).collect();
+                Paragraph::new(format!("{} · {}{}", parsed.title, when, tags))
+                    .style(Style::default().fg(Color::Green))
+            }
+            Err(e) => Paragraph::new(e).style(Style::default().fg(Color::DarkGray)),
+        };
+        f.render_widget(preview.block(Block::default().borders(Borders::ALL).title("Preview")), chunks[1]);
+    }
+
+    let help = Paragraph::new("Enter: Save | Esc: Cancel")
+        .alignment(Alignment::Center)
+        .style(Style::default().fg(Color::Yellow));
+    f.render_widget(help, chunks[2]);
+
+    if let Some(err) = &app.error_msg {
+        let error = Paragraph::new(err.clone())
+            .block(Block::default().borders(Borders::ALL).title("Error"))
+            .style(Style::default().fg(Color::Red));
+        f.render_widget(error, chunks[3]);
+    }
+}
+
+fn draw_calendar(f: &mut Frame, app: &AppState, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(3)])
+        .split(area);
+
+    let selected = app.calendar_day;
+    let today = Local::now().date_naive();
+    let first = selected.with_day(1).unwrap_or(selected);
+    let next_month = first.checked_add_months(Months::new(1)).unwrap_or(first);
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(" Mo   Tu   We   Th   Fr   Sa   Su", Style::default().fg(Color::Yellow))),
+    ];
+    let mut week = vec![Span::raw("     ".repeat(first.weekday().num_days_from_monday() as usize))];
+    let mut day = first;
+    while day < next_month {
+        let marker = if app.reminders.iter().any(|r| schedule::fires_on(r, day)) { "•" } else { " " };
+        let mut style = Style::default();
+        if day == today {
+            style = style.fg(Color::Green).add_modifier(Modifier::BOLD);
+        }
+        if day == selected {
+            style = style.bg(Color::Blue).fg(Color::White);
+        }
+        week.push(Span::styled(format!(" {:>2}{}", day.day(), marker), style));
+        week.push(Span::raw(" "));
+
+        if day.weekday() == Weekday::Sun {
+            lines.push(Line::from(std::mem::take(&mut week)));
+            lines.push(Line::from(""));
+        }
+        day += Duration::days(1);
+    }
+    if !week.is_empty() {
+        // Pad the last week so centring keeps the columns aligned.
+        let rest = 6 - (next_month - Duration::days(1)).weekday().num_days_from_monday() as usize;
+        week.push(Span::raw("     ".repeat(rest)));
+        lines.push(Line::from(week));
+    }
+
+    let calendar = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title(format!("📅 {}", selected.format("%B %Y"))))
+        .alignment(Alignment::Center);
+    f.render_widget(calendar, chunks[0]);
+
+    let help = Paragraph::new("←→↑↓ Move | PgUp/PgDn Month | t Today | Enter Show day | Esc Back")
+        .block(Block::default().borders(Borders::ALL).title("Help"))
+        .alignment(Alignment::Center)
+        .style(Style::default().fg(Color::Yellow));
+    f.render_widget(help, chunks[1]);
+}
+
+fn draw_agenda(f: &mut Frame, app: &AppState, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(3)])
+        .split(area);
+
+    let today = Local::now().date_naive();
+    let monday = app.calendar_day - Duration::days(app.calendar_day.weekday().num_days_from_monday() as i64);
+
+    let mut lines = Vec::new();
+    for day in (0..7).map(|offset| monday + Duration::days(offset)) {
+        let mut heading = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
+        if day == today {
+            heading = heading.fg(Color::Green);
+        }
+        lines.push(Line::from(Span::styled(day.format("%A %Y-%m-%d").to_string(), heading)));
+
+        let mut entries: Vec<_> = app
+            .reminders
+            .iter()
+            .filter_map(|r| {
+                let at = schedule::next_fire(r, day.and_time(NaiveTime::MIN))?;
+                (at.date() == day).then_some((at.time(), r))
+            })
+            .collect();
+        entries.sort_by_key(|(time, _)| *time);
+
+        if entries.is_empty() {
+            lines.push(Line::from(Span::styled("  —", Style::default().fg(Color::DarkGray))));
+        }
+        for (time, reminder) in entries {
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {:>8}  ", app.time_format.format(time)), Style::default().fg(Color::Cyan)),
+                Span::raw(reminder.title.clone()),
+                Span::styled(format!(" - {}", first_line(&reminder.description)), Style::default().fg(Color::Gray)),
+            ]));
+        }
+        lines.push(Line::from(""));
+    }
+
+    let title = format!(
+        "🗓  Week of {} – {}",
+        monday.format("%b %-d"),
+        (monday + Duration::days(6)).format("%b %-d, %Y")
+    );
+    let agenda = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(agenda, chunks[0]);
+
+    let help = Paragraph::new("←→ Previous/next week | t This week | Esc Back")
+        .block(Block::default().borders(Borders::ALL).title("Help"))
+        .alignment(Alignment::Center)
+        .style(Style::default().fg(Color::Yellow));
+    f.render_widget(help, chunks[1]);
+}
+
+/// The end of the log, newest line at the bottom.
+fn draw_logs(f: &mut Frame, app: &mut AppState, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(3)])
+        .split(area);
+
+    let height = chunks[0].height.saturating_sub(2) as usize;
+    app.page_size = height.max(1);
+    app.log_scroll = app.log_scroll.min(app.log.len().saturating_sub(height));
+    let end = app.log.len() - app.log_scroll;
+    let mut lines: Vec<Line> = app.log[end.saturating_sub(height)..end]
+        .iter()
+        .map(|line| Line::from(Span::styled(line.clone(), log_style(line))))
+        .collect();
+    if lines.is_empty() {
+        lines.push(Line::from(Span::styled("Nothing logged yet", Style::default().fg(Color::DarkGray))));
+    }
+
+    let mut title = match &app.log_path {
+        Some(path) => format!("📜 {}", path.display()),
+        None => "📜 Log".to_string(),
+    };
+    if app.log_scroll > 0 {
+        title.push_str(&format!(" ({} newer line(s) below)", app.log_scroll));
+    }
+    let log = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(log, chunks[0]);
+
+    let help = Paragraph::new("↑↓/PgUp/PgDn Scroll | Home/End Oldest/Newest | Esc Back")
+        .block(Block::default().borders(Borders::ALL).title("Help"))
+        .alignment(Alignment::Center)
+        .style(Style::default().fg(Color::Yellow));
+    f.render_widget(help, chunks[1]);
+}
+
+/// Colours a log line by its level.
+fn log_style(line: &str) -> Style {
+    if line.contains(" ERROR ") {
+        Style::default().fg(Color::Red)
+    } else if line.contains(" WARN ") {
+        Style::default().fg(Color::Yellow)
+    } else if line.contains(" DEBUG ") || line.contains(" TRACE ") {
+        Style::default().fg(Color::DarkGray)
+    } else {
+        Style::default()
+    }
+}
+
+/// Every notification shown or tried, newest first, with what became of it.
+fn draw_notifications(f: &mut Frame, app: &mut AppState, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(3)])
+        .split(area);
+
+    // Borders and the header take three rows.
+    let height = (chunks[0].height as usize).saturating_sub(3);
+    app.page_size = height.max(1);
+    app.notifications_scroll = app.notifications_scroll.min(app.notifications.len().saturating_sub(height));
+
+    let header = Row::new(["When", "Result", "Notification", "Error"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let rows = app.notifications.iter().skip(app.notifications_scroll).map(|notification| {
+        let (result, color) = match notification.delivery {
+            Delivery::Delivered => ("delivered", Color::Green),
+            Delivery::Failed => ("failed", Color::Red),
+            Delivery::HeldBack => ("held back", Color::Yellow),
+            Delivery::Muted => ("muted", Color::Gray),
+        };
+        Row::new(vec![
+            Span::raw(notification.at.clone()),
+            Span::styled(result, Style::default().fg(color)),
+            Span::raw(notification.summary.clone()),
+            Span::styled(notification.error.clone().unwrap_or_default(), Style::default().fg(Color::Gray)),
+        ])
+    });
+    let widths = [Constraint::Length(19), Constraint::Length(9), Constraint::Fill(2), Constraint::Fill(1)];
+    let title = if app.notifications.is_empty() {
+        "🔔 Notifications (none yet)".to_string()
+    } else {
+        format!("🔔 Notifications (last {})", app.notifications.len())
+    };
+    let table = Table::new(rows, widths).header(header).block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(table, chunks[0]);
+
+    let help = Paragraph::new("↑↓/PgUp/PgDn Scroll | Home/End Newest/Oldest | Esc Back")
+        .block(Block::default().borders(Borders::ALL).title("Help"))
+        .alignment(Alignment::Center)
+        .style(Style::default().fg(Color::Yellow));
+    f.render_widget(help, chunks[1]);
+}
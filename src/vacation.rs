@@ -0,0 +1,53 @@
+use crate::config::Config;
+use crate::{open_store, validate_date_format};
+use chrono::Local;
+use std::error::Error;
+
+/// `reminder vacation add`: suspends reminders from `start` to `end`, only
+/// those tagged with one of `tags` unless none are given.
+pub fn add(config: &Config, start: &str, end: &str, tags: &[String]) -> Result<(), Box<dyn Error>> {
+    for date in [start, end] {
+        if !validate_date_format(date) {
+            return Err(format!("invalid date {:?}, expected YYYY-MM-DD", date).into());
+        }
+    }
+    if end < start {
+        return Err(format!("the vacation ends ({}) before it starts ({})", end, start).into());
+    }
+    let tags: Vec<String> = tags.iter().map(|tag| tag.trim_start_matches('#').to_lowercase()).collect();
+    let vacation = open_store(config)?.add_vacation(start, end, &tags)?;
+    println!("Added vacation {}: {}", vacation.id, describe(&vacation.start, &vacation.end, &vacation.tags));
+    Ok(())
+}
+
+/// `reminder vacation list`: the ones that haven't ended yet.
+pub fn list(config: &Config) -> Result<(), Box<dyn Error>> {
+    let today = Local::now().format("%Y-%m-%d").to_string();
+    let vacations: Vec<_> = open_store(config)?.vacations()?.into_iter().filter(|v| v.end >= today).collect();
+    if vacations.is_empty() {
+        println!("No vacations planned");
+    }
+    for vacation in vacations {
+        println!("{:>4}  {}", vacation.id, describe(&vacation.start, &vacation.end, &vacation.tags));
+    }
+    Ok(())
+}
+
+/// `reminder vacation remove`: reminders resume straight away.
+pub fn remove(config: &Config, id: i32) -> Result<(), Box<dyn Error>> {
+    let store = open_store(config)?;
+    if !store.vacations()?.iter().any(|v| v.id == id) {
+        return Err(format!("no vacation {}", id).into());
+    }
+    store.delete_vacation(id)?;
+    println!("Removed vacation {}", id);
+    Ok(())
+}
+
+fn describe(start: &str, end: &str, tags: &[String]) -> String {
+    let what = match tags {
+        [] => "all reminders".to_string(),
+        tags => tags.iter().map(|tag| format!("#{}", tag)).collect::<Vec<_>>().join(" "),
+    };
+    format!("{} to {}, {}", start, end, what)
+}
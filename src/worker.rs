@@ -0,0 +1,786 @@
+use crate::backup;
+use crate::config::Config;
+use crate::db::{Delivery, LoggedNotification, Reminder, Vacation};
+use crate::holidays;
+use reminder::{store, ReminderStore, SharedStore};
+use crate::events::{publish, EventBus, Missed, ReminderEvent};
+use crate::schedule;
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime};
+use notify_rust::{Notification, Timeout};
+use std::{collections::BTreeSet, sync::Arc};
+use tokio::{
+    sync::mpsc::UnboundedReceiver,
+    task,
+    time::{timeout_at, Instant},
+};
+use tracing::{debug, error, info, warn};
+
+/// The longest the worker sleeps between checks, even with nothing due:
+/// its timer stops while the machine is asleep and doesn't follow changes
+/// to the clock.
+const LONGEST_SLEEP: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// The most fires of one reminder counted as missed; an every-minute cron
+/// over a long trip would otherwise take a while to count.
+const MISSED_LIMIT: usize = 1000;
+
+/// How long after a notification its reminder still counts as due, for
+/// "Snooze all".
+const DUE_FOR_MINUTES: i64 = 15;
+
+/// How soon to try again after a notification couldn't be shown, while its
+/// minute lasts.
+const RETRY_AFTER: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Notifications looked through for what is due.
+const DUE_LOOKBACK: usize = 200;
+
+/// Trashed reminders are deleted for good after this many days.
+const TRASH_DAYS: i64 = 30;
+
+/// Reminders trashed before this (`YYYY-MM-DD HH:MM`) are due to be purged.
+pub(crate) fn trash_cutoff(now: DateTime<Local>) -> String {
+    (now - Duration::days(TRASH_DAYS)).format("%Y-%m-%d %H:%M").to_string()
+}
+
+/// The most notifications waiting on their "Snooze all" button at once.
+/// Each waits on a thread until it closes, which a resident one only does
+/// when dismissed, so past this many they are shown without the button.
+#[cfg(all(unix, not(target_os = "macos")))]
+const MAX_ACTION_WAITERS: usize = 8;
+
+#[cfg(all(unix, not(target_os = "macos")))]
+static ACTION_WAITERS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+/// One of the [`MAX_ACTION_WAITERS`] places, given back when dropped.
+#[cfg(all(unix, not(target_os = "macos")))]
+struct ActionWaiter;
+
+#[cfg(all(unix, not(target_os = "macos")))]
+impl ActionWaiter {
+    fn take() -> Option<Self> {
+        use std::sync::atomic::Ordering;
+        ACTION_WAITERS
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| (n < MAX_ACTION_WAITERS).then_some(n + 1))
+            .ok()
+            .map(|_| ActionWaiter)
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+impl Drop for ActionWaiter {
+    fn drop(&mut self) {
+        ACTION_WAITERS.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+fn show_notification(summary: &str, body: &str) -> Result<(), notify_rust::error::Error> {
+    Notification::new()
+        .summary(summary)
+        .body(body)
+        .timeout(5000)
+        .show()
+        .map(|_| ())
+}
+
+/// Like [`show_notification`], in `reminder`'s icon, category and
+/// residency, with a "Snooze all" button that asks the worker to snooze
+/// everything due, for a morning routine that goes off at once. Only
+/// notification servers on Linux and the BSDs have buttons and categories,
+/// and at most [`MAX_ACTION_WAITERS`] notifications have the button at once.
+fn show_reminder_notification(
+    reminder: &Reminder,
+    summary: &str,
+    body: &str,
+    events: &EventBus,
+) -> Result<(), notify_rust::error::Error> {
+    let mut notification = Notification::new();
+    notification.summary(summary).body(body);
+    if let Some(icon) = &reminder.icon {
+        notification.icon(icon);
+    }
+    notification.timeout(if reminder.resident { Timeout::Never } else { Timeout::Milliseconds(5000) });
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        use notify_rust::Hint;
+        if let Some(category) = &reminder.category {
+            notification.hint(Hint::Category(category.clone()));
+        }
+        if reminder.resident {
+            notification.hint(Hint::Resident(true));
+        }
+        let Some(waiter) = ActionWaiter::take() else {
+            debug!("too many notifications waiting on a button; showing this one without");
+            return notification.show().map(|_| ());
+        };
+        let handle = notification.action("snooze-all", "Snooze all").show()?;
+        let events = events.clone();
+        // Waiting blocks until the notification closes.
+        std::thread::spawn(move || {
+            handle.wait_for_action(|action| {
+                if action == "snooze-all" {
+                    publish(&events, ReminderEvent::SnoozeAll);
+                }
+            });
+            drop(waiter);
+        });
+        Ok(())
+    }
+    #[cfg(not(all(unix, not(target_os = "macos"))))]
+    {
+        let _ = events;
+        notification.show().map(|_| ())
+    }
+}
+
+/// Snoozes every open reminder notified about in the last
+/// [`DUE_FOR_MINUTES`] for `minutes` from `now`. Returns the titles snoozed.
+pub(crate) fn snooze_due(db: &dyn ReminderStore, now: DateTime<Local>, minutes: i64) -> store::Result<Vec<String>> {
+    let since = (now - Duration::minutes(DUE_FOR_MINUTES)).format("%Y-%m-%d %H:%M:%S").to_string();
+    let until = (now + Duration::minutes(minutes)).format("%Y-%m-%d %H:%M").to_string();
+    let mut ids = Vec::new();
+    for notification in db.notification_log(DUE_LOOKBACK)?.into_iter().take_while(|n| n.at >= since) {
+        match notification.reminder_id {
+            Some(id) if notification.delivery == Delivery::Delivered && !ids.contains(&id) => ids.push(id),
+            _ => {}
+        }
+    }
+    let mut titles = Vec::new();
+    for id in ids {
+        if let Some(reminder) = db.get_reminder(id)?.filter(|r| r.completed_at.is_none()) {
+            db.snooze_reminder(id, &until)?;
+            titles.push(reminder.title);
+        }
+    }
+    Ok(titles)
+}
+
+/// Adds a notification for `reminder_id` (or several, for `None`) to the
+/// store's notification log.
+fn record(db: &dyn ReminderStore, reminder_id: Option<i32>, summary: &str, delivery: Delivery, error: Option<String>) {
+    let notification = LoggedNotification {
+        reminder_id,
+        at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        summary: summary.to_string(),
+        delivery,
+        error,
+    };
+    if let Err(e) = db.log_notification(&notification) {
+        error!("failed to log the notification: {}", e);
+    }
+}
+
+/// Notifications not shown straight away, and what the next wake is worked
+/// out from, carried from one check to the next.
+#[derive(Default)]
+struct Held {
+    /// Set with `m` in the TUI or `muted` in the config.
+    muted: bool,
+    /// Whether the current check falls inside quiet hours.
+    quiet: bool,
+    /// Alerts that fell inside quiet hours, delivered as one summary once they end.
+    quiet_hours: Vec<String>,
+    /// Alerts that went off while muted, handed to the TUI once unmuted.
+    while_muted: Vec<Missed>,
+    /// A notification failed in the last check and is worth trying again soon.
+    retry: bool,
+    /// The open reminders [`next_wake`] looks through, read again only once
+    /// reminders change or an interval reminder goes off.
+    upcoming: Option<Vec<Reminder>>,
+}
+
+/// Shows a notification for `reminder` (or several, for `None`), or holds
+/// it back while muted or during quiet hours, and logs what became of it.
+/// Returns `false` if it couldn't be shown, so the caller can retry on the
+/// next tick.
+fn deliver(
+    db: &dyn ReminderStore,
+    held: &mut Held,
+    events: &EventBus,
+    at: &str,
+    reminder: Option<&Reminder>,
+    summary: &str,
+    body: &str,
+) -> bool {
+    let reminder_id = reminder.map(|r| r.id);
+    if held.muted {
+        info!(summary, "muted");
+        // Summaries of several are left out; their reminders are reported on their own.
+        if let Some(id) = reminder_id {
+            let at = Local::now().format("%Y-%m-%d %H:%M").to_string();
+            match held.while_muted.iter_mut().find(|m| m.id == id && m.title == summary) {
+                Some(missed) => {
+                    missed.at = at;
+                    missed.times += 1;
+                }
+                None => held.while_muted.push(Missed { id, title: summary.to_string(), at, times: 1 }),
+            }
+        }
+        record(db, reminder_id, summary, Delivery::Muted, None);
+        return true;
+    }
+    if held.quiet {
+        info!(summary, "held back for quiet hours");
+        held.quiet_hours.push(format!("{} {}", at, summary));
+        record(db, reminder_id, summary, Delivery::HeldBack, None);
+        return true;
+    }
+    let shown = match reminder {
+        Some(reminder) => show_reminder_notification(reminder, summary, body, events),
+        None => show_notification(summary, body),
+    };
+    match shown {
+        Ok(_) => {
+            info!(summary, "notified");
+            record(db, reminder_id, summary, Delivery::Delivered, None);
+            true
+        }
+        Err(e) => {
+            warn!(summary, "failed to send notification: {}", e);
+            record(db, reminder_id, summary, Delivery::Failed, Some(e.to_string()));
+            held.retry = true;
+            false
+        }
+    }
+}
+
+/// Days reminders stay quiet on: vacations, and holidays for those marked
+/// to skip them.
+#[derive(Default)]
+struct DaysOff {
+    vacations: Vec<Vacation>,
+    holidays: BTreeSet<NaiveDate>,
+}
+
+impl DaysOff {
+    /// Reads them afresh, so edits to the holiday file apply without a restart.
+    fn load(config: &Config, db: &dyn ReminderStore) -> Self {
+        let vacations = db.vacations().unwrap_or_else(|e| {
+            error!("failed to look up vacations: {}", e);
+            Vec::new()
+        });
+        let holidays = config.holidays.as_deref().map_or_else(BTreeSet::new, |path| {
+            holidays::load(path).unwrap_or_else(|e| {
+                error!(path = %path.display(), "failed to read the holidays: {}", e);
+                BTreeSet::new()
+            })
+        });
+        DaysOff { vacations, holidays }
+    }
+
+    /// Whether `reminder` stays quiet on `day`.
+    fn covers(&self, reminder: &Reminder, day: NaiveDate) -> bool {
+        (reminder.skip_holidays && self.holidays.contains(&day)) || self.vacations.iter().any(|v| v.covers(reminder, day))
+    }
+}
+
+/// Moves a reminder that has just gone off to the trash if it is marked to
+/// delete after firing, or is a one-off and the config says so.
+fn delete_after_firing(config: &Config, db: &dyn ReminderStore, reminder: &Reminder, events: &EventBus) {
+    let one_off = reminder.date.is_some() && reminder.cron.is_none() && reminder.interval_minutes.is_none();
+    if !(reminder.delete_after_firing || (config.delete_after_firing && one_off)) {
+        return;
+    }
+    match db.delete_reminder(reminder.id) {
+        Ok(()) => publish(events, ReminderEvent::Changed),
+        Err(e) => error!(id = reminder.id, "failed to move a fired reminder to the trash: {}", e),
+    }
+}
+
+/// What wakes the worker up besides its regular check.
+pub enum WorkerEvent {
+    Reminder(ReminderEvent),
+    /// Finish up and return.
+    Stop,
+}
+
+/// Checks for due alerts until told to stop, sleeping until the minute the
+/// next one is due (see [`next_wake`]), and straight away when reminders
+/// change so the next wake is worked out afresh and edits for the current
+/// minute aren't missed.
+/// Follows the TUI to another profile's settings when it switches, and to
+/// muting, which lasts until unmuted whatever the profile. The checks
+/// themselves talk to the store and the notification daemon, so they run on
+/// the blocking pool.
+pub async fn notification_worker(
+    mut config: Config,
+    db: Arc<SharedStore>,
+    inbox: &mut UnboundedReceiver<WorkerEvent>,
+    events: EventBus,
+) {
+    let mut held = Held { muted: config.muted, ..Default::default() };
+    let mut next_check = Instant::now();
+
+    loop {
+        match timeout_at(next_check, inbox.recv()).await {
+            Ok(Some(WorkerEvent::Stop) | None) => {
+                debug!("worker stopping");
+                return;
+            }
+            Ok(Some(WorkerEvent::Reminder(ReminderEvent::Fired { .. } | ReminderEvent::Missed { .. }))) => continue,
+            Ok(Some(WorkerEvent::Reminder(ReminderEvent::Changed))) => {
+                debug!("woke up: reminders changed");
+                held.upcoming = None;
+            }
+            Ok(Some(WorkerEvent::Reminder(ReminderEvent::SnoozeAll))) => {
+                let (minutes, db, events) = (config.snooze_all_minutes(), Arc::clone(&db), events.clone());
+                let snoozed = task::spawn_blocking(move || {
+                    let snoozed = snooze_due(&*db, Local::now(), minutes);
+                    if matches!(&snoozed, Ok(titles) if !titles.is_empty()) {
+                        publish(&events, ReminderEvent::Changed);
+                    }
+                    snoozed
+                })
+                .await;
+                match snoozed {
+                    Ok(Ok(titles)) => info!(count = titles.len(), minutes, "snoozed everything due"),
+                    Ok(Err(e)) => error!("failed to snooze the due reminders: {}", e),
+                    Err(e) => error!("snoozing the due reminders failed: {}", e),
+                }
+                continue;
+            }
+            Ok(Some(WorkerEvent::Reminder(ReminderEvent::Muted { muted }))) => {
+                info!(muted, "notifications toggled");
+                held.muted = muted;
+                if !muted && !held.while_muted.is_empty() {
+                    let reminders = std::mem::take(&mut held.while_muted);
+                    publish(&events, ReminderEvent::Missed { reminders, while_muted: true });
+                }
+                continue;
+            }
+            Err(_) => debug!("woke up: regular check"),
+            Ok(Some(WorkerEvent::Reminder(ReminderEvent::ProfileSwitched { profile }))) => {
+                debug!(profile = profile.as_deref().unwrap_or("default"), "woke up: profile switched");
+                held.upcoming = None;
+                match Config::load(profile.as_deref()) {
+                    Ok(profile_config) => config = profile_config,
+                    Err(e) => error!("failed to load the profile's config: {}", e),
+                }
+            }
+        }
+
+        let (config, db, events) = (config.clone(), Arc::clone(&db), events.clone());
+        let muted = held.muted;
+        let mut pending = std::mem::take(&mut held);
+        let (checked, wake) = match task::spawn_blocking(move || {
+            check(&config, &*db, &mut pending, &events);
+            let wake = next_wake(&config, &*db, &mut pending, Local::now().naive_local());
+            (pending, wake)
+        })
+        .await
+        {
+            Ok(checked) => checked,
+            Err(e) => {
+                error!("checking reminders failed: {}", e);
+                (Held { muted, ..Default::default() }, None)
+            }
+        };
+        held = checked;
+        let sleep = wake
+            .and_then(|wake| wake.and_local_timezone(Local).earliest())
+            .and_then(|wake| (wake - Local::now()).to_std().ok())
+            .map_or(LONGEST_SLEEP, |sleep| sleep.min(LONGEST_SLEEP))
+            .min(if held.retry { RETRY_AFTER } else { LONGEST_SLEEP });
+        debug!(?wake, ?sleep, "sleeping until the next check");
+        next_check = Instant::now() + sleep;
+    }
+}
+
+/// The next local time after `now` that the worker has something to do at:
+/// an open reminder's alert, the end of a snooze, or the end of quiet hours
+/// when a summary is waiting for it. Alerts are on the second for reminders
+/// set to one and at the start of their minute otherwise. The open reminders
+/// are only read from the store when `held` has none yet.
+fn next_wake(config: &Config, db: &dyn ReminderStore, held: &mut Held, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let this_minute = schedule::minute_of(now);
+    let from = this_minute + Duration::minutes(1);
+    let reminders = held.upcoming.get_or_insert_with(|| match db.get_all_reminders() {
+        Ok(reminders) => reminders.into_iter().filter(|r| r.completed_at.is_none()).collect(),
+        Err(e) => {
+            error!("failed to look up the next alert: {}", e);
+            Vec::new()
+        }
+    });
+    let alerts: Vec<NaiveDateTime> = reminders
+        .iter()
+        .filter_map(|r| {
+            schedule::next_alert(r, this_minute)
+                .filter(|at| *at > now)
+                .or_else(|| schedule::next_alert(r, from))
+        })
+        .collect();
+    let snooze = match db.next_snooze() {
+        Ok(until) => until.and_then(|until| NaiveDateTime::parse_from_str(&until, "%Y-%m-%d %H:%M").ok()),
+        Err(e) => {
+            error!("failed to look up the next snooze: {}", e);
+            None
+        }
+    };
+    let quiet_end = match &config.quiet_hours {
+        Some(quiet) if !held.quiet_hours.is_empty() => quiet.next_end(now),
+        _ => None,
+    };
+    alerts.into_iter().chain(snooze.map(|at| at.max(from))).chain(quiet_end).min()
+}
+
+/// One round of the worker: the quiet hours summary if they just ended, what
+/// was missed since the last round, due alerts, and the daily backup.
+fn check(config: &Config, db: &dyn ReminderStore, held: &mut Held, events: &EventBus) {
+    let now = Local::now();
+    held.retry = false;
+    held.quiet = config
+        .quiet_hours
+        .as_ref()
+        .is_some_and(|q| q.contains(now.time()));
+
+    if !held.quiet && !held.muted && !held.quiet_hours.is_empty() {
+        let summary = format!("{} reminder(s) during quiet hours", held.quiet_hours.len());
+        match show_notification(&summary, &held.quiet_hours.join("\n")) {
+            Ok(_) => {
+                record(db, None, &summary, Delivery::Delivered, None);
+                held.quiet_hours.clear();
+            }
+            Err(e) => {
+                warn!(summary, "failed to send notification: {}", e);
+                record(db, None, &summary, Delivery::Failed, Some(e.to_string()));
+            }
+        }
+    }
+
+    let days_off = DaysOff::load(config, db);
+    catch_up(db, now, &days_off, held, events);
+    check_reminders(config, db, now, &days_off, held, events);
+
+    if let Err(e) = backup::daily(config, now.date_naive()) {
+        error!("daily backup failed: {}", e);
+    }
+}
+
+/// Sums up what should have gone off between the last check and the minute
+/// of `now`, which [`check_reminders`] is about to handle, then records this
+/// check. Anything is only missed if no worker checked in the meantime: the
+/// app wasn't running or the machine was asleep.
+fn catch_up(db: &dyn ReminderStore, now: DateTime<Local>, days_off: &DaysOff, held: &mut Held, events: &EventBus) {
+    let minute = schedule::minute_of(now.naive_local());
+    let last = match db.last_check() {
+        Ok(last) => last.and_then(|last| NaiveDateTime::parse_from_str(&last, "%Y-%m-%d %H:%M").ok()),
+        Err(e) => {
+            error!("failed to look up the last check: {}", e);
+            return;
+        }
+    };
+
+    if let Some(last) = last.filter(|last| minute - *last > Duration::minutes(1)) {
+        info!(since = %last.format("%Y-%m-%d %H:%M"), "catching up on missed reminders");
+        let reminders = db.get_all_reminders().unwrap_or_else(|e| {
+            error!("failed to look up missed reminders: {}", e);
+            Vec::new()
+        });
+        let missed = missed(&reminders, days_off, last, minute);
+        if !missed.is_empty() {
+            let summary = format!("{} missed reminder(s)", missed.len());
+            let body: Vec<String> = missed
+                .iter()
+                .map(|m| match m.times {
+                    1 => format!("{} {}", m.at, m.title),
+                    n => format!("{} {} ({}×)", m.at, m.title, n),
+                })
+                .collect();
+            deliver(db, held, events, &now.format("%H:%M").to_string(), None, &summary, &body.join("\n"));
+            publish(events, ReminderEvent::Missed { reminders: missed, while_muted: false });
+        }
+    }
+
+    if let Err(e) = db.record_check(&minute.format("%Y-%m-%d %H:%M").to_string()) {
+        error!("failed to record the check: {}", e);
+    }
+}
+
+/// The open reminders that should have gone off after the minute `last` and
+/// before `now`, other than on `days_off`. Interval reminders are left out:
+/// they fire straight away once a worker is back.
+fn missed(reminders: &[Reminder], days_off: &DaysOff, last: NaiveDateTime, now: NaiveDateTime) -> Vec<Missed> {
+    let mut missed = Vec::new();
+    for reminder in reminders {
+        if reminder.completed_at.is_some() || reminder.interval_minutes.is_some() {
+            continue;
+        }
+        let fires: Vec<NaiveDateTime> =
+            std::iter::successors(schedule::next_fire(reminder, last + Duration::minutes(1)), |at| {
+                schedule::next_fire(reminder, *at + Duration::minutes(1))
+            })
+            .take_while(|at| *at < now)
+            .filter(|at| !days_off.covers(reminder, at.date()))
+            .take(MISSED_LIMIT)
+            .collect();
+        if let Some(at) = fires.last() {
+            missed.push(Missed {
+                id: reminder.id,
+                title: reminder.title.clone(),
+                at: at.format("%Y-%m-%d %H:%M").to_string(),
+                times: fires.len(),
+            });
+        }
+    }
+    missed.sort_by(|a, b| a.at.cmp(&b.at));
+    missed
+}
+
+/// What a notification for the occurrence at `at` says: the description,
+/// below the note an exception added for that day.
+fn body_at(reminder: &Reminder, at: NaiveDateTime) -> String {
+    match schedule::note_at(reminder, at) {
+        Some(note) if reminder.description.is_empty() => note.to_string(),
+        Some(note) => format!("{}\n\n{}", note, reminder.description),
+        None => reminder.description.clone(),
+    }
+}
+
+/// Delivers whatever is due in the minute of `now`, unless it is a day off,
+/// and tidies up the archive, trash and snoozes.
+fn check_reminders(
+    config: &Config,
+    db: &dyn ReminderStore,
+    now: DateTime<Local>,
+    days_off: &DaysOff,
+    held: &mut Held,
+    events: &EventBus,
+) {
+    let current_time = now.format("%H:%M").to_string();
+    let now_minute = now.format("%Y-%m-%d %H:%M").to_string();
+    let mut reminders = db.get_due_reminders(now.naive_local()).unwrap_or_else(|e| {
+        error!("failed to look up due reminders: {}", e);
+        Vec::new()
+    });
+    reminders.retain(|reminder| {
+        let off = days_off.covers(reminder, now.date_naive());
+        if off {
+            debug!(id = reminder.id, "day off");
+        }
+        !off
+    });
+    let this_minute = schedule::minute_of(now.naive_local());
+
+    for reminder in reminders {
+        // Due this minute, but set for a second of it still to come.
+        if schedule::next_alert(&reminder, this_minute).is_some_and(|at| at > now.naive_local()) {
+            continue;
+        }
+        if reminder.interval_minutes.is_some() {
+            // Interval reminders remember their last fire instead of
+            // claiming each (offset, day) once.
+            let previous = reminder.last_fired_at.as_deref();
+            if !schedule::fires_at(&reminder, now.naive_local())
+                || !matches!(db.claim_interval_fire(reminder.id, previous, Some(&now_minute)), Ok(true))
+            {
+                continue;
+            }
+            let body = body_at(&reminder, now.naive_local());
+            if !deliver(db, held, events, &current_time, Some(&reminder), &reminder.title, &body) {
+                let _ = db.claim_interval_fire(reminder.id, Some(&now_minute), previous);
+                continue;
+            }
+            // Its next fire follows from this one.
+            held.upcoming = None;
+            publish(
+                events,
+                ReminderEvent::Fired {
+                    id: reminder.id,
+                    title: reminder.title.clone(),
+                    offset_minutes: 0,
+                },
+            );
+            delete_after_firing(config, db, &reminder, events);
+            continue;
+        }
+
+        for offset in schedule::due_alerts(&reminder, now.naive_local()) {
+            // Claimed by the minute it's for, not the day, so a cron
+            // reminder going off several times a day alerts each time.
+            let occurrence = (this_minute + Duration::minutes(offset)).format("%Y-%m-%d %H:%M").to_string();
+            if !matches!(db.claim_notification(reminder.id, offset, &occurrence), Ok(true)) {
+                continue;
+            }
+
+            let summary = if offset == 0 {
+                reminder.title.clone()
+            } else {
+                format!("{} (in {} min)", reminder.title, offset)
+            };
+
+            let body = body_at(&reminder, now.naive_local() + Duration::minutes(offset));
+            if !deliver(db, held, events, &current_time, Some(&reminder), &summary, &body) {
+                let _ = db.release_notification(reminder.id, offset, &occurrence);
+                continue;
+            }
+
+            publish(
+                events,
+                ReminderEvent::Fired {
+                    id: reminder.id,
+                    title: reminder.title.clone(),
+                    offset_minutes: offset,
+                },
+            );
+            if offset == 0 {
+                delete_after_firing(config, db, &reminder, events);
+            }
+        }
+    }
+
+    match db.archive_past(now.naive_local()) {
+        Ok(0) => {}
+        Ok(_) => publish(events, ReminderEvent::Changed),
+        Err(e) => error!("failed to archive past reminders: {}", e),
+    }
+    if let Err(e) = db.purge_trash(&trash_cutoff(now)) {
+        error!("failed to empty the trash: {}", e);
+    }
+
+    let snoozed = db.claim_due_snoozes(&now_minute).unwrap_or_else(|e| {
+        error!("failed to look up due snoozes: {}", e);
+        Vec::new()
+    });
+    for id in snoozed {
+        if let Ok(Some(reminder)) = db.get_reminder(id) {
+            publish(
+                events,
+                ReminderEvent::Fired {
+                    id: reminder.id,
+                    title: reminder.title.clone(),
+                    offset_minutes: 0,
+                },
+            );
+            let summary = format!("{} (snoozed)", reminder.title);
+            deliver(db, held, events, &current_time, Some(&reminder), &summary, &reminder.description);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use reminder::{MemoryStore, NewReminder};
+
+    #[test]
+    fn counts_what_went_off_while_away() {
+        let store = MemoryStore::new();
+        for (title, time, date) in [("Meds", "07:00", None), ("Dentist", "10:00", Some("2026-10-14")), ("Tea", "16:00", None)] {
+            store
+                .add_reminder(NewReminder {
+                    title: title.to_string(),
+                    time: time.to_string(),
+                    date: date.map(str::to_string),
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+        let at = |day, hour, minute| NaiveDate::from_ymd_opt(2026, 10, day).unwrap().and_hms_opt(hour, minute, 0).unwrap();
+
+        let away = missed(&store.get_all_reminders().unwrap(), &DaysOff::default(), at(13, 12, 0), at(15, 8, 0));
+        let found: Vec<(&str, &str, usize)> = away.iter().map(|m| (m.title.as_str(), m.at.as_str(), m.times)).collect();
+        assert_eq!(
+            found,
+            [("Dentist", "2026-10-14 10:00", 1), ("Tea", "2026-10-14 16:00", 2), ("Meds", "2026-10-15 07:00", 2)]
+        );
+        assert!(missed(&store.get_all_reminders().unwrap(), &DaysOff::default(), at(15, 7, 0), at(15, 8, 0)).is_empty());
+
+        let off = Vacation { id: 1, start: "2026-10-14".to_string(), end: "2026-10-14".to_string(), tags: Vec::new() };
+        let days_off = DaysOff { vacations: vec![off], ..Default::default() };
+        let away = missed(&store.get_all_reminders().unwrap(), &days_off, at(13, 12, 0), at(15, 8, 0));
+        let found: Vec<(&str, usize)> = away.iter().map(|m| (m.title.as_str(), m.times)).collect();
+        assert_eq!(found, [("Tea", 1), ("Meds", 1)]);
+    }
+
+    #[test]
+    fn alerts_for_each_time_a_cron_reminder_goes_off() {
+        let db = reminder::Database::new(":memory:").unwrap();
+        db.add_reminder(NewReminder {
+            title: "Water".to_string(),
+            alerts: vec![0],
+            cron: Some("0 9,13 * * *".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        let events = crate::events::new_bus();
+        let mut held = Held { muted: true, ..Default::default() };
+        let at = |hour| {
+            let at = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap().and_hms_opt(hour, 0, 0).unwrap();
+            at.and_local_timezone(Local).earliest().unwrap()
+        };
+
+        for hour in [9, 9, 13, 13] {
+            check_reminders(&Config::default(), &db, at(hour), &DaysOff::default(), &mut held, &events);
+        }
+        let fired = db.notification_log(10).unwrap().iter().filter(|n| n.summary == "Water").count();
+        assert_eq!(fired, 2);
+    }
+
+    #[test]
+    fn wakes_up_for_whatever_is_due_first() {
+        let store = MemoryStore::new();
+        let meds = store
+            .add_reminder(NewReminder {
+                title: "Meds".to_string(),
+                time: "09:00".to_string(),
+                alerts: vec![10, 0],
+                ..Default::default()
+            })
+            .unwrap();
+        let at = |hour, minute, second| NaiveDate::from_ymd_opt(2026, 10, 15).unwrap().and_hms_opt(hour, minute, second).unwrap();
+        let (config, mut held) = (Config::default(), Held::default());
+
+        assert_eq!(next_wake(&config, &store, &mut held, at(8, 40, 30)), Some(at(8, 50, 0)));
+        assert_eq!(next_wake(&config, &store, &mut held, at(8, 50, 0)), Some(at(9, 0, 0)));
+        store.snooze_reminder(meds.id, "2026-10-15 08:55").unwrap();
+        assert_eq!(next_wake(&config, &store, &mut held, at(8, 50, 0)), Some(at(8, 55, 0)));
+        store.unsnooze_reminder(meds.id).unwrap();
+
+        // The reminders are read once, until they change.
+        store.set_completed(meds.id, Some("2026-10-15 08:50")).unwrap();
+        assert_eq!(next_wake(&config, &store, &mut held, at(8, 50, 0)), Some(at(9, 0, 0)));
+        held.upcoming = None;
+        assert_eq!(next_wake(&config, &store, &mut held, at(8, 50, 0)), None);
+    }
+
+    #[test]
+    fn keeps_what_goes_off_while_muted() {
+        let store = MemoryStore::new();
+        let events = crate::events::new_bus();
+        let mut held = Held { muted: true, ..Default::default() };
+        let meds = store
+            .add_reminder(NewReminder { title: "Meds".to_string(), time: "09:00".to_string(), ..Default::default() })
+            .unwrap();
+        assert!(deliver(&store, &mut held, &events, "09:00", Some(&meds), "Meds", ""));
+        assert!(deliver(&store, &mut held, &events, "09:00", None, "2 missed reminder(s)", ""));
+        assert!(deliver(&store, &mut held, &events, "09:05", Some(&meds), "Meds", ""));
+
+        let kept: Vec<(i32, &str, usize)> = held.while_muted.iter().map(|m| (m.id, m.title.as_str(), m.times)).collect();
+        assert_eq!(kept, [(1, "Meds", 2)]);
+        let log = store.notification_log(10).unwrap();
+        assert_eq!(log.len(), 3);
+        assert!(log.iter().all(|n| n.delivery == Delivery::Muted));
+    }
+
+    #[test]
+    fn snoozes_everything_that_went_off_lately() {
+        let store = MemoryStore::new();
+        let now = Local::now();
+        for (title, minutes_ago, delivery) in
+            [("Tea", 40, Delivery::Delivered), ("Meds", 5, Delivery::Delivered), ("Stretch", 2, Delivery::Delivered), ("Walk", 1, Delivery::Muted)]
+        {
+            let reminder = store
+                .add_reminder(NewReminder { title: title.to_string(), time: "07:00".to_string(), ..Default::default() })
+                .unwrap();
+            let at = (now - Duration::minutes(minutes_ago)).format("%Y-%m-%d %H:%M:%S").to_string();
+            let notification =
+                LoggedNotification { reminder_id: Some(reminder.id), at, summary: title.to_string(), delivery, error: None };
+            store.log_notification(&notification).unwrap();
+        }
+
+        assert_eq!(snooze_due(&store, now, 10).unwrap(), ["Stretch", "Meds"]);
+        let until = (now + Duration::minutes(10)).format("%Y-%m-%d %H:%M").to_string();
+        assert_eq!(store.claim_due_snoozes(&until).unwrap().len(), 2);
+    }
+}